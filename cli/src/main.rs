@@ -0,0 +1,399 @@
+//! Command line tools for debugging the eth-client.
+//!
+//! `inspect` prints the client cell data, `diff` compares the input and the output
+//! client cells of a transaction. `relay-one` builds the unsigned transaction of a single
+//! header submission. `explain` names the contract error of a rejected
+//! transaction. `validate` checks a json fixture before it is relayed. The cell data is passed as hex.
+
+use eth_client_errors::{lookup, validation_failure, Diagnostic};
+use eth_client_sdk::cell_data::{parse_cell_data, ChainDigest};
+use eth_client_sdk::fixture::{BlockWithProofsJson, RootsCollectionJson};
+use eth_client_sdk::relay::{append_header, chain_event, BlockWithProofs};
+use eth_client_sdk::relay_tx::{relay_tx_skeleton, set_chain_event, set_relay_witness, top_up_client_capacity, ClientCell};
+use eth_client_sdk::types::{
+    basic::{BytesVec, HeaderInfo, HeaderInfoVec},
+    cell_data::{CellData, ClientConfig, RelayerSet},
+};
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{DepType, TransactionView},
+    packed,
+    prelude::{Pack, Unpack},
+};
+use molecule::prelude::*;
+use serde_json::{json, Value};
+use std::{env, fs, process};
+
+const USAGE: &str = "usage:
+    eth-client-cli inspect <cell data hex>
+    eth-client-cli diff <input cell data hex> <output cell data hex>
+    eth-client-cli relay-one <live client cell json> <client out point> <contract out point> <dags merkle roots out point> <block with proofs json>
+    eth-client-cli explain <rejection message or error code> [<input cell data hex>]
+    eth-client-cli validate <block with proofs or dags merkle roots json>";
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let result = match args.get(1).map(String::as_str) {
+        Some("inspect") if args.len() == 3 => inspect(&args[2]),
+        Some("diff") if args.len() == 4 => diff(&args[2], &args[3]),
+        Some("relay-one") if args.len() == 7 => relay_one(&args[2], &args[3], &args[4], &args[5], &args[6]),
+        Some("explain") if args.len() == 3 => explain(&args[2], None),
+        Some("explain") if args.len() == 4 => explain(&args[2], Some(&args[3])),
+        Some("validate") if args.len() == 3 => validate(&args[2]),
+        _ => Err(USAGE.to_string()),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+#[derive(PartialEq)]
+struct HeaderEntry {
+    number: u64,
+    hash: String,
+    total_difficulty: u64,
+    ckb_timestamp: u64,
+}
+
+struct Client {
+    digest: ChainDigest,
+    cell_data: CellData,
+    main: Vec<HeaderEntry>,
+    uncle: Vec<HeaderEntry>,
+    pending: Vec<HeaderEntry>,
+}
+
+fn decode_hex(data_hex: &str) -> Result<Vec<u8>, String> {
+    hex::decode(data_hex.trim_start_matches("0x")).map_err(|err| format!("invalid hex: {}", err))
+}
+
+fn load(data_hex: &str) -> Result<Client, String> {
+    let data = decode_hex(data_hex)?;
+    let (digest, cell_data) = parse_cell_data(&data).map_err(|err| err.to_string())?;
+    let headers = cell_data.headers();
+    Ok(Client {
+        main: bytes_vec_entries(&headers.main())?,
+        uncle: header_info_vec_entries(&headers.uncle())?,
+        pending: bytes_vec_entries(&headers.pending())?,
+        digest,
+        cell_data,
+    })
+}
+
+fn bytes_vec_entries(header_infos: &BytesVec) -> Result<Vec<HeaderEntry>, String> {
+    header_infos
+        .clone()
+        .into_iter()
+        .map(|header_info| {
+            let header_info = HeaderInfo::from_slice(&header_info.raw_data())
+                .map_err(|err| format!("invalid header info: {}", err))?;
+            header_entry(&header_info)
+        })
+        .collect()
+}
+
+fn header_info_vec_entries(header_infos: &HeaderInfoVec) -> Result<Vec<HeaderEntry>, String> {
+    header_infos.clone().into_iter().map(|header_info| header_entry(&header_info)).collect()
+}
+
+fn header_entry(header_info: &HeaderInfo) -> Result<HeaderEntry, String> {
+    // the block number is the 9th field of the rlp encoded header.
+    let number = rlp::Rlp::new(&header_info.header().raw_data())
+        .val_at(8)
+        .map_err(|err| format!("invalid header: {}", err))?;
+    Ok(HeaderEntry {
+        number,
+        hash: format!("0x{}", hex::encode(header_info.hash().as_slice())),
+        total_difficulty: to_u64(header_info.total_difficulty().as_slice()),
+        ckb_timestamp: to_u64(header_info.ckb_timestamp().as_slice()),
+    })
+}
+
+fn to_u64(slice: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(slice);
+    u64::from_le_bytes(buf)
+}
+
+fn to_u32(slice: &[u8]) -> u32 {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(slice);
+    u32::from_le_bytes(buf)
+}
+
+fn format_digest(digest: &ChainDigest) -> String {
+    format!(
+        "number {}, hash 0x{}, total difficulty {}, mmr root 0x{}, finalized number {}",
+        digest.number,
+        hex::encode(digest.hash),
+        digest.total_difficulty,
+        hex::encode(digest.mmr_root),
+        digest.finalized_number
+    )
+}
+
+fn format_relayers(relayers: &RelayerSet) -> String {
+    if relayers.lock_hashes().is_empty() {
+        return "open relay".to_string();
+    }
+    let lock_hashes: Vec<String> = relayers
+        .lock_hashes()
+        .into_iter()
+        .map(|lock_hash| format!("0x{}", hex::encode(lock_hash.as_slice())))
+        .collect();
+    match to_u64(relayers.slot_length().as_slice()) {
+        0 => format!("[{}], any of them", lock_hashes.join(", ")),
+        slot_length => format!("[{}], slot length {}", lock_hashes.join(", "), slot_length),
+    }
+}
+
+fn format_config(config: &ClientConfig) -> String {
+    format!(
+        "main cache limit {}, uncle cache limit {}, max timestamp gap {}s, max future drift {}s, uncle retention depth {}, max reorg depth {}, finality weight {}",
+        to_u32(config.main_cache_limit().as_slice()),
+        to_u32(config.uncle_cache_limit().as_slice()),
+        to_u64(config.max_timestamp_gap().as_slice()),
+        to_u64(config.max_future_drift().as_slice()),
+        to_u64(config.uncle_retention_depth().as_slice()),
+        to_u64(config.max_reorg_depth().as_slice()),
+        to_u64(config.finality_weight().as_slice()),
+    )
+}
+
+fn format_entry(entry: &HeaderEntry) -> String {
+    format!("#{} {} td {} ckb timestamp {}", entry.number, entry.hash, entry.total_difficulty, entry.ckb_timestamp)
+}
+
+/// the printable fields of the client, except the chains.
+fn fields(client: &Client) -> Vec<(&'static str, String)> {
+    let cell_data = &client.cell_data;
+    vec![
+        ("digest", format_digest(&client.digest)),
+        ("paused", (cell_data.paused().as_slice()[0] == 1).to_string()),
+        ("user lockscript", format!("0x{}", hex::encode(cell_data.user_lockscript().as_slice()))),
+        ("governance lock hash", format!("0x{}", hex::encode(cell_data.governance_lock_hash().as_slice()))),
+        ("relayers", format_relayers(&cell_data.relayers())),
+        ("config", format_config(&cell_data.config())),
+        ("update sequence", to_u64(cell_data.update_sequence().as_slice()).to_string()),
+    ]
+}
+
+fn chains(client: &Client) -> Vec<(&'static str, &Vec<HeaderEntry>)> {
+    vec![("main", &client.main), ("uncle", &client.uncle), ("pending", &client.pending)]
+}
+
+fn inspect(data_hex: &str) -> Result<(), String> {
+    let client = load(data_hex)?;
+    for (name, value) in fields(&client) {
+        println!("{}: {}", name, value);
+    }
+    for (name, entries) in chains(&client) {
+        println!("{} ({}):", name, entries.len());
+        for entry in entries {
+            println!("    {}", format_entry(entry));
+        }
+    }
+    Ok(())
+}
+
+/// print the changed fields, and the headers removed from (-) or added to (+) each chain.
+fn diff(input_hex: &str, output_hex: &str) -> Result<(), String> {
+    let input = load(input_hex)?;
+    let output = load(output_hex)?;
+    for ((name, input_value), (_, output_value)) in fields(&input).into_iter().zip(fields(&output)) {
+        if input_value != output_value {
+            println!("{}:\n    - {}\n    + {}", name, input_value, output_value);
+        }
+    }
+    for ((name, input_entries), (_, output_entries)) in chains(&input).into_iter().zip(chains(&output)) {
+        let removed: Vec<&HeaderEntry> = input_entries.iter().filter(|entry| !output_entries.contains(entry)).collect();
+        let added: Vec<&HeaderEntry> = output_entries.iter().filter(|entry| !input_entries.contains(entry)).collect();
+        if removed.is_empty() && added.is_empty() {
+            continue;
+        }
+        println!("{} ({} -> {}):", name, input_entries.len(), output_entries.len());
+        for entry in removed {
+            println!("    - {}", format_entry(entry));
+        }
+        for entry in added {
+            println!("    + {}", format_entry(entry));
+        }
+    }
+    Ok(())
+}
+
+fn read_block(path: &str) -> Result<BlockWithProofs, String> {
+    let file = fs::File::open(path).map_err(|err| format!("cannot open {}: {}", path, err))?;
+    let block: BlockWithProofsJson =
+        serde_json::from_reader(file).map_err(|err| format!("invalid block with proofs: {}", err))?;
+    block.validate().map_err(|err| format!("invalid block with proofs: {}", err))
+}
+
+/// print the unsigned transaction extending the main chain of the live client cell by one header, in
+/// the json format of the ckb rpc. the out points are `<tx hash>:<index>`. fetching the header, running
+/// ethashproof, adding the fee inputs and the lock deps and signing the transaction are left to the relayer.
+fn relay_one(cell_path: &str, client_out_point: &str, contract_out_point: &str, dags_merkle_roots_out_point: &str, block_path: &str) -> Result<(), String> {
+    let client = read_live_cell(cell_path, parse_out_point(client_out_point)?)?;
+    let contract = parse_out_point(contract_out_point)?;
+    let dags_merkle_roots = parse_out_point(dags_merkle_roots_out_point)?;
+    let (digest, _) = parse_cell_data(&client.data).map_err(|err| err.to_string())?;
+    let block = read_block(block_path)?;
+    let merkle_proofs = block.merkle_proofs().map_err(|err| err.to_string())?;
+    let output = append_header(&client.data, &block.header_rlp).map_err(|err| err.to_string())?;
+    let event = chain_event(&client.data, &output).map_err(|err| err.to_string())?;
+    let tx = relay_tx_skeleton(&client, &contract, &dags_merkle_roots, &output);
+    let tx = set_relay_witness(&tx, &client.out_point, &dags_merkle_roots, &block.header_rlp, &merkle_proofs, digest.hash)
+        .map_err(|err| err.to_string())?;
+    let tx = set_chain_event(&tx, 0, &event).map_err(|err| err.to_string())?;
+    let tx = top_up_client_capacity(&tx, 0).map_err(|err| err.to_string())?;
+    println!("{}", serde_json::to_string_pretty(&tx_json(&tx)).map_err(|err| err.to_string())?);
+    Ok(())
+}
+
+fn parse_out_point(out_point: &str) -> Result<packed::OutPoint, String> {
+    let invalid = || format!("invalid out point {}, expected <tx hash>:<index>", out_point);
+    let mut parts = out_point.splitn(2, ':');
+    let tx_hash = decode_hex(parts.next().unwrap_or(""))?;
+    let tx_hash = packed::Byte32::from_slice(&tx_hash).map_err(|_| invalid())?;
+    let index: u32 = parts.next().ok_or_else(invalid)?.parse().map_err(|_| invalid())?;
+    Ok(packed::OutPoint::new(tx_hash, index))
+}
+
+/// the client cell from the result of the `get_live_cell` rpc, queried with the data.
+fn read_live_cell(path: &str, out_point: packed::OutPoint) -> Result<ClientCell, String> {
+    let file = fs::File::open(path).map_err(|err| format!("cannot open {}: {}", path, err))?;
+    let live_cell: Value = serde_json::from_reader(file).map_err(|err| format!("invalid live cell: {}", err))?;
+    if live_cell["status"] != "live" {
+        return Err(format!("the client cell is not live: {}", live_cell["status"]));
+    }
+    let output = &live_cell["cell"]["output"];
+    let capacity = output["capacity"].as_str().ok_or_else(|| "the live cell has no capacity".to_string())?;
+    let capacity = u64::from_str_radix(capacity.trim_start_matches("0x"), 16).map_err(|err| format!("invalid capacity: {}", err))?;
+    let type_ = match &output["type"] {
+        Value::Null => None,
+        script => Some(parse_script(script)?),
+    };
+    let data = live_cell["cell"]["data"]["content"]
+        .as_str()
+        .ok_or_else(|| "the live cell has no data, query it with the data".to_string())?;
+    Ok(ClientCell {
+        out_point,
+        output: packed::CellOutput::new_builder()
+            .capacity(capacity.pack())
+            .lock(parse_script(&output["lock"])?)
+            .type_(type_.pack())
+            .build(),
+        data: Bytes::from(decode_hex(data)?),
+    })
+}
+
+fn parse_script(script: &Value) -> Result<packed::Script, String> {
+    let field = |name: &str| script[name].as_str().ok_or_else(|| format!("the script has no {}", name));
+    let hash_type = match field("hash_type")? {
+        "data" => 0u8,
+        "type" => 1,
+        "data1" => 2,
+        hash_type => return Err(format!("unknown hash type {}", hash_type)),
+    };
+    let code_hash = packed::Byte32::from_slice(&decode_hex(field("code_hash")?)?)
+        .map_err(|_| "the code hash is not 32 bytes".to_string())?;
+    Ok(packed::Script::new_builder()
+        .code_hash(code_hash)
+        .hash_type(packed::Byte::new(hash_type))
+        .args(Bytes::from(decode_hex(field("args")?)?).pack())
+        .build())
+}
+
+/// the transaction in the json format of the ckb rpc, numbers are hex.
+fn tx_json(tx: &TransactionView) -> Value {
+    let hex = |data: &[u8]| format!("0x{}", hex::encode(data));
+    let number = |number: u64| format!("{:#x}", number);
+    let out_point = |out_point: packed::OutPoint| {
+        let index: u32 = out_point.index().unpack();
+        json!({ "tx_hash": hex(out_point.tx_hash().as_slice()), "index": number(index.into()) })
+    };
+    let script = |script: packed::Script| {
+        let hash_type = match script.hash_type().as_slice()[0] {
+            0 => "data",
+            1 => "type",
+            _ => "data1",
+        };
+        json!({ "code_hash": hex(script.code_hash().as_slice()), "hash_type": hash_type, "args": hex(&script.args().raw_data()) })
+    };
+    let cell_deps: Vec<Value> = tx
+        .cell_deps()
+        .into_iter()
+        .map(|cell_dep| {
+            let dep_type = if cell_dep.dep_type() == DepType::Code.into() { "code" } else { "dep_group" };
+            json!({ "out_point": out_point(cell_dep.out_point()), "dep_type": dep_type })
+        })
+        .collect();
+    let inputs: Vec<Value> = tx
+        .inputs()
+        .into_iter()
+        .map(|input| {
+            let since: u64 = input.since().unpack();
+            json!({ "since": number(since), "previous_output": out_point(input.previous_output()) })
+        })
+        .collect();
+    let outputs: Vec<Value> = tx
+        .outputs()
+        .into_iter()
+        .map(|output| {
+            let capacity: u64 = output.capacity().unpack();
+            json!({ "capacity": number(capacity), "lock": script(output.lock()), "type": output.type_().to_opt().map(script) })
+        })
+        .collect();
+    json!({
+        "version": number(tx.version().into()),
+        "cell_deps": cell_deps,
+        "header_deps": tx.header_deps().into_iter().map(|hash| hex(hash.as_slice())).collect::<Vec<_>>(),
+        "inputs": inputs,
+        "outputs": outputs,
+        "outputs_data": tx.outputs_data().into_iter().map(|data| hex(&data.raw_data())).collect::<Vec<_>>(),
+        "witnesses": tx.witnesses().into_iter().map(|witness| hex(&witness.raw_data())).collect::<Vec<_>>(),
+    })
+}
+
+/// check a block with proofs or a dags merkle roots json, telling the format from the fields.
+fn validate(path: &str) -> Result<(), String> {
+    let json = fs::read_to_string(path).map_err(|err| format!("cannot open {}: {}", path, err))?;
+    let block_err = match serde_json::from_str::<BlockWithProofsJson>(&json) {
+        Ok(block) => {
+            let block = block.validate().map_err(|err| format!("invalid block with proofs: {}", err))?;
+            let number: u64 = rlp::Rlp::new(&block.header_rlp)
+                .val_at(8)
+                .map_err(|err| format!("invalid block with proofs: {}", err))?;
+            println!("block with proofs of header {}, proof length {}", number, block.proof_length);
+            return Ok(());
+        }
+        Err(err) => err,
+    };
+    let roots_err = match serde_json::from_str::<RootsCollectionJson>(&json) {
+        Ok(roots) => {
+            let roots = roots.validate().map_err(|err| format!("invalid dags merkle roots: {}", err))?;
+            println!("dags merkle roots of {} epochs", roots.len());
+            return Ok(());
+        }
+        Err(err) => err,
+    };
+    Err(format!("neither a block with proofs ({}) nor dags merkle roots ({})", block_err, roots_err))
+}
+
+/// print the contract error of a rejected relay transaction, with the main chain tail of the
+/// input client cell if given.
+fn explain(message: &str, input_hex: Option<&str>) -> Result<(), String> {
+    let code = message
+        .trim()
+        .parse()
+        .ok()
+        .or_else(|| validation_failure(message))
+        .ok_or_else(|| format!("not a script validation failure: {}", message))?;
+    let tail_number = match input_hex {
+        Some(input_hex) => Some(load(input_hex)?.digest.number),
+        None => None,
+    };
+    println!("{}", Diagnostic { code, error: lookup(code), tail_number });
+    Ok(())
+}
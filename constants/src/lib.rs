@@ -0,0 +1,40 @@
+//! The constants of the eth-client contract, shared with the sdk and the tests.
+//!
+//! The contract depends on this crate, so the off-chain code can't drift from the deployed binary.
+//! The error codes are in the `eth-client-errors` crate.
+
+#![no_std]
+
+/// The length of the digest at the head of the client cell data.
+pub const DIGEST_LEN: usize = 88;
+
+/// The length of the unique id at the head of the type script args.
+pub const UNIQUE_ID_LEN: usize = 32;
+/// The type script args may carry a u64 LE after the unique id, the min number of ckb blocks
+/// between two header updates.
+pub const MIN_UPDATE_INTERVAL_LEN: usize = 8;
+/// The type script args may carry the type hash of the dags merkle roots cells after the min update
+/// interval, only the roots cells of that type are trusted then.
+pub const ROOTS_TYPE_HASH_LEN: usize = 32;
+/// The type script args may carry a u64 LE after the roots type hash, the headers of a batch buried
+/// by at least this many later headers of the batch may omit their ethash proofs. 0 disables it.
+/// The difficulty of a header without proofs doesn't count toward the total difficulty.
+pub const POW_SKIP_DEPTH_LEN: usize = 8;
+
+/// The number of blocks of an ethash epoch, the dataset and its merkle root change every epoch.
+pub const EPOCH_LENGTH: u64 = 30000;
+/// Ethash reads 64 pairs of dag nodes, each with its merkle proof.
+pub const ETHASH_ACCESSES: usize = 64;
+
+// hard limits of the witness, checked before the expensive verification.
+pub const MAX_HEADER_SIZE: usize = 1024;
+pub const MAX_MERKLE_PROOFS: usize = ETHASH_ACCESSES;
+pub const MAX_BATCH_LEN: usize = 32;
+
+pub const PENDING_HEADER_CACHE_LIMIT: usize = 50;
+/// A parked header has at least the difficulty of the main chain tail over this, so filling the
+/// pending pool costs about as much work as mining the main chain.
+pub const PENDING_MIN_DIFFICULTY_DIVISOR: u64 = 2;
+
+/// The eip-2718 transaction types of the receipts accepted in receipt proofs, after the legacy receipts.
+pub const RECEIPT_TYPES: [u8; 2] = [1, 2];
@@ -0,0 +1,51 @@
+//! Leveled logging for the contract.
+//!
+//! Each level is compiled in only when its feature is enabled, so disabled levels
+//! cost no cycles. Enabling a level enables the levels above it, e.g. `log-info`
+//! also prints warnings and errors. With the `simulator` feature logs are printed
+//! to stdout instead of the ckb debug syscall.
+
+macro_rules! __log {
+    ($level:literal, $($args:tt)+) => {
+        #[cfg(not(feature = "simulator"))]
+        ckb_std::syscalls::debug(alloc::format!("[{}] {}", $level, alloc::format!($($args)+)));
+        #[cfg(feature = "simulator")]
+        std::println!("[{}] {}", $level, alloc::format!($($args)+));
+    };
+}
+
+macro_rules! error {
+    ($($args:tt)+) => {
+        #[cfg(feature = "log-error")]
+        {
+            __log!("ERROR", $($args)+);
+        }
+    };
+}
+
+macro_rules! warn {
+    ($($args:tt)+) => {
+        #[cfg(feature = "log-warn")]
+        {
+            __log!("WARN", $($args)+);
+        }
+    };
+}
+
+macro_rules! info {
+    ($($args:tt)+) => {
+        #[cfg(feature = "log-info")]
+        {
+            __log!("INFO", $($args)+);
+        }
+    };
+}
+
+macro_rules! trace {
+    ($($args:tt)+) => {
+        #[cfg(feature = "log-trace")]
+        {
+            __log!("TRACE", $($args)+);
+        }
+    };
+}
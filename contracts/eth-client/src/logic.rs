@@ -1,82 +1,724 @@
-use crate::types::{Error, CellDataView, witness::WitnessReader, basic::{ChainReader, Uint64}, dags_merkle_roots::DagsMerkleRootsReader, double_node_with_merkle_proof::DoubleNodeWithMerkleProofReader};
+use crate::types::{Error, BlockHash, CellDataView, to_u64, cell_data::{ClientConfigReader, RelayerSetReader}, witness::{ChainEventReader, ChainEventUnionReader, VersionedWitnessReader, VersionedWitnessUnionReader, WitnessReader, WitnessV2Reader}, basic::{Byte32Reader, ChainReader, Uint32}, dags_merkle_roots::{DagsMerkleRootsReader, EpochMerkleRootsReader}, double_node_with_merkle_proof::DoubleNodeWithMerkleProofReader};
 use crate::helper::{*, DoubleNodeWithMerkleProof};
+use eth_client_core::header::{gas_in_bounds, london_fork_block, timestamp_in_bounds, DecodeError, HeaderView};
+use eth_client_reader::find_by_number;
 use alloc::{vec, vec::Vec};
 use ckb_std::{
     ckb_constants::Source,
-    debug,
-    high_level::{load_cell_data, load_witness_args, QueryIter},
+    error::SysError,
+    ckb_types::prelude::Unpack,
+    high_level::{load_cell_capacity, load_cell_data, load_cell_lock_hash, load_cell_type_hash, load_header, load_input, load_input_since, load_script, load_script_hash, load_witness_args, QueryIter},
 };
 use molecule::prelude::{Reader,  Entity};
 use eth_spv_lib::eth_types::*;
-use crate::types::basic::{ HeaderInfoReader, BytesVecReader};
+use crate::types::basic::{ HeaderInfoReader, HeaderInfoVecReader, BytesVecReader};
+use core::cmp::Ordering;
 
-pub const MAIN_HEADER_CACHE_LIMIT: usize = 500;
-pub const UNCLE_HEADER_CACHE_LIMIT: usize = 500;
+pub use eth_client_constants::{
+    EPOCH_LENGTH, MAX_BATCH_LEN, MAX_HEADER_SIZE, MAX_MERKLE_PROOFS, MIN_UPDATE_INTERVAL_LEN,
+    PENDING_HEADER_CACHE_LIMIT, PENDING_MIN_DIFFICULTY_DIVISOR, POW_SKIP_DEPTH_LEN, ROOTS_TYPE_HASH_LEN, UNIQUE_ID_LEN,
+};
 
 #[derive(Debug)]
 pub struct CellDataTuple(Option<CellDataView>, Option<CellDataView>);
 
 pub fn verify() -> Result<(), Error> {
-    let input_data = get_data(Source::GroupInput)?.expect("should not happen");
-    let output_data = get_data(Source::GroupOutput)?.expect("should not happen");
-    verify_data(&input_data, &output_data)?;
-    debug!("verify data finish");
-    verify_witness(&input_data, &output_data)?;
+    let input_data = get_data(Source::GroupInput)?;
+    let output_data = get_data(Source::GroupOutput)?;
+    match (input_data, output_data) {
+        (None, Some(output_data)) => {
+            verify_init(&output_data)?;
+            if output_data.update_sequence != 0 {
+                return Err(Error::InvalidUpdateSequence);
+            }
+            verify_digest(&output_data)
+        }
+        (Some(input_data), Some(output_data)) => {
+            verify_capacity(&input_data)?;
+            verify_cell_lock(&input_data)?;
+            if is_touch()? {
+                info!("touch the client cell");
+                return Ok(());
+            }
+            verify_data(&input_data, &output_data)?;
+            trace!("verify data finish");
+            if input_data.user_lockscript.as_ref() != output_data.user_lockscript.as_ref() {
+                verify_lockscript_rotation(&input_data, &output_data)?;
+            } else if input_data.paused != output_data.paused
+                || input_data.relayers.as_ref() != output_data.relayers.as_ref()
+            {
+                verify_governance(&input_data, &output_data)?;
+            } else {
+                verify_witness(&input_data, &output_data)?;
+            }
+            verify_update_sequence(&input_data, &output_data)?;
+            verify_digest(&output_data)?;
+            verify_chain_event(&input_data, &output_data)
+        }
+        _ => Err(Error::TxInvalid),
+    }
+}
+
+/// every update records what it did to the main chain in the `output_type` witness of the client cell,
+/// and the record must match the digests and the main chains of the cell data.
+fn verify_chain_event(input: &CellDataView, output: &CellDataView) -> Result<(), Error> {
+    let event_raw = match load_witness_args(0, Source::GroupOutput) {
+        Ok(witness_args) => witness_args.output_type().to_opt().ok_or(Error::InvalidChainEvent)?.raw_data(),
+        Err(SysError::IndexOutOfBound) => return Err(Error::InvalidChainEvent),
+        Err(err) => return Err(err.into()),
+    };
+    if ChainEventReader::verify(&event_raw, false).is_err() {
+        return Err(Error::InvalidChainEvent);
+    }
+    let tail_unchanged = input.digest.hash == output.digest.hash;
+    let is_new_tail = |number: u64, hash: Byte32Reader| number == output.digest.number && BlockHash::from(hash).matches(&output.digest.hash);
+    let valid = match ChainEventReader::new_unchecked(&event_raw).to_enum() {
+        ChainEventUnionReader::Append(append) => {
+            !tail_unchanged
+                && reorg_depth(input, output)? == 0
+                && is_new_tail(to_u64(&append.number().to_entity()), append.hash())
+        }
+        ChainEventUnionReader::Reorg(reorg) => {
+            let depth = reorg_depth(input, output)?;
+            depth > 0
+                && to_u64(&reorg.depth().to_entity()) == depth
+                && is_new_tail(to_u64(&reorg.new_tail_number().to_entity()), reorg.new_tail_hash())
+        }
+        ChainEventUnionReader::Unchanged(_) => tail_unchanged,
+    };
+    if !valid {
+        return Err(Error::InvalidChainEvent);
+    }
     Ok(())
 }
 
+/// the number of headers of the input main chain which are not on the output main chain, counted from
+/// the input tail back to the last header both chains share.
+fn reorg_depth(input: &CellDataView, output: &CellDataView) -> Result<u64, Error> {
+    let mut number = core::cmp::min(input.digest.number, output.digest.number);
+    loop {
+        match (find_by_number(input, number)?, find_by_number(output, number)?) {
+            (Some(input_header), Some(output_header)) => {
+                if BlockHash::from(input_header.hash()) == BlockHash::from(output_header.hash()) {
+                    return Ok(input.digest.number - number);
+                }
+            }
+            // a reorg never reaches past the cached main chain.
+            _ => return Err(Error::InvalidCellData),
+        }
+        number = number.checked_sub(1).ok_or(Error::InvalidCellData)?;
+    }
+}
+
+/// every transition which changes the cell data increments the sequence by one, so indexers can order
+/// the transitions and detect gaps.
+fn verify_update_sequence(input: &CellDataView, output: &CellDataView) -> Result<(), Error> {
+    if input.update_sequence.checked_add(1) != Some(output.update_sequence) {
+        return Err(Error::InvalidUpdateSequence);
+    }
+    Ok(())
+}
+
+/// the digest must describe the main chain tail of the output. it runs after every transition, and the
+/// tail is recomputed from the stored headers, so no branch can leave them inconsistent with the digest:
+/// the hash is the hash of the tail header, which is the child of the previous main header, and the
+/// total difficulty is accumulated from it. the finalized number is recomputed with the finality weight.
+fn verify_digest(output: &CellDataView) -> Result<(), Error> {
+    if ChainReader::verify(&output.headers, false).is_err() {
+        return Err(Error::InvalidCellData);
+    }
+    let main_reader = ChainReader::new_unchecked(&output.headers).main();
+    if main_reader.is_empty() {
+        return Err(Error::InvalidCellData);
+    }
+    let main_tail_info_reader = parse_header_info(main_reader.get_unchecked(main_reader.len() - 1).raw_data())?;
+    let tail_raw = main_tail_info_reader.header().raw_data();
+    let tail = HeaderView::new(tail_raw).map_err(|_| Error::InvalidCellData)?;
+    let number = tail.number().map_err(|_| Error::InvalidCellData)?;
+    let hash = BlockHash::from(&header_hash(tail_raw));
+    let total_difficulty = to_u64(&main_tail_info_reader.total_difficulty().to_entity());
+    if !hash.matches(main_tail_info_reader.hash().raw_data()) {
+        return Err(Error::InvalidCellData);
+    }
+    if main_reader.len() > 1 {
+        let parent_info_reader = parse_header_info(main_reader.get_unchecked(main_reader.len() - 2).raw_data())?;
+        let difficulty = tail.difficulty_u64().map_err(|_| Error::InvalidCellData)?;
+        if !BlockHash::from(parent_info_reader.hash()).matches(tail.parent_hash().map_err(|_| Error::InvalidCellData)?)
+            || to_u64(&parent_info_reader.total_difficulty().to_entity()).checked_add(difficulty) != Some(total_difficulty)
+        {
+            return Err(Error::InvalidCellData);
+        }
+    }
+    let digest = &output.digest;
+    if digest.number != number || !hash.matches(&digest.hash) || digest.total_difficulty != total_difficulty {
+        return Err(Error::InvalidDigest);
+    }
+    // no mmr of the main chain is maintained, the root is reserved and always zero.
+    if digest.mmr_root != [0u8; 32] {
+        return Err(Error::InvalidDigest);
+    }
+    let finality_weight = to_u64(&parse_config(&output.config)?.finality_weight().to_entity());
+    if digest.finalized_number != finalized_number(main_reader, number, total_difficulty, finality_weight)? {
+        return Err(Error::InvalidDigest);
+    }
+    Ok(())
+}
+
+/// the number of the newest main chain header with at least `finality_weight` difficulty accumulated on
+/// top of it, the difficulty of the header itself is not counted. 0 if no cached header is heavy enough.
+fn finalized_number(main_reader: BytesVecReader, tail_number: u64, tail_total_difficulty: u64, finality_weight: u64) -> Result<u64, Error> {
+    for depth in 0..main_reader.len() {
+        let header_info_reader = parse_header_info(main_reader.get_unchecked(main_reader.len() - 1 - depth).raw_data())?;
+        let weight = tail_total_difficulty
+            .checked_sub(to_u64(&header_info_reader.total_difficulty().to_entity()))
+            .ok_or(Error::InvalidCellData)?;
+        if weight >= finality_weight {
+            return Ok(tail_number - depth as u64);
+        }
+    }
+    Ok(0)
+}
+
+/// create a client cell. the type script args must be the unique id derived from
+/// the first input of the creating tx, so there is only one canonical client instance.
+fn verify_init(output: &CellDataView) -> Result<(), Error> {
+    info!("create the client cell");
+    let args = load_script()?.args().raw_data();
+    if args.len() != UNIQUE_ID_LEN
+        && args.len() != UNIQUE_ID_LEN + MIN_UPDATE_INTERVAL_LEN
+        && args.len() != UNIQUE_ID_LEN + MIN_UPDATE_INTERVAL_LEN + ROOTS_TYPE_HASH_LEN
+        && args.len() != UNIQUE_ID_LEN + MIN_UPDATE_INTERVAL_LEN + ROOTS_TYPE_HASH_LEN + POW_SKIP_DEPTH_LEN
+    {
+        return Err(Error::InvalidTypeArgs);
+    }
+    let first_input = load_input(0, Source::Input)?;
+    let script_hash = load_script_hash()?;
+    let output_index = QueryIter::new(load_cell_type_hash, Source::Output)
+        .position(|type_hash| type_hash == Some(script_hash))
+        .ok_or(Error::ItemMissing)?;
+    let mut blake2b = new_blake2b();
+    blake2b.update(first_input.as_slice());
+    blake2b.update(&(output_index as u64).to_le_bytes());
+    let mut unique_id = [0u8; UNIQUE_ID_LEN];
+    blake2b.finalize(&mut unique_id);
+    if args[..UNIQUE_ID_LEN] != unique_id {
+        return Err(Error::InvalidTypeArgs);
+    }
+
+    // the initial chain must be well formed with at least one header on the main chain.
+    if ChainReader::verify(&output.headers, false).is_err() {
+        return Err(Error::InvalidCellData);
+    }
+    let chain_reader = ChainReader::new_unchecked(&output.headers);
+    if chain_reader.main().is_empty() {
+        return Err(Error::InvalidCellData);
+    }
+    verify_init_chain(chain_reader, load_ckb_timestamp()?)?;
+    // pending headers skip the proof of work check when promoted, so none can be created unverified.
+    if !chain_reader.pending().is_empty() {
+        return Err(Error::InvalidCellData);
+    }
+    // the cache limits can not change afterwards, so they must hold the initial chain.
+    let config_reader = parse_config(&output.config)?;
+    let main_cache_limit = to_u32(&config_reader.main_cache_limit().to_entity()) as usize;
+    let uncle_cache_limit = to_u32(&config_reader.uncle_cache_limit().to_entity()) as usize;
+    if main_cache_limit == 0 || uncle_cache_limit == 0 {
+        return Err(Error::InvalidConfig);
+    }
+    if to_u64(&config_reader.max_timestamp_gap().to_entity()) == 0 || to_u64(&config_reader.max_future_drift().to_entity()) == 0 {
+        return Err(Error::InvalidConfig);
+    }
+    let uncle_retention_depth = to_u64(&config_reader.uncle_retention_depth().to_entity());
+    if uncle_retention_depth == 0 {
+        return Err(Error::InvalidConfig);
+    }
+    // the fork point of a reorg is a cached main header, and the replaced branch is made of retained uncles.
+    let max_reorg_depth = to_u64(&config_reader.max_reorg_depth().to_entity());
+    if max_reorg_depth == 0 || max_reorg_depth > uncle_retention_depth || max_reorg_depth >= main_cache_limit as u64 {
+        return Err(Error::InvalidConfig);
+    }
+    // the main chain tail would be final right away.
+    if to_u64(&config_reader.finality_weight().to_entity()) == 0 {
+        return Err(Error::InvalidConfig);
+    }
+    if chain_reader.main().len() > main_cache_limit || chain_reader.uncle().len() > uncle_cache_limit {
+        return Err(Error::InvalidCellData);
+    }
+    Ok(())
+}
+
+/// the updates rely on the shape of the initial chain: the main chain is contiguous, so a header is
+/// found by its offset from the tail, and the uncle chain is sorted by hash without duplicates for
+/// `search_uncle`. the headers are stamped with the ckb timestamp of the creating tx.
+fn verify_init_chain(chain_reader: ChainReader, ckb_timestamp: u64) -> Result<(), Error> {
+    let main_reader = chain_reader.main();
+    let mut parent: Option<(HeaderInfoReader, u64)> = None;
+    for i in 0..main_reader.len() {
+        let header_info_reader = parse_header_info(main_reader.get_unchecked(i).raw_data())?;
+        verify_ckb_timestamp(&header_info_reader, ckb_timestamp)?;
+        let header = HeaderView::new(header_info_reader.header().raw_data()).map_err(|_| Error::InvalidCellData)?;
+        let number = header.number().map_err(|_| Error::InvalidCellData)?;
+        if let Some((parent_info_reader, parent_number)) = &parent {
+            let parent_hash = header.parent_hash().map_err(|_| Error::InvalidCellData)?;
+            if !BlockHash::from(parent_info_reader.hash()).matches(parent_hash) || parent_number.checked_add(1) != Some(number) {
+                return Err(Error::InvalidCellData);
+            }
+        }
+        parent = Some((header_info_reader, number));
+    }
+    let uncle_reader = chain_reader.uncle();
+    for i in 0..uncle_reader.len() {
+        let uncle_info_reader = uncle_reader.get_unchecked(i);
+        verify_header_info_hash(&uncle_info_reader)?;
+        verify_ckb_timestamp(&uncle_info_reader, ckb_timestamp)?;
+        if i > 0 && uncle_reader.get_unchecked(i - 1).hash().raw_data() >= uncle_info_reader.hash().raw_data() {
+            return Err(Error::InvalidCellData);
+        }
+    }
+    Ok(())
+}
+
+/// a tx which leaves the client cell data byte for byte and has no witness for it, e.g. a capacity
+/// top-up, or a rotation of the cell lock authorized by the user lockscript, see `verify_cell_lock`.
+/// a tx with a witness is verified as an update.
+fn is_touch() -> Result<bool, Error> {
+    if load_cell_data(0, Source::GroupInput)? != load_cell_data(0, Source::GroupOutput)? {
+        return Ok(false);
+    }
+    match load_witness_args(0, Source::GroupInput) {
+        Ok(witness_args) => Ok(witness_args.input_type().to_opt().is_none()),
+        Err(SysError::IndexOutOfBound) => Ok(true),
+        Err(err) => Err(err.into()),
+    }
+}
+
 fn verify_data(
     input_data: &CellDataView,
     output_data: &CellDataView,
 ) -> Result<(), Error> {
-    if input_data.user_lockscript.as_ref() != output_data.user_lockscript.as_ref()
+    if input_data.governance_lock_hash.as_ref() != output_data.governance_lock_hash.as_ref()
+    {
+        return Err(Error::InvalidDataChange);
+    }
+    if input_data.config.as_ref() != output_data.config.as_ref()
+    {
+        return Err(Error::InvalidDataChange);
+    }
+    Ok(())
+}
+
+/// change the user lockscript, nothing else can change. the tx must be authorized by the current
+/// user lockscript.
+fn verify_lockscript_rotation(
+    input_data: &CellDataView,
+    output_data: &CellDataView,
+) -> Result<(), Error> {
+    info!("rotate the user lockscript");
+    if input_data.headers.as_ref() != output_data.headers.as_ref()
+        || input_data.paused != output_data.paused
+        || input_data.relayers.as_ref() != output_data.relayers.as_ref()
     {
         return Err(Error::InvalidDataChange);
     }
+    if !has_input_lock(&user_lock_hash(input_data)) {
+        return Err(Error::UserLockMissing);
+    }
+    Ok(())
+}
+
+/// the capacity of the client cell belongs to the user, other updates can't take any of it. so the
+/// cell can have a lock anyone can unlock, and open relayers can update it without draining it.
+fn verify_capacity(input_data: &CellDataView) -> Result<(), Error> {
+    let input_capacity = load_cell_capacity(0, Source::GroupInput)?;
+    let output_capacity = load_cell_capacity(0, Source::GroupOutput)?;
+    if output_capacity < input_capacity && !has_input_lock(&user_lock_hash(input_data)) {
+        return Err(Error::CapacityDecreased);
+    }
+    Ok(())
+}
+
+/// the cell lock belongs to the user as well. with a lock anyone can unlock, any tx could otherwise
+/// set a lock nobody can unlock, or its own lock, and take the client.
+fn verify_cell_lock(input_data: &CellDataView) -> Result<(), Error> {
+    if load_cell_lock_hash(0, Source::GroupInput)? != load_cell_lock_hash(0, Source::GroupOutput)?
+        && !has_input_lock(&user_lock_hash(input_data))
+    {
+        return Err(Error::UserLockMissing);
+    }
+    Ok(())
+}
+
+fn user_lock_hash(data: &CellDataView) -> [u8; 32] {
+    let mut lock_hash = [0u8; 32];
+    let mut blake2b = new_blake2b();
+    blake2b.update(&data.user_lockscript);
+    blake2b.finalize(&mut lock_hash);
+    lock_hash
+}
+
+/// pause or unpause the client, or change the relayer set. the headers can not change, and the tx
+/// must be authorized by the governance lock.
+fn verify_governance(
+    input_data: &CellDataView,
+    output_data: &CellDataView,
+) -> Result<(), Error> {
+    info!("governance: set paused to {}", output_data.paused);
+    if input_data.headers.as_ref() != output_data.headers.as_ref() {
+        return Err(Error::InvalidDataChange);
+    }
+    if !has_input_lock(&input_data.governance_lock_hash) {
+        return Err(Error::GovernanceLockMissing);
+    }
+    Ok(())
+}
+
+/// a lock in the inputs authorizes the tx, since the lock script is executed on the tx.
+fn has_input_lock(lock_hash: &[u8]) -> bool {
+    QueryIter::new(load_cell_lock_hash, Source::Input).any(|hash| hash[..] == lock_hash[..])
+}
+
+/// when a relayer set is configured, the relayers take turns to submit headers, each one
+/// for `slot_length` blocks. the relayer of the slot authorizes the tx with its lock. a zero
+/// slot length is an allowlist, any of the relayers authorizes the tx.
+fn verify_relayer(relayers: &[u8], number: u64) -> Result<(), Error> {
+    if RelayerSetReader::verify(relayers, false).is_err() {
+        return Err(Error::InvalidCellData);
+    }
+    let relayer_set_reader = RelayerSetReader::new_unchecked(relayers);
+    let lock_hashes = relayer_set_reader.lock_hashes();
+    if lock_hashes.is_empty() {
+        return Ok(());
+    }
+    let slot_length = to_u64(&relayer_set_reader.slot_length().to_entity());
+    if slot_length == 0 {
+        if !(0..lock_hashes.len()).any(|i| has_input_lock(lock_hashes.get_unchecked(i).raw_data())) {
+            return Err(Error::InvalidRelayer);
+        }
+        return Ok(());
+    }
+    let slot = (number / slot_length) % lock_hashes.len() as u64;
+    if !has_input_lock(lock_hashes.get_unchecked(slot as usize).raw_data()) {
+        return Err(Error::InvalidRelayer);
+    }
     Ok(())
 }
 
 /// ensure transfer happen on XChain by verifying the spv proof
 fn verify_witness(input: &CellDataView, output: &CellDataView) -> Result<(), Error> {
-    let witness_args = load_witness_args(0, Source::GroupInput)?.input_type();
-    if witness_args.is_none() {
-        return Err(Error::InvalidWitness);
+    if input.paused {
+        return Err(Error::ClientPaused);
     }
-    let witness_args = witness_args.to_opt().unwrap().raw_data();
-    if WitnessReader::verify(&witness_args, false).is_err() {
+    verify_update_interval()?;
+    let witness_args = load_witness_args(0, Source::GroupInput)?
+        .input_type()
+        .to_opt()
+        .ok_or(Error::InvalidWitness)?
+        .raw_data();
+    // relayers built before the versioned witness send the v1 table directly.
+    if WitnessReader::verify(&witness_args, false).is_ok() {
+        return verify_witness_v1(input, output, WitnessReader::new_unchecked(&witness_args));
+    }
+    if VersionedWitnessReader::verify(&witness_args, false).is_err() {
         return Err(Error::InvalidWitness);
     }
-    let witness = WitnessReader::new_unchecked(&witness_args);
-    // parse header
-    let header_raw = witness.header().raw_data();
+    match VersionedWitnessReader::new_unchecked(&witness_args).to_enum() {
+        VersionedWitnessUnionReader::Witness(witness) => verify_witness_v1(input, output, witness),
+        VersionedWitnessUnionReader::WitnessV2(witness) => verify_witness_v2(input, output, witness),
+    }
+}
+
+/// when the type script args carry a min update interval, the client cell input must be at least
+/// that many ckb blocks old, i.e. a relative block number `since`. governance txs are not throttled.
+fn verify_update_interval() -> Result<(), Error> {
+    let args = load_script()?.args().raw_data();
+    if args.len() < UNIQUE_ID_LEN + MIN_UPDATE_INTERVAL_LEN {
+        return Ok(());
+    }
+    let mut interval = [0u8; MIN_UPDATE_INTERVAL_LEN];
+    interval.copy_from_slice(&args[UNIQUE_ID_LEN..UNIQUE_ID_LEN + MIN_UPDATE_INTERVAL_LEN]);
+    let min_update_interval = u64::from_le_bytes(interval);
+    if min_update_interval == 0 {
+        return Ok(());
+    }
+    // the relative flag is the highest bit, the metric flags after it are 0 for block numbers.
+    let since = load_input_since(0, Source::GroupInput)?;
+    if since >> 56 != 0x80 || since & 0x00ff_ffff_ffff_ffff < min_update_interval {
+        return Err(Error::UpdateTooFrequent);
+    }
+    Ok(())
+}
+
+fn verify_witness_v1(input: &CellDataView, output: &CellDataView, witness: WitnessReader) -> Result<(), Error> {
+    verify_witness_limits(witness.header().raw_data(), witness.merkle_proof())?;
+    verify_header_submission(
+        input,
+        output,
+        witness.header().raw_data(),
+        witness.merkle_proof(),
+        witness.cell_dep_index_list().raw_data(),
+        witness.expected_tail_hash().raw_data(),
+    )
+}
+
+/// a single header is handled like v1, a longer batch must extend the main chain.
+fn verify_witness_v2(input: &CellDataView, output: &CellDataView, witness: WitnessV2Reader) -> Result<(), Error> {
+    // receipt proofs and checkpoint signatures are reserved for later versions of the contract.
+    if !witness.receipt_proofs().is_empty() || !witness.checkpoint_signatures().is_empty() {
+        return Err(Error::UnsupportedWitness);
+    }
+    let headers = witness.headers();
+    if headers.len() > MAX_BATCH_LEN {
+        return Err(Error::BatchTooLong);
+    }
+    for i in 0..headers.len() {
+        verify_witness_limits(headers.get_unchecked(i).header().raw_data(), headers.get_unchecked(i).merkle_proof())?;
+    }
+    match headers.len() {
+        0 => Err(Error::InvalidWitness),
+        1 => verify_header_submission(
+            input,
+            output,
+            headers.get_unchecked(0).header().raw_data(),
+            headers.get_unchecked(0).merkle_proof(),
+            witness.cell_dep_index_list().raw_data(),
+            witness.expected_tail_hash().raw_data(),
+        ),
+        _ => verify_batch(input, output, witness),
+    }
+}
+
+fn verify_witness_limits(header_raw: &[u8], merkle_proof: BytesVecReader) -> Result<(), Error> {
+    if header_raw.len() > MAX_HEADER_SIZE {
+        return Err(Error::HeaderTooLarge);
+    }
+    if merkle_proof.len() > MAX_MERKLE_PROOFS {
+        return Err(Error::TooManyMerkleProofs);
+    }
+    Ok(())
+}
+
+fn verify_header_submission(
+    input: &CellDataView,
+    output: &CellDataView,
+    header_raw: &[u8],
+    merkle_proof: BytesVecReader,
+    cell_dep_index_list: &[u8],
+    expected_tail_hash: &[u8],
+) -> Result<(), Error> {
     // check input && output data
-    let header = verify_input_output_data(input, output, header_raw)?;
+    let (header, promoted) = verify_input_output_data(input, output, header_raw, expected_tail_hash)?;
+    verify_relayer(&input.relayers, header.number)?;
+    if promoted {
+        // the proof of work had been verified when the header was parked.
+        return Ok(());
+    }
+    verify_header_pow(&header, merkle_proof, cell_dep_index_list)
+}
+
+fn verify_header_pow(header: &BlockHeader, merkle_proof: BytesVecReader, cell_dep_index_list: &[u8]) -> Result<(), Error> {
+    // one proof for each dag access, so no proof is left unchecked or missing.
+    if merkle_proof.len() != MAX_MERKLE_PROOFS {
+        return Err(Error::InvalidMerkleProofCount);
+    }
     // parse merkle proof
     let mut proofs = vec![];
-    for i in 0..witness.merkle_proof().len() {
-        let proof_raw = witness.merkle_proof().get_unchecked(i).raw_data();
-        let proof = parse_proof(proof_raw)?;
+    for i in 0..merkle_proof.len() {
+        let proof = parse_proof(merkle_proof.get_unchecked(i).raw_data())?;
         proofs.push(proof);
     }
     // parse dep data
-    let merkle_root = parse_dep_data(witness, header.number)?;
-    if !verify_header(&header, Option::None, merkle_root, &proofs) {
+    let merkle_root = parse_dep_data(cell_dep_index_list, header.number)?;
+    if !verify_header(header, Option::None, merkle_root, &proofs) {
         return Err(Error::InvalidMerkleProofData);
     }
     Ok(())
 }
 
-fn verify_input_output_data(input: &CellDataView, output: &CellDataView, header_raw: &[u8]) -> Result<BlockHeader, Error> {
-    debug!("verify input && output data. make sure the main chain is right.");
-    let header: BlockHeader = rlp::decode(header_raw.to_vec().as_slice()).unwrap();
-    debug!("header after decode is {:?}", header);
+/// the headers of the batch extend the main chain one by one, none of them can fork or be parked.
+fn verify_batch(input: &CellDataView, output: &CellDataView, witness: WitnessV2Reader) -> Result<(), Error> {
+    if ChainReader::verify(&input.headers, false).is_err() || ChainReader::verify(&output.headers, false).is_err() {
+        return Err(Error::InvalidCellData);
+    }
+    let chain_input_reader = ChainReader::new_unchecked(&input.headers);
+    let chain_output_reader = ChainReader::new_unchecked(&output.headers);
+    let main_input_reader = chain_input_reader.main();
+    let main_output_reader = chain_output_reader.main();
+    if main_input_reader.is_empty()
+        || chain_input_reader.uncle().as_slice() != chain_output_reader.uncle().as_slice()
+        || chain_input_reader.pending().as_slice() != chain_output_reader.pending().as_slice()
+    {
+        return Err(Error::InvalidCellData);
+    }
+    let main_tail_info_input_reader = parse_header_info(main_input_reader.get_unchecked(main_input_reader.len() - 1).raw_data())?;
+    if !BlockHash::from(main_tail_info_input_reader.hash()).matches(witness.expected_tail_hash().raw_data()) {
+        return Err(Error::StaleWitness);
+    }
+    let config_reader = parse_config(&input.config)?;
+    let main_cache_limit = to_u32(&config_reader.main_cache_limit().to_entity()) as usize;
+    let pow_skip_depth = load_pow_skip_depth()?;
+    let ckb_timestamp = load_ckb_timestamp()?;
+
+    let headers = witness.headers();
+    let new_start = main_output_reader.len().checked_sub(headers.len()).ok_or(Error::InvalidCellData)?;
+    let mut parent_hash = main_tail_info_input_reader.hash().raw_data();
+    let mut parent_raw = main_tail_info_input_reader.header().raw_data();
+    let parent = HeaderView::new(parent_raw).map_err(|_| Error::InvalidCellData)?;
+    let mut parent_number = parent.number().map_err(|_| Error::InvalidCellData)?;
+    let mut parent_timestamp = parent.timestamp().map_err(|_| Error::InvalidCellData)?;
+    let mut parent_gas_limit = parent.gas_limit_u64().map_err(|_| Error::InvalidCellData)?;
+    let mut total_difficulty = to_u64(&main_tail_info_input_reader.total_difficulty().to_entity());
+    let mut expected_main = vec![];
+    for i in 0..main_input_reader.len() {
+        expected_main.push(main_input_reader.get_unchecked(i).raw_data());
+    }
+    // every header is decoded, a buried header only skips the proof of work.
+    let mut decoded = Vec::with_capacity(headers.len());
+    for i in 0..headers.len() {
+        let witness_header = headers.get_unchecked(i);
+        let header = decode_header(witness_header.header().raw_data())?;
+        let fields = HeaderFields::from_header(&header)?;
+        // the last `pow_skip_depth` headers of the batch always carry their proofs.
+        let buried = (headers.len() - 1 - i) as u64;
+        let verify_pow = pow_skip_depth == 0 || buried < pow_skip_depth || !witness_header.merkle_proof().is_empty();
+        decoded.push((header, fields, verify_pow));
+    }
+    // the proofs of work of the later headers don't cover a buried header, so its difficulty is only a
+    // claim. it is bounded by the difficulty of the next header with a verified proof of work, and only
+    // verified difficulty is accumulated, so headers without proofs can't win a reorg.
+    let mut verified_difficulty = 0;
+    for (_, fields, verify_pow) in decoded.iter().rev() {
+        if *verify_pow {
+            verified_difficulty = fields.difficulty;
+        } else if fields.difficulty > verified_difficulty {
+            return Err(Error::BuriedDifficultyTooHigh);
+        }
+    }
+    for (i, (header, fields, verify_pow)) in decoded.iter().enumerate() {
+        let witness_header = headers.get_unchecked(i);
+        let header_raw = witness_header.header().raw_data();
+        let hash = header_hash(header_raw);
+        if header.hash != Some(hash) {
+            return Err(Error::InvalidHeaderHash);
+        }
+        if !fields.parent_hash.matches(parent_hash) || parent_number.checked_add(1) != Some(fields.number) {
+            return Err(Error::InvalidWitness);
+        }
+        // a known uncle would take another cache slot, as in a single header update.
+        if search_uncle(chain_input_reader.uncle(), &hash)?.is_ok() {
+            return Err(Error::HeaderAlreadyExists);
+        }
+        // the parent is the main chain tail when the header is appended.
+        verify_timestamp(fields.timestamp, Some(parent_timestamp), parent_timestamp, &config_reader)?;
+        verify_gas(fields.gas_limit, fields.gas_used, Some(parent_gas_limit), london_fork_block(header_raw, parent_raw))?;
+        if *verify_pow {
+            total_difficulty = total_difficulty.checked_add(fields.difficulty).ok_or(Error::InvalidCellData)?;
+        }
+
+        let header_info_raw = main_output_reader.get_unchecked(new_start + i).raw_data();
+        let header_info_reader = parse_header_info(header_info_raw)?;
+        if header_info_reader.header().raw_data() != header_raw
+            || to_u64(&header_info_reader.total_difficulty().to_entity()) != total_difficulty
+        {
+            return Err(Error::InvalidCellData);
+        }
+        verify_ckb_timestamp(&header_info_reader, ckb_timestamp)?;
+        expected_main.push(header_info_raw);
+
+        verify_relayer(&input.relayers, fields.number)?;
+        if *verify_pow {
+            verify_header_pow(header, witness_header.merkle_proof(), witness.cell_dep_index_list().raw_data())?;
+        }
+        parent_hash = header_info_reader.hash().raw_data();
+        parent_number = fields.number;
+        parent_timestamp = fields.timestamp;
+        parent_gas_limit = fields.gas_limit;
+        parent_raw = header_raw;
+    }
+
+    let trimmed = expected_main.len().saturating_sub(main_cache_limit);
+    if main_output_reader.len() != expected_main.len() - trimmed {
+        return Err(Error::InvalidCellData);
+    }
+    for (i, header_info) in expected_main[trimmed..].iter().enumerate() {
+        if main_output_reader.get_unchecked(i).raw_data() != *header_info {
+            return Err(Error::InvalidCellData);
+        }
+    }
+    Ok(())
+}
+
+/// decode a witness header, see `eth_client_core::header::decode_header`.
+fn decode_header(header_raw: &[u8]) -> Result<BlockHeader, Error> {
+    eth_client_core::header::decode_header(header_raw).map_err(|err| match err {
+        DecodeError::Undecodable => Error::HeaderDecodeFailed,
+        DecodeError::NonCanonical => Error::NonCanonicalHeader,
+    })
+}
+
+/// the fields linking a witness header to its parent.
+struct HeaderFields {
+    parent_hash: BlockHash,
+    number: u64,
+    timestamp: u64,
+    // the total difficulty is accumulated in a u64.
+    difficulty: u64,
+    gas_limit: u64,
+    gas_used: u64,
+}
+
+impl HeaderFields {
+    fn from_header(header: &BlockHeader) -> Result<HeaderFields, Error> {
+        if header.difficulty.0 > u64::MAX.into() {
+            return Err(Error::InvalidWitness);
+        }
+        if header.gas_limit.0 > u64::MAX.into() || header.gas_used.0 > u64::MAX.into() {
+            return Err(Error::InvalidGasLimit);
+        }
+        Ok(HeaderFields {
+            parent_hash: BlockHash::from(&header.parent_hash),
+            number: header.number,
+            timestamp: header.timestamp,
+            difficulty: header.difficulty.0.as_u64(),
+            gas_limit: header.gas_limit.0.as_u64(),
+            gas_used: header.gas_used.0.as_u64(),
+        })
+    }
+}
+
+/// bound the timestamps with the config, see `timestamp_in_bounds`.
+fn verify_timestamp(timestamp: u64, parent_timestamp: Option<u64>, tail_timestamp: u64, config_reader: &ClientConfigReader) -> Result<(), Error> {
+    let max_timestamp_gap = to_u64(&config_reader.max_timestamp_gap().to_entity());
+    let max_future_drift = to_u64(&config_reader.max_future_drift().to_entity());
+    if !timestamp_in_bounds(timestamp, parent_timestamp, tail_timestamp, max_timestamp_gap, max_future_drift) {
+        return Err(Error::InvalidTimestamp);
+    }
+    Ok(())
+}
+
+/// bound the gas fields, see `gas_in_bounds`.
+fn verify_gas(gas_limit: u64, gas_used: u64, parent_gas_limit: Option<u64>, london_fork_block: bool) -> Result<(), Error> {
+    if !gas_in_bounds(gas_limit, gas_used, parent_gas_limit, london_fork_block) {
+        return Err(Error::InvalidGasLimit);
+    }
+    Ok(())
+}
+
+/// returns the new header, and whether it was promoted from the pending pool.
+fn verify_input_output_data(input: &CellDataView, output: &CellDataView, header_raw: &[u8], expected_tail_hash: &[u8]) -> Result<(BlockHeader, bool), Error> {
+    trace!("verify input && output data. make sure the main chain is right.");
+    let header = decode_header(header_raw)?;
+    trace!("header after decode is {:?}", header);
+    let hash = header_hash(header_raw);
+    if header.hash != Some(hash) {
+        return Err(Error::InvalidHeaderHash);
+    }
 
     if ChainReader::verify(&input.headers, false).is_err() {
         return Err(Error::InvalidCellData);
     }
     let chain_input_reader = ChainReader::new_unchecked(&input.headers);
     let main_input_reader = chain_input_reader.main();
-    debug!("input: the main chain length: {:?}", main_input_reader.len());
+    trace!("input: the main chain length: {:?}", main_input_reader.len());
     let uncle_input_reader = chain_input_reader.uncle();
     if ChainReader::verify(&output.headers, false).is_err() {
         return Err(Error::InvalidCellData);
@@ -84,172 +726,538 @@ fn verify_input_output_data(input: &CellDataView, output: &CellDataView, header_
     let chain_output_reader = ChainReader::new_unchecked(&output.headers);
     let main_output_reader = chain_output_reader.main();
     let uncle_output_reader = chain_output_reader.uncle();
-    debug!("output: the main chain length: {:?}", main_output_reader.len());
-    // header is on main chain.
-    let main_tail_info_input = main_input_reader.get_unchecked(main_input_reader.len() - 1).raw_data();
-    if HeaderInfoReader::verify(&main_tail_info_input, false).is_err() {
+    trace!("output: the main chain length: {:?}", main_output_reader.len());
+    if main_input_reader.is_empty() || main_output_reader.is_empty() {
         return Err(Error::InvalidCellData);
     }
-    let main_tail_info_input_reader = HeaderInfoReader::new_unchecked(main_tail_info_input);
-    let main_tail_header_input = main_tail_info_input_reader.header().raw_data();
+    // the witness is built on another state of the client, e.g. a replayed or front-run tx.
+    let main_tail_info_input_reader = parse_header_info(main_input_reader.get_unchecked(main_input_reader.len() - 1).raw_data())?;
+    if !BlockHash::from(main_tail_info_input_reader.hash()).matches(expected_tail_hash) {
+        return Err(Error::StaleWitness);
+    }
+    let parent_number = header.number.checked_sub(1).ok_or(Error::InvalidWitness)?;
+    // the difficulty is accumulated in a u64.
+    if header.difficulty.0 > u64::MAX.into() {
+        return Err(Error::InvalidWitness);
+    }
+    let difficulty = header.difficulty.0.as_u64();
+    // the config is the same on both sides, see `verify_data`.
+    let config_reader = parse_config(&input.config)?;
+    let main_cache_limit = to_u32(&config_reader.main_cache_limit().to_entity()) as usize;
+    let uncle_cache_limit = to_u32(&config_reader.uncle_cache_limit().to_entity()) as usize;
+    let uncle_retention_depth = to_u64(&config_reader.uncle_retention_depth().to_entity());
+    let max_reorg_depth = to_u64(&config_reader.max_reorg_depth().to_entity());
+    let tail_timestamp = HeaderView::new(main_tail_info_input_reader.header().raw_data()).and_then(|header| header.timestamp()).map_err(|_| Error::InvalidCellData)?;
+    let parent_info_reader = find_known_header(main_input_reader, uncle_input_reader, &header.parent_hash, parent_number)?;
+    let parent_timestamp = match &parent_info_reader {
+        Some(parent_info_reader) => Some(HeaderView::new(parent_info_reader.header().raw_data()).and_then(|header| header.timestamp()).map_err(|_| Error::InvalidCellData)?),
+        None => None,
+    };
+    verify_timestamp(header.timestamp, parent_timestamp, tail_timestamp, &config_reader)?;
+    let parent_gas_limit = match &parent_info_reader {
+        Some(parent_info_reader) => Some(HeaderView::new(parent_info_reader.header().raw_data()).and_then(|header| header.gas_limit_u64()).map_err(|_| Error::InvalidCellData)?),
+        None => None,
+    };
+    let fork_block = match &parent_info_reader {
+        Some(parent_info_reader) => london_fork_block(header_raw, parent_info_reader.header().raw_data()),
+        None => false,
+    };
+    let fields = HeaderFields::from_header(&header)?;
+    verify_gas(fields.gas_limit, fields.gas_used, parent_gas_limit, fork_block)?;
 
-    let main_tail_info_output = main_output_reader.get_unchecked(main_output_reader.len() - 1).raw_data();
-    if HeaderInfoReader::verify(&main_tail_info_output, false).is_err() {
-        return Err(Error::InvalidCellData);
+    let pending_input_reader = chain_input_reader.pending();
+    let pending_output_reader = chain_output_reader.pending();
+    let mut promoted = false;
+    if pending_output_reader.len() + 1 == pending_input_reader.len() {
+        info!("promote the header from the pending pool");
+        verify_pending_promotion(pending_input_reader, pending_output_reader, header_raw)?;
+        promoted = true;
+    } else if pending_input_reader.as_slice() != pending_output_reader.as_slice() {
+        info!("park the header in the pending pool");
+        // the main chain and the uncle chain should be the same.
+        if main_input_reader.as_slice() != main_output_reader.as_slice()
+            || uncle_input_reader.as_slice() != uncle_output_reader.as_slice()
+        {
+            return Err(Error::InvalidCellData);
+        }
+        if parent_info_reader.is_some() {
+            return Err(Error::InvalidCellData);
+        }
+        verify_pending_difficulty(difficulty, main_tail_info_input_reader.header().raw_data())?;
+        verify_pending_parking(pending_input_reader, pending_output_reader, header_raw)?;
+        return Ok((header, false));
+    }
+    // a known header would take another cache slot.
+    if find_known_header(main_input_reader, uncle_input_reader, &hash, header.number)?.is_some() {
+        return Err(Error::HeaderAlreadyExists);
     }
-    let main_tail_info_output_reader = HeaderInfoReader::new_unchecked(main_tail_info_output);
-    let main_tail_header_output = main_tail_info_output_reader.header().raw_data();
+
+    let main_tail_info_output_reader = parse_header_info(main_output_reader.get_unchecked(main_output_reader.len() - 1).raw_data())?;
+    let ckb_timestamp = load_ckb_timestamp()?;
 
     // header is on main chain.
-    if main_tail_header_output == header_raw {
-        debug!("the new header is on main chain");
-        assert_eq!(main_tail_info_output_reader.hash().raw_data(), header.hash.unwrap().0.as_bytes());
-        let main_tail_input: BlockHeader = rlp::decode(main_tail_header_input.to_vec().as_slice()).unwrap();
-        debug!("new header parent hash: {:?} ", header.parent_hash.0);
-        debug!("input main chain tail hash: {:?}", main_tail_input.hash.unwrap().0);
+    if main_tail_info_output_reader.header().raw_data() == header_raw {
+        info!("the new header is on main chain");
+        verify_ckb_timestamp(&main_tail_info_output_reader, ckb_timestamp)?;
+        let total_difficulty = to_u64(&main_tail_info_output_reader.total_difficulty().to_entity());
+        let prev_total_difficulty = to_u64(&main_tail_info_input_reader.total_difficulty().to_entity());
+        trace!("new header parent hash: {:?} ", header.parent_hash.0);
         // if header.parent_hash == tail_input.hash => the chain is not reorg.
         // else do reorg.
-        if main_tail_input.hash.unwrap() == header.parent_hash {
-            debug!("the main chain is not reorg.");
-            let prev_difficult: Uint64 = main_tail_info_input_reader.total_difficulty().to_entity();
-            let left: Uint64 = main_tail_info_output_reader.total_difficulty().to_entity();
-            let right: Uint64 = header.difficulty.0.as_u64().into();
-            debug!("The total difficulty of the output chain is the total difficulty of the input chain plus the difficulty of the new block");
-            debug!("left difficulty u64: {} right difficulty u64: {}", to_u64(&left), to_u64(&right).checked_add(to_u64(&prev_difficult)).unwrap());
-            assert_eq!(to_u64(&left), to_u64(&right).checked_add(to_u64(&prev_difficult)).unwrap());
-
-            if main_output_reader.len() > MAIN_HEADER_CACHE_LIMIT {
+        if BlockHash::from(main_tail_info_input_reader.hash()) == BlockHash::from(&header.parent_hash) {
+            info!("the main chain is not reorg.");
+            trace!("The total difficulty of the output chain is the total difficulty of the input chain plus the difficulty of the new block");
+            trace!("left difficulty u64: {} right difficulty u64: {}", total_difficulty, difficulty);
+            if prev_total_difficulty.checked_add(difficulty) != Some(total_difficulty) {
                 return Err(Error::InvalidCellData);
             }
-            debug!("the uncle chain should be the same");
-            verify_original_chain_data(main_input_reader, main_output_reader, MAIN_HEADER_CACHE_LIMIT)?;
-            // the uncle chain should be the same.
-            assert_eq!(uncle_input_reader.as_slice(),uncle_output_reader.as_slice());
+            verify_original_chain_data(main_input_reader, main_output_reader, main_cache_limit)?;
+            // the uncle chain should be the same, except that stale uncles may be pruned.
+            verify_uncle_pruning(uncle_input_reader, uncle_output_reader, header.number, uncle_retention_depth)?;
         } else {
-            debug!("warning: the main chain had been reorged.");
-            let left: Uint64 = main_tail_info_input_reader.total_difficulty().to_entity();
-            let right: Uint64 = main_tail_info_output_reader.total_difficulty().to_entity();
-            if to_u64(&right) >= to_u64(&left) {// header.number < main_tail_input.number
-                // assert_eq!(main_tail_input.number - header.number > 0, true)
-                let mut number = header.number - 1;
-                let mut current_hash = header.parent_hash;
-                loop {
-                    if number == 0 {
-                        return Err(Error::InvalidCellData);
-                    }
-                    // find parent header.
-                    if main_tail_input.number <= number { // the parent header is on uncle chain.
-                        traverse_uncle_chain(uncle_input_reader, &mut current_hash, &mut number)?;
-                    } else {
-                        let offset = (main_tail_input.number - number-1) as usize;
-                        if offset > main_input_reader.len() {
-                            return Err(Error::InvalidCellData);
-                        }
-                        let header_info_temp = main_input_reader.get_unchecked(main_input_reader.len()-1-offset).raw_data();
-                        if HeaderInfoReader::verify(&header_info_temp, false).is_err() {
-                            return Err(Error::InvalidCellData);
-                        }
-                        let header_info_temp_reader = HeaderInfoReader::new_unchecked(header_info_temp);
-                        // let header_temp: BlockHeader = rlp::decode(header_temp.to_vec().as_slice()).unwrap();
-                        if header_info_temp_reader.hash().raw_data() == current_hash.0.as_bytes() {// the parent header is on main chain.
-                            let mut input_data = vec![];
-                            for i in 1..main_input_reader.len()-1-offset {
-                                input_data.push(main_input_reader.get_unchecked(i).raw_data())
-                            }
-                            let mut output_data = vec![];
-                            for i in 0..main_output_reader.len()-1 {
-                                output_data.push(main_output_reader.get_unchecked(i).raw_data())
-                            }
-                            assert_eq!(input_data, output_data);
-                            break;
-                        } else {// the parent header is on uncle chain.
-                            traverse_uncle_chain(uncle_input_reader, &mut current_hash, &mut number)?;
-                        }
-                    }
-                }
-            } else {
+            warn!("the main chain had been reorged.");
+            // on a tie the current chain is kept and the header becomes an uncle, so equal weight forks
+            // can not flip the main chain back and forth.
+            if total_difficulty <= prev_total_difficulty {
                 return Err(Error::InvalidCellData);
             }
+            verify_reorg(
+                main_input_reader,
+                main_output_reader,
+                uncle_input_reader,
+                uncle_output_reader,
+                &header,
+                difficulty,
+                total_difficulty,
+                main_cache_limit,
+                uncle_cache_limit,
+                uncle_retention_depth,
+                max_reorg_depth,
+            )?;
         }
     } else {
-        debug!("warning: the new header is not on main chain.");
-        // the header is on uncle chain. just do append.
-        verify_original_chain_data(uncle_input_reader, uncle_output_reader, UNCLE_HEADER_CACHE_LIMIT)?;
+        warn!("the new header is not on main chain.");
+        // the parent of an uncle must be known, otherwise the header should be parked.
+        let parent_info_reader = parent_info_reader.ok_or(Error::InvalidCellData)?;
+        // the header is on uncle chain. insert it in hash order.
+        let tail_number = HeaderView::new(main_tail_info_input_reader.header().raw_data()).and_then(|header| header.number()).map_err(|_| Error::InvalidCellData)?;
+        if tail_number.saturating_sub(header.number) > uncle_retention_depth {
+            return Err(Error::UncleTooOld);
+        }
+        verify_uncle_insertion(uncle_input_reader, uncle_output_reader, &hash, header_raw, tail_number, uncle_cache_limit, uncle_retention_depth)?;
+        let uncle_index = search_uncle(uncle_output_reader, &hash)?.map_err(|_| Error::InvalidCellData)?;
+        verify_ckb_timestamp(&uncle_output_reader.get_unchecked(uncle_index), ckb_timestamp)?;
+        let total_difficulty = to_u64(&uncle_output_reader.get_unchecked(uncle_index).total_difficulty().to_entity());
+        if to_u64(&parent_info_reader.total_difficulty().to_entity()).checked_add(difficulty) != Some(total_difficulty) {
+            return Err(Error::InvalidCellData);
+        }
+        // a heavier header must reorg the main chain.
+        if total_difficulty > to_u64(&main_tail_info_input_reader.total_difficulty().to_entity()) {
+            return Err(Error::InvalidCellData);
+        }
         // the main chain should be the same.
-        assert_eq!(main_output_reader.as_slice(),main_input_reader.as_slice());
+        if main_output_reader.as_slice() != main_input_reader.as_slice() {
+            return Err(Error::InvalidCellData);
+        }
     }
-    // assert_eq!(main_output_reader.get_unchecked(main_output_reader.len() - 1).raw_data(), header_raw);
-    Ok(header)
+    Ok((header, promoted))
 }
 
-fn traverse_uncle_chain(uncle_input_reader: BytesVecReader,  current_hash: &mut H256,  number: &mut u64) -> Result<(), Error>{
-    let mut index = uncle_input_reader.len()-1;
-    loop {
-        if index == 0 {
+/// the new header forks from the main chain. walking back from its parent through the uncle chain to
+/// the fork point, the branch replaces the main chain after the fork point and the replaced headers
+/// become uncles. both chains are trimmed to their cache limits.
+#[allow(clippy::too_many_arguments)]
+fn verify_reorg(
+    main_input_reader: BytesVecReader,
+    main_output_reader: BytesVecReader,
+    uncle_input_reader: HeaderInfoVecReader,
+    uncle_output_reader: HeaderInfoVecReader,
+    header: &BlockHeader,
+    difficulty: u64,
+    total_difficulty: u64,
+    main_cache_limit: usize,
+    uncle_cache_limit: usize,
+    uncle_retention_depth: u64,
+    max_reorg_depth: u64,
+) -> Result<(), Error> {
+    // indexes on the uncle chain, from the parent of the new header to the fork point.
+    let mut branch = vec![];
+    let mut current_hash = header.parent_hash;
+    let mut number = header.number.checked_sub(1).ok_or(Error::InvalidWitness)?;
+    let fork_index = loop {
+        if let Some(index) = main_chain_index(main_input_reader, &current_hash, number)? {
+            break index;
+        }
+        let uncle_index = search_uncle(uncle_input_reader, &current_hash)?.map_err(|_| Error::InvalidCellData)?;
+        let uncle_header_raw = uncle_input_reader.get_unchecked(uncle_index).header().raw_data();
+        if HeaderView::new(uncle_header_raw).and_then(|header| header.number()).map_err(|_| Error::InvalidCellData)? != number {
             return Err(Error::InvalidCellData);
         }
-        let uncle_tail_input = uncle_input_reader.get_unchecked(index).raw_data();
-        if HeaderInfoReader::verify(&uncle_tail_input, false).is_err() {
+        branch.push(uncle_index);
+        let parent_hash = HeaderView::new(uncle_header_raw).and_then(|uncle| uncle.parent_hash()).map_err(|_| Error::InvalidCellData)?;
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(parent_hash);
+        current_hash = H256(hash.into());
+        // the fork point must be on the cached main chain.
+        number = number.checked_sub(1).ok_or(Error::InvalidCellData)?;
+    };
+    if (main_input_reader.len() - 1 - fork_index) as u64 > max_reorg_depth {
+        return Err(Error::ReorgTooDeep);
+    }
+
+    let parent_total_difficulty = match branch.first() {
+        Some(index) => to_u64(&uncle_input_reader.get_unchecked(*index).total_difficulty().to_entity()),
+        None => to_u64(&parse_header_info(main_input_reader.get_unchecked(fork_index).raw_data())?.total_difficulty().to_entity()),
+    };
+    if parent_total_difficulty.checked_add(difficulty) != Some(total_difficulty) {
+        return Err(Error::InvalidCellData);
+    }
+
+    let mut expected_main = vec![];
+    for i in 0..=fork_index {
+        expected_main.push(main_input_reader.get_unchecked(i).raw_data());
+    }
+    for index in branch.iter().rev() {
+        expected_main.push(uncle_input_reader.get_unchecked(*index).as_slice());
+    }
+    expected_main.push(main_output_reader.get_unchecked(main_output_reader.len() - 1).raw_data());
+    let trimmed = expected_main.len().saturating_sub(main_cache_limit);
+    if main_output_reader.len() != expected_main.len() - trimmed {
+        return Err(Error::InvalidCellData);
+    }
+    for (i, header_info) in expected_main[trimmed..].iter().enumerate() {
+        if main_output_reader.get_unchecked(i).raw_data() != *header_info {
+            return Err(Error::InvalidCellData);
+        }
+    }
+
+    let mut expected_uncle = vec![];
+    for i in 0..uncle_input_reader.len() {
+        if !branch.contains(&i) {
+            expected_uncle.push(uncle_input_reader.get_unchecked(i).as_slice());
+        }
+    }
+    for i in fork_index + 1..main_input_reader.len() {
+        let header_info_raw = main_input_reader.get_unchecked(i).raw_data();
+        parse_header_info(header_info_raw)?;
+        expected_uncle.push(header_info_raw);
+    }
+    expected_uncle.sort_by(|a, b| {
+        HeaderInfoReader::new_unchecked(a).hash().raw_data().cmp(HeaderInfoReader::new_unchecked(b).hash().raw_data())
+    });
+    evict_stale_uncles(&mut expected_uncle, header.number, uncle_retention_depth)?;
+    while expected_uncle.len() > uncle_cache_limit {
+        let evicted = find_oldest_uncle(&expected_uncle)?;
+        expected_uncle.remove(evicted);
+    }
+    if uncle_output_reader.len() != expected_uncle.len() {
+        return Err(Error::InvalidCellData);
+    }
+    for (i, uncle) in expected_uncle.iter().enumerate() {
+        if uncle_output_reader.get_unchecked(i).as_slice() != *uncle {
+            return Err(Error::InvalidCellData);
+        }
+    }
+    Ok(())
+}
+
+/// the main chain is continuous, so the header is at a fixed offset from the tail.
+fn main_chain_index(main_reader: BytesVecReader, hash: &H256, number: u64) -> Result<Option<usize>, Error> {
+    if main_reader.is_empty() {
+        return Err(Error::InvalidCellData);
+    }
+    let main_tail_info_reader = parse_header_info(main_reader.get_unchecked(main_reader.len() - 1).raw_data())?;
+    let tail_number = HeaderView::new(main_tail_info_reader.header().raw_data()).and_then(|header| header.number()).map_err(|_| Error::InvalidCellData)?;
+    if number > tail_number || tail_number - number >= main_reader.len() as u64 {
+        return Ok(None);
+    }
+    let index = main_reader.len() - 1 - (tail_number - number) as usize;
+    let header_info_reader = parse_header_info(main_reader.get_unchecked(index).raw_data())?;
+    if BlockHash::from(header_info_reader.hash()) != BlockHash::from(hash) {
+        return Ok(None);
+    }
+    Ok(Some(index))
+}
+
+/// find a known header, either on the main chain or the uncle chain.
+fn find_known_header<'a>(
+    main_reader: BytesVecReader<'a>,
+    uncle_reader: HeaderInfoVecReader<'a>,
+    hash: &H256,
+    number: u64,
+) -> Result<Option<HeaderInfoReader<'a>>, Error> {
+    if let Ok(index) = search_uncle(uncle_reader, hash)? {
+        return Ok(Some(uncle_reader.get_unchecked(index)));
+    }
+    match main_chain_index(main_reader, hash, number)? {
+        Some(index) => Ok(Some(parse_header_info(main_reader.get_unchecked(index).raw_data())?)),
+        None => Ok(None),
+    }
+}
+
+fn pending_header(pending_raw: &[u8]) -> Result<&[u8], Error> {
+    if HeaderInfoReader::verify(pending_raw, false).is_err() {
+        return Err(Error::InvalidCellData);
+    }
+    Ok(HeaderInfoReader::new_unchecked(pending_raw).header().raw_data())
+}
+
+/// the proof of work of a parked header is only checked against its own difficulty, which must be close
+/// to the one of the main chain tail. otherwise cheap headers would evict the pending headers of the
+/// main chain from the pool.
+fn verify_pending_difficulty(difficulty: u64, tail_raw: &[u8]) -> Result<(), Error> {
+    let tail_difficulty = HeaderView::new(tail_raw)
+        .and_then(|tail| tail.difficulty_u64())
+        .map_err(|_| Error::InvalidCellData)?;
+    if difficulty < tail_difficulty / PENDING_MIN_DIFFICULTY_DIVISOR {
+        return Err(Error::PendingDifficultyTooLow);
+    }
+    Ok(())
+}
+
+/// append the header to the pending pool, the oldest one is evicted when the pool is full.
+fn verify_pending_parking(pending_input_reader: BytesVecReader, pending_output_reader: BytesVecReader, header_raw: &[u8]) -> Result<(), Error> {
+    for i in 0..pending_input_reader.len() {
+        if pending_header(pending_input_reader.get_unchecked(i).raw_data())? == header_raw {
             return Err(Error::InvalidCellData);
         }
-        let uncle_header_info_reader = HeaderInfoReader::new_unchecked(uncle_tail_input);
-        if uncle_header_info_reader.hash().raw_data() == current_hash.0.as_bytes() {
-            let uncle_header_raw = uncle_header_info_reader.header().raw_data();
-            let uncle_header: BlockHeader = rlp::decode(uncle_header_raw.to_vec().as_slice()).unwrap();
-            // TODO: make sure the header on uncle chain also exist on the main chain.
+    }
+    verify_original_chain_data(pending_input_reader, pending_output_reader, PENDING_HEADER_CACHE_LIMIT)?;
+    let pending_tail_raw = pending_output_reader.get_unchecked(pending_output_reader.len() - 1).raw_data();
+    let pending_tail_reader = parse_header_info(pending_tail_raw)?;
+    if pending_tail_reader.header().raw_data() != header_raw {
+        return Err(Error::InvalidCellData);
+    }
+    // the total difficulty is unknown until the header is promoted, and the header is stamped then.
+    if to_u64(&pending_tail_reader.total_difficulty().to_entity()) != 0 || to_u64(&pending_tail_reader.ckb_timestamp().to_entity()) != 0 {
+        return Err(Error::InvalidCellData);
+    }
+    Ok(())
+}
 
-            *number -= 1;
-            *current_hash = uncle_header.parent_hash;
-            break;
+/// remove the header from the pending pool, the other pending headers should be the same.
+fn verify_pending_promotion(pending_input_reader: BytesVecReader, pending_output_reader: BytesVecReader, header_raw: &[u8]) -> Result<(), Error> {
+    let mut found = false;
+    let mut input_data = vec![];
+    for i in 0..pending_input_reader.len() {
+        let pending_raw = pending_input_reader.get_unchecked(i).raw_data();
+        if !found && pending_header(pending_raw)? == header_raw {
+            found = true;
         } else {
-            index -= 1;
+            input_data.push(pending_raw);
         }
     }
+    if !found {
+        return Err(Error::InvalidCellData);
+    }
+    let mut output_data = vec![];
+    for i in 0..pending_output_reader.len() {
+        output_data.push(pending_output_reader.get_unchecked(i).raw_data());
+    }
+    if input_data != output_data {
+        return Err(Error::InvalidCellData);
+    }
     Ok(())
 }
 
-fn verify_original_chain_data(uncle_input_reader: BytesVecReader, uncle_output_reader: BytesVecReader, limit: usize) -> Result<(), Error> {
-    if uncle_input_reader.len() == uncle_output_reader.len() && uncle_output_reader.len() == limit {
-        let mut input_data = vec![];
-        for i in 1..uncle_input_reader.len() {
-            input_data.push(uncle_input_reader.get_unchecked(i).raw_data())
+/// parse a HeaderInfo from cell data, the stored hash must be the keccak256 of the stored header.
+fn parse_header_info(header_info_raw: &[u8]) -> Result<HeaderInfoReader, Error> {
+    if HeaderInfoReader::verify(header_info_raw, false).is_err() {
+        return Err(Error::InvalidCellData);
+    }
+    let header_info_reader = HeaderInfoReader::new_unchecked(header_info_raw);
+    verify_header_info_hash(&header_info_reader)?;
+    Ok(header_info_reader)
+}
+
+fn verify_header_info_hash(header_info_reader: &HeaderInfoReader) -> Result<(), Error> {
+    let hash = header_hash(header_info_reader.header().raw_data());
+    if BlockHash::from(header_info_reader.hash()) != BlockHash::from(&hash) {
+        return Err(Error::InvalidHeaderHash);
+    }
+    Ok(())
+}
+
+/// binary search the uncle chain, which is sorted by hash in ascending order.
+/// like `slice::binary_search`, returns `Ok(index)` if found, or `Err(index)` where the hash should be inserted.
+fn search_uncle(uncle_reader: HeaderInfoVecReader, hash: &H256) -> Result<Result<usize, usize>, Error> {
+    let mut low = 0;
+    let mut high = uncle_reader.len();
+    while low < high {
+        let mid = (low + high) / 2;
+        let uncle_header_info_reader = uncle_reader.get_unchecked(mid);
+        verify_header_info_hash(&uncle_header_info_reader)?;
+        match uncle_header_info_reader.hash().raw_data().cmp(hash.0.as_bytes()) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => return Ok(Ok(mid)),
+        }
+    }
+    Ok(Err(low))
+}
+
+/// the output uncle chain must be the input one with the new header inserted at its hash position.
+/// the stale uncles are evicted, then the uncle with the lowest block number when the cache is still full.
+fn verify_uncle_insertion(
+    uncle_input_reader: HeaderInfoVecReader,
+    uncle_output_reader: HeaderInfoVecReader,
+    hash: &H256,
+    header_raw: &[u8],
+    tail_number: u64,
+    limit: usize,
+    retention_depth: u64,
+) -> Result<(), Error> {
+    if search_uncle(uncle_input_reader, hash)?.is_ok() {
+        return Err(Error::InvalidCellData);
+    }
+    let mut expected = vec![];
+    for i in 0..uncle_input_reader.len() {
+        expected.push(uncle_input_reader.get_unchecked(i).as_slice());
+    }
+    evict_stale_uncles(&mut expected, tail_number, retention_depth)?;
+    // the uncles are still sorted by hash after the eviction.
+    let mut position = 0;
+    while position < expected.len() && HeaderInfoReader::new_unchecked(expected[position]).hash().raw_data() < hash.0.as_bytes() {
+        position += 1;
+    }
+    if expected.len() >= limit {
+        let evicted = find_oldest_uncle(&expected)?;
+        expected.remove(evicted);
+        if evicted < position {
+            position -= 1;
+        }
+    }
+    if uncle_output_reader.len() != expected.len() + 1 {
+        return Err(Error::InvalidCellData);
+    }
+    let new_uncle_reader = uncle_output_reader.get_unchecked(position);
+    verify_header_info_hash(&new_uncle_reader)?;
+    if new_uncle_reader.header().raw_data() != header_raw {
+        return Err(Error::InvalidCellData);
+    }
+    expected.insert(position, new_uncle_reader.as_slice());
+    for (i, uncle) in expected.iter().enumerate() {
+        if uncle_output_reader.get_unchecked(i).as_slice() != *uncle {
+            return Err(Error::InvalidCellData);
         }
-        let mut output_data = vec![];
-        for i in 0..uncle_output_reader.len()-1 {
-            output_data.push(uncle_output_reader.get_unchecked(i).raw_data())
+    }
+    Ok(())
+}
+
+fn is_stale_uncle(uncle: &[u8], tail_number: u64, retention_depth: u64) -> Result<bool, Error> {
+    let number = HeaderView::new(HeaderInfoReader::new_unchecked(uncle).header().raw_data()).and_then(|header| header.number()).map_err(|_| Error::InvalidCellData)?;
+    Ok(tail_number.saturating_sub(number) > retention_depth)
+}
+
+/// drop the uncles more than `retention_depth` blocks behind the main chain tail, keeping the order.
+fn evict_stale_uncles(uncles: &mut Vec<&[u8]>, tail_number: u64, retention_depth: u64) -> Result<(), Error> {
+    let mut kept = Vec::with_capacity(uncles.len());
+    for uncle in uncles.iter() {
+        if !is_stale_uncle(uncle, tail_number, retention_depth)? {
+            kept.push(*uncle);
         }
-        assert_eq!(input_data, output_data);
-    } else if uncle_input_reader.len() < uncle_output_reader.len(){
-        let mut input_data = vec![];
-        for i in 0..uncle_input_reader.len() {
-            input_data.push(uncle_input_reader.get_unchecked(i).raw_data())
+    }
+    *uncles = kept;
+    Ok(())
+}
+
+/// the output uncle chain must be the input one with some of the stale uncles removed, in the same order.
+fn verify_uncle_pruning(
+    uncle_input_reader: HeaderInfoVecReader,
+    uncle_output_reader: HeaderInfoVecReader,
+    tail_number: u64,
+    retention_depth: u64,
+) -> Result<(), Error> {
+    if uncle_input_reader.as_slice() == uncle_output_reader.as_slice() {
+        return Ok(());
+    }
+    let mut kept = 0;
+    for i in 0..uncle_input_reader.len() {
+        let uncle = uncle_input_reader.get_unchecked(i).as_slice();
+        if kept < uncle_output_reader.len() && uncle_output_reader.get_unchecked(kept).as_slice() == uncle {
+            kept += 1;
+        } else if !is_stale_uncle(uncle, tail_number, retention_depth)? {
+            return Err(Error::InvalidCellData);
         }
-        let mut output_data = vec![];
-        for i in 0..uncle_output_reader.len()-1 {
-            output_data.push(uncle_output_reader.get_unchecked(i).raw_data())
+    }
+    if kept != uncle_output_reader.len() {
+        return Err(Error::InvalidCellData);
+    }
+    Ok(())
+}
+
+fn find_oldest_uncle(uncles: &[&[u8]]) -> Result<usize, Error> {
+    let mut oldest_index = 0;
+    let mut oldest_number = u64::MAX;
+    for (i, uncle) in uncles.iter().enumerate() {
+        let number = HeaderView::new(HeaderInfoReader::new_unchecked(uncle).header().raw_data()).and_then(|header| header.number())
+            .map_err(|_| Error::InvalidCellData)?;
+        if number < oldest_number {
+            oldest_index = i;
+            oldest_number = number;
         }
-        assert_eq!(input_data, output_data);
+    }
+    Ok(oldest_index)
+}
+
+/// the output chain must be the input one with a header appended, the first one is dropped when
+/// the chain is full.
+fn verify_original_chain_data(input_reader: BytesVecReader, output_reader: BytesVecReader, limit: usize) -> Result<(), Error> {
+    if limit == 0 {
+        return Err(Error::InvalidConfig);
+    }
+    if output_reader.is_empty() || output_reader.len() > limit {
+        return Err(Error::InvalidCellData);
+    }
+    let dropped = if input_reader.len() == output_reader.len() && output_reader.len() == limit {
+        1
+    } else if input_reader.len() + 1 == output_reader.len() {
+        0
     } else {
         return Err(Error::InvalidCellData);
+    };
+    for i in dropped..input_reader.len() {
+        if input_reader.get_unchecked(i).raw_data() != output_reader.get_unchecked(i - dropped).raw_data() {
+            return Err(Error::InvalidCellData);
+        }
     }
     Ok(())
 }
 
+fn parse_config(config: &[u8]) -> Result<ClientConfigReader, Error> {
+    if ClientConfigReader::verify(config, false).is_err() {
+        return Err(Error::InvalidCellData);
+    }
+    Ok(ClientConfigReader::new_unchecked(config))
+}
+
 fn parse_proof(proof_raw: &[u8]) -> Result<DoubleNodeWithMerkleProof, Error> {
     if DoubleNodeWithMerkleProofReader::verify(&proof_raw, false).is_err() {
         return Err(Error::InvalidWitness);
     }
     let merkle_proof = DoubleNodeWithMerkleProofReader::new_unchecked(proof_raw);
+    // a pair of dag nodes.
+    if merkle_proof.dag_nodes().len() != 2 {
+        return Err(Error::InvalidWitness);
+    }
     let mut dag_nodes = vec![];
     for i in 0..merkle_proof.dag_nodes().len() {
+        let node_raw = merkle_proof.dag_nodes().get_unchecked(i).raw_data();
+        if node_raw.len() != 64 {
+            return Err(Error::InvalidWitness);
+        }
         let mut node = [0u8; 64];
-        node.copy_from_slice(merkle_proof.dag_nodes().get_unchecked(i).raw_data());
+        node.copy_from_slice(node_raw);
         dag_nodes.push(H512(node.into()));
     }
     let mut proofs = vec![];
     for i in 0..merkle_proof.proof().len() {
+        let proof_raw = merkle_proof.proof().get_unchecked(i).raw_data();
+        if proof_raw.len() != 16 {
+            return Err(Error::InvalidWitness);
+        }
         let mut proof = [0u8; 16];
-        proof.copy_from_slice(merkle_proof.proof().get_unchecked(i).raw_data());
+        proof.copy_from_slice(proof_raw);
         proofs.push(H128(proof.into()));
     }
     Ok(DoubleNodeWithMerkleProof::new(
@@ -258,21 +1266,120 @@ fn parse_proof(proof_raw: &[u8]) -> Result<DoubleNodeWithMerkleProof, Error> {
     ))
 }
 
-fn parse_dep_data(witness: WitnessReader, number: u64) -> Result<H128, Error> {
-    let cell_dep_index_list = witness.cell_dep_index_list().raw_data();
-    if cell_dep_index_list.len() != 1 {
-        return Err(Error::InvalidWitness);
+/// when the type script args pin the type hash of the dags merkle roots cells, the dep cell must have it.
+/// a zero hash pins nothing, it fills the args before the pow skip depth.
+fn verify_roots_cell_type(dep_index: usize) -> Result<(), Error> {
+    let args = load_script()?.args().raw_data();
+    let offset = UNIQUE_ID_LEN + MIN_UPDATE_INTERVAL_LEN;
+    if args.len() < offset + ROOTS_TYPE_HASH_LEN {
+        return Ok(());
+    }
+    let roots_type_hash = &args[offset..offset + ROOTS_TYPE_HASH_LEN];
+    if roots_type_hash == [0u8; ROOTS_TYPE_HASH_LEN] {
+        return Ok(());
+    }
+    match load_cell_type_hash(dep_index, Source::CellDep)? {
+        Some(type_hash) if type_hash[..] == roots_type_hash[..] => Ok(()),
+        _ => Err(Error::UntrustedRootsCell),
+    }
+}
+
+/// the timestamp in milliseconds of the first header dep of the tx, stored with every header the tx adds.
+/// the tx is committed after that block, so the header was not known by the client before it. 0 if the
+/// tx has no header deps.
+fn load_ckb_timestamp() -> Result<u64, Error> {
+    match load_header(0, Source::HeaderDep) {
+        Ok(header) => Ok(header.raw().timestamp().unpack()),
+        Err(SysError::IndexOutOfBound) => Ok(0),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// a new header info must carry the ckb timestamp of the tx which adds it, see `load_ckb_timestamp`.
+fn verify_ckb_timestamp(header_info_reader: &HeaderInfoReader, ckb_timestamp: u64) -> Result<(), Error> {
+    if to_u64(&header_info_reader.ckb_timestamp().to_entity()) != ckb_timestamp {
+        return Err(Error::InvalidCellData);
+    }
+    Ok(())
+}
+
+/// the pow skip depth of the type script args, 0 if there is none.
+fn load_pow_skip_depth() -> Result<u64, Error> {
+    let args = load_script()?.args().raw_data();
+    let offset = UNIQUE_ID_LEN + MIN_UPDATE_INTERVAL_LEN + ROOTS_TYPE_HASH_LEN;
+    if args.len() < offset + POW_SKIP_DEPTH_LEN {
+        return Ok(0);
     }
+    let mut depth = [0u8; POW_SKIP_DEPTH_LEN];
+    depth.copy_from_slice(&args[offset..offset + POW_SKIP_DEPTH_LEN]);
+    Ok(u64::from_le_bytes(depth))
+}
+
+/// `cell_dep_index_list` is the index of the dep cell, optionally followed by the index of the root in
+/// the dep cell as a u32 LE, so a relayer racing an update of the dep cell fails with `EpochRootMismatch`
+/// instead of being checked against another root.
+fn parse_dep_data(cell_dep_index_list: &[u8], number: u64) -> Result<H128, Error> {
+    let root_index = match cell_dep_index_list.len() {
+        1 => None,
+        5 => {
+            let mut root_index = [0u8; 4];
+            root_index.copy_from_slice(&cell_dep_index_list[1..]);
+            Some(u32::from_le_bytes(root_index) as usize)
+        }
+        _ => return Err(Error::InvalidWitness),
+    };
+    verify_roots_cell_type(cell_dep_index_list[0].into())?;
     let dep_data = load_cell_data(cell_dep_index_list[0].into(), Source::CellDep)?;
     // debug!("dep data is {:?}", &dep_data);
-    if DagsMerkleRootsReader::verify(&dep_data, false).is_err() {
+    let epoch = number / EPOCH_LENGTH;
+    let mut merkle_root = [0u8; 16];
+    if EpochMerkleRootsReader::verify(&dep_data, false).is_ok() {
+        let roots_reader = EpochMerkleRootsReader::new_unchecked(&dep_data).roots();
+        // the roots may be sharded across cells, the shard must cover the epoch.
+        if roots_reader.is_empty()
+            || epoch < to_u64(&roots_reader.get_unchecked(0).epoch().to_entity())
+            || epoch > to_u64(&roots_reader.get_unchecked(roots_reader.len() - 1).epoch().to_entity())
+        {
+            return Err(Error::EpochRootMismatch);
+        }
+        if let Some(root_index) = root_index {
+            let root_reader = roots_reader.get(root_index).ok_or(Error::DagsMerkleRootNotFound)?;
+            if to_u64(&root_reader.epoch().to_entity()) != epoch {
+                return Err(Error::EpochRootMismatch);
+            }
+            merkle_root.copy_from_slice(root_reader.root().raw_data());
+            return Ok(H128(merkle_root.into()));
+        }
+        // binary search, the roots are sorted by epoch.
+        let mut low = 0;
+        let mut high = roots_reader.len();
+        while low < high {
+            let mid = (low + high) / 2;
+            let root_reader = roots_reader.get_unchecked(mid);
+            match to_u64(&root_reader.epoch().to_entity()).cmp(&epoch) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => {
+                    merkle_root.copy_from_slice(root_reader.root().raw_data());
+                    return Ok(H128(merkle_root.into()));
+                }
+            }
+        }
+        return Err(Error::DagsMerkleRootNotFound);
+    } else if DagsMerkleRootsReader::verify(&dep_data, false).is_ok() {
+        // the roots start from epoch 0, so the index of the root is the epoch.
+        if root_index.map_or(false, |root_index| root_index as u64 != epoch) {
+            return Err(Error::EpochRootMismatch);
+        }
+        let dags_reader = DagsMerkleRootsReader::new_unchecked(&dep_data);
+        let merkle_root_tmp = dags_reader.dags_merkle_roots().get(epoch as usize).ok_or(Error::DagsMerkleRootNotFound)?.raw_data();
+        if merkle_root_tmp.len() != 16 {
+            return Err(Error::DagsMerkleRootsDataInvalid);
+        }
+        merkle_root.copy_from_slice(merkle_root_tmp);
+    } else {
         return Err(Error::DagsMerkleRootsDataInvalid);
     }
-    let dags_reader = DagsMerkleRootsReader::new_unchecked(&dep_data);
-    let idx: usize = (number / 30000) as usize;
-    let merkle_root_tmp = dags_reader.dags_merkle_roots().get_unchecked(idx).raw_data();
-    let mut merkle_root = [0u8; 16];
-    merkle_root.copy_from_slice(merkle_root_tmp);
     Ok(H128(merkle_root.into()))
 }
 
@@ -287,8 +1394,8 @@ fn get_data(source: Source) -> Result<Option<CellDataView>, Error> {
     }
 }
 
-fn to_u64(data: &Uint64) -> u64 {
-    let mut res = [0u8; 8];
+fn to_u32(data: &Uint32) -> u32 {
+    let mut res = [0u8; 4];
     res.copy_from_slice(data.as_slice());
-    u64::from_be_bytes(res)
+    u32::from_le_bytes(res)
 }
@@ -4,34 +4,52 @@
 //! See `entry.rs` for the `main` function.
 //! See `error.rs` for the `Error` type.
 
-#![no_std]
-#![no_main]
-#![feature(lang_items)]
-#![feature(alloc_error_handler)]
-#![feature(panic_info_message)]
+#![cfg_attr(not(feature = "simulator"), no_std)]
+#![cfg_attr(not(feature = "simulator"), no_main)]
+#![cfg_attr(not(feature = "simulator"), feature(lang_items))]
+#![cfg_attr(not(feature = "simulator"), feature(alloc_error_handler))]
+#![cfg_attr(not(feature = "simulator"), feature(panic_info_message))]
 #![allow(non_snake_case)]
 
+#[cfg(feature = "simulator")]
+extern crate alloc;
+
 // define modules
+#[macro_use]
+mod log;
 mod logic;
-mod types;
 mod helper;
+use eth_client_reader::types;
 use types::Error;
 
 
+#[cfg(not(feature = "simulator"))]
 use ckb_std::default_alloc;
 
+#[cfg(not(feature = "simulator"))]
 ckb_std::entry!(program_entry);
+#[cfg(not(feature = "simulator"))]
 default_alloc!();
 
+/// run the contract natively, panics are reported with backtraces by std.
+#[cfg(feature = "simulator")]
+fn main() {
+    std::process::exit(program_entry() as i32);
+}
+
 /// program entry
 fn program_entry() -> i8 {
     // Call main function and return error code
-    match main() {
+    match run() {
         Ok(_) => 0,
-        Err(err) => err as i8,
+        Err(err) => {
+            let code = err as i8;
+            error!("verification failed with error code {}", code);
+            code
+        }
     }
 }
 
-fn main() -> Result<(), Error> {
+fn run() -> Result<(), Error> {
     logic::verify()
 }
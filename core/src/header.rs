@@ -0,0 +1,241 @@
+//! Decoding a witness header and the bounds of its timestamp and gas.
+
+use eth_spv_lib::eth_types::*;
+use rlp::{DecoderError, Rlp, RlpStream};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The header is not a rlp encoded ethereum header.
+    Undecodable,
+    /// The header decodes, but re-encodes to other bytes.
+    NonCanonical,
+}
+
+/// The block hash, the keccak256 of the rlp encoded header.
+pub fn header_hash(header_raw: &[u8]) -> H256 {
+    H256(my_keccak256(header_raw).into())
+}
+
+/// A view over a rlp encoded header, to read a few fields without decoding the whole `BlockHeader`.
+/// The fields are borrowed from the encoded header.
+pub struct HeaderView<'a> {
+    rlp: Rlp<'a>,
+}
+
+impl<'a> HeaderView<'a> {
+    pub fn new(header_raw: &'a [u8]) -> Result<HeaderView<'a>, DecoderError> {
+        let rlp = Rlp::new(header_raw);
+        if !rlp.is_list() {
+            return Err(DecoderError::RlpExpectedToBeList);
+        }
+        // Trailing bytes would give the same fields another hash.
+        if rlp.payload_info()?.total() != header_raw.len() {
+            return Err(DecoderError::RlpInconsistentLengthAndData);
+        }
+        Ok(HeaderView { rlp })
+    }
+
+    fn field(&self, index: usize) -> Result<&'a [u8], DecoderError> {
+        self.rlp.at(index)?.data()
+    }
+
+    fn fixed_field(&self, index: usize, len: usize) -> Result<&'a [u8], DecoderError> {
+        let data = self.field(index)?;
+        if data.len() != len {
+            return Err(DecoderError::RlpInvalidLength);
+        }
+        Ok(data)
+    }
+
+    pub fn parent_hash(&self) -> Result<&'a [u8], DecoderError> {
+        self.fixed_field(0, 32)
+    }
+
+    pub fn uncles_hash(&self) -> Result<&'a [u8], DecoderError> {
+        self.fixed_field(1, 32)
+    }
+
+    pub fn author(&self) -> Result<&'a [u8], DecoderError> {
+        self.fixed_field(2, 20)
+    }
+
+    pub fn state_root(&self) -> Result<&'a [u8], DecoderError> {
+        self.fixed_field(3, 32)
+    }
+
+    pub fn transactions_root(&self) -> Result<&'a [u8], DecoderError> {
+        self.fixed_field(4, 32)
+    }
+
+    pub fn receipts_root(&self) -> Result<&'a [u8], DecoderError> {
+        self.fixed_field(5, 32)
+    }
+
+    pub fn logs_bloom(&self) -> Result<&'a [u8], DecoderError> {
+        self.fixed_field(6, 256)
+    }
+
+    /// Big endian without leading zeros, like all the rlp encoded numbers.
+    pub fn difficulty(&self) -> Result<&'a [u8], DecoderError> {
+        self.field(7)
+    }
+
+    /// `RlpIsTooBig` if the difficulty doesn't fit in a u64.
+    pub fn difficulty_u64(&self) -> Result<u64, DecoderError> {
+        self.rlp.val_at(7)
+    }
+
+    pub fn number(&self) -> Result<u64, DecoderError> {
+        self.rlp.val_at(8)
+    }
+
+    pub fn gas_limit(&self) -> Result<&'a [u8], DecoderError> {
+        self.field(9)
+    }
+
+    pub fn gas_used(&self) -> Result<&'a [u8], DecoderError> {
+        self.field(10)
+    }
+
+    /// `RlpIsTooBig` if the gas limit doesn't fit in a u64.
+    pub fn gas_limit_u64(&self) -> Result<u64, DecoderError> {
+        self.rlp.val_at(9)
+    }
+
+    /// `RlpIsTooBig` if the gas used doesn't fit in a u64.
+    pub fn gas_used_u64(&self) -> Result<u64, DecoderError> {
+        self.rlp.val_at(10)
+    }
+
+    pub fn timestamp(&self) -> Result<u64, DecoderError> {
+        self.rlp.val_at(11)
+    }
+
+    pub fn extra_data(&self) -> Result<&'a [u8], DecoderError> {
+        self.field(12)
+    }
+
+    pub fn mix_hash(&self) -> Result<&'a [u8], DecoderError> {
+        self.fixed_field(13, 32)
+    }
+
+    pub fn nonce(&self) -> Result<&'a [u8], DecoderError> {
+        self.fixed_field(14, 8)
+    }
+}
+
+/// The ommers hash of a header is the keccak256 of the rlp list of its ommer headers. Every ommer must
+/// be exactly one rlp encoded header, otherwise a list could be split into other ommers with the same hash.
+pub fn ommers_hash(ommers: &[&[u8]]) -> Result<H256, DecoderError> {
+    let mut stream = RlpStream::new_list(ommers.len());
+    for ommer in ommers {
+        let ommer_rlp = Rlp::new(ommer);
+        if !ommer_rlp.is_list() || ommer_rlp.payload_info()?.total() != ommer.len() {
+            return Err(DecoderError::RlpInconsistentLengthAndData);
+        }
+        stream.append_raw(ommer, 1);
+    }
+    Ok(H256(my_keccak256(&stream.out()).into()))
+}
+
+/// The number of fields of a header before London.
+pub const HEADER_ITEMS: usize = 15;
+/// London appends the base fee of eip-1559, the last field the proof of work chain added.
+pub const LONDON_HEADER_ITEMS: usize = 16;
+
+/// Whether the encoded header carries the base fee of London.
+pub fn is_london(header_raw: &[u8]) -> bool {
+    Rlp::new(header_raw).item_count() == Ok(LONDON_HEADER_ITEMS)
+}
+
+/// Decode a witness header. The hash is the hash of the raw header, so the encoding must be canonical,
+/// e.g. trailing bytes would give the same fields another hash.
+///
+/// `BlockHeader` has no base fee, so a London header is checked field by field: the fields before
+/// London re-encode to the same items, and the only other item is the base fee. Its seal hash covers
+/// the base fee too, so the partial hash of the proof of work is computed here.
+pub fn decode_header(header_raw: &[u8]) -> Result<BlockHeader, DecodeError> {
+    let rlp = Rlp::new(header_raw);
+    let mut header: BlockHeader = rlp.as_val().map_err(|_| DecodeError::Undecodable)?;
+    let base_fee: Option<U256> = match rlp.item_count().map_err(|_| DecodeError::Undecodable)? {
+        HEADER_ITEMS => None,
+        LONDON_HEADER_ITEMS => Some(rlp.val_at(HEADER_ITEMS).map_err(|_| DecodeError::Undecodable)?),
+        _ => return Err(DecodeError::NonCanonical),
+    };
+    let encoded = rlp::encode(&header);
+    let mut stream = RlpStream::new_list(if base_fee.is_some() { LONDON_HEADER_ITEMS } else { HEADER_ITEMS });
+    for item in Rlp::new(&encoded).iter() {
+        stream.append_raw(item.as_raw(), 1);
+    }
+    if let Some(base_fee) = &base_fee {
+        stream.append(base_fee);
+    }
+    if stream.out()[..] != header_raw[..] {
+        return Err(DecodeError::NonCanonical);
+    }
+    if let Some(base_fee) = &base_fee {
+        // the header without the mix hash and the nonce.
+        let mut stream = RlpStream::new_list(HEADER_ITEMS - 1);
+        for item in rlp.iter().take(HEADER_ITEMS - 2) {
+            stream.append_raw(item.as_raw(), 1);
+        }
+        stream.append(base_fee);
+        header.partial_hash = Some(H256(my_keccak256(&stream.out()).into()));
+    }
+    Ok(header)
+}
+
+/// Bound the timestamps, so a fork can't be built with timestamps far in the future. A header must be
+/// newer than its parent if the parent is known, and not too far ahead of the main chain tail.
+pub fn timestamp_in_bounds(
+    timestamp: u64,
+    parent_timestamp: Option<u64>,
+    tail_timestamp: u64,
+    max_timestamp_gap: u64,
+    max_future_drift: u64,
+) -> bool {
+    if timestamp > tail_timestamp.saturating_add(max_future_drift) {
+        return false;
+    }
+    match parent_timestamp {
+        Some(parent_timestamp) => timestamp > parent_timestamp && timestamp - parent_timestamp <= max_timestamp_gap,
+        None => true,
+    }
+}
+
+/// The min gas limit of a header.
+pub const MIN_GAS_LIMIT: u64 = 5000;
+/// A gas limit differs from the one of its parent by less than the parent gas limit over this.
+pub const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+/// London targets the gas limit over this, the gas used can reach the whole limit.
+pub const ELASTICITY_MULTIPLIER: u64 = 2;
+
+/// Bound the gas fields. The gas used must fit in the gas limit, which is at least `MIN_GAS_LIMIT`
+/// and close to the gas limit of the parent if the parent is known.
+///
+/// A London header targets half of its gas limit for the elasticity of eip-1559. At the fork block,
+/// the first header with a base fee, the parent targeted its whole gas limit, so the gas limit is
+/// bounded by the parent gas limit times `ELASTICITY_MULTIPLIER`, as in `london_fork_block`.
+pub fn gas_in_bounds(gas_limit: u64, gas_used: u64, parent_gas_limit: Option<u64>, london_fork_block: bool) -> bool {
+    if gas_used > gas_limit || gas_limit < MIN_GAS_LIMIT {
+        return false;
+    }
+    match parent_gas_limit {
+        Some(parent_gas_limit) => {
+            let parent_gas_limit = if london_fork_block {
+                parent_gas_limit.saturating_mul(ELASTICITY_MULTIPLIER)
+            } else {
+                parent_gas_limit
+            };
+            // `abs_diff` is newer than the toolchain of the contract.
+            let diff = if gas_limit > parent_gas_limit { gas_limit - parent_gas_limit } else { parent_gas_limit - gas_limit };
+            diff < parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR
+        }
+        None => true,
+    }
+}
+
+/// Whether the header is the London fork block, the first one with a base fee.
+pub fn london_fork_block(header_raw: &[u8], parent_raw: &[u8]) -> bool {
+    is_london(header_raw) && !is_london(parent_raw)
+}
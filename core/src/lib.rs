@@ -0,0 +1,14 @@
+//! The header verification rules of the eth-client contract, without the CKB cell plumbing.
+//!
+//! The contract verifies the headers of its witnesses with this crate, so off-chain consumers like
+//! wallets and auditors can check a chain segment with the exact on-chain rules, see `verify_segment`.
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod header;
+pub mod pow;
+pub mod segment;
+
+pub use segment::{verify_segment, SegmentConfig, SegmentError, TrustedAnchor, VerifiedSegment};
@@ -0,0 +1,146 @@
+//! Verify a segment of headers extending a trusted header, with the rules of a batch update of the
+//! client cell.
+
+use crate::header::{decode_header, gas_in_bounds, header_hash, london_fork_block, timestamp_in_bounds, DecodeError};
+use crate::pow::{verify_header, DoubleNodeWithMerkleProof};
+use alloc::vec::Vec;
+use core::fmt;
+use eth_client_constants::{EPOCH_LENGTH, MAX_HEADER_SIZE, MAX_MERKLE_PROOFS};
+use eth_spv_lib::eth_types::*;
+
+/// A header the segment extends, e.g. the main chain tail of a client cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedAnchor {
+    pub hash: H256,
+    pub number: u64,
+    pub timestamp: u64,
+    pub total_difficulty: u64,
+}
+
+/// The bounds of the client config, and the dags merkle roots the proofs are checked against.
+#[derive(Debug, Clone)]
+pub struct SegmentConfig<'a> {
+    pub max_timestamp_gap: u64,
+    pub max_future_drift: u64,
+    /// The roots of the contiguous epochs from epoch 0.
+    pub dag_merkle_roots: &'a [H128],
+}
+
+/// The last header of a verified segment, and the hashes of all its headers in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedSegment {
+    pub tail: TrustedAnchor,
+    pub hashes: Vec<H256>,
+}
+
+/// Why the segment was rejected, with the index of the offending header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentError {
+    /// There is not one list of proofs for each header.
+    ProofsMissing,
+    HeaderTooLarge(usize),
+    HeaderDecodeFailed(usize),
+    NonCanonicalHeader(usize),
+    /// The header is not the child of the previous header, or of the anchor.
+    NotChild(usize),
+    InvalidTimestamp(usize),
+    /// The gas used exceeds the gas limit, or the gas limit is out of the bounds of its parent.
+    InvalidGasLimit(usize),
+    /// The difficulty or the total difficulty exceeds u64.
+    DifficultyOverflow(usize),
+    InvalidMerkleProofCount(usize),
+    DagsMerkleRootNotFound(usize),
+    InvalidProofOfWork(usize),
+}
+
+impl fmt::Display for SegmentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SegmentError::ProofsMissing => write!(f, "the headers and the proofs differ in length"),
+            SegmentError::HeaderTooLarge(i) => write!(f, "header {} exceeds {} bytes", i, MAX_HEADER_SIZE),
+            SegmentError::HeaderDecodeFailed(i) => write!(f, "header {} is not a rlp encoded header", i),
+            SegmentError::NonCanonicalHeader(i) => write!(f, "header {} is not canonically rlp encoded", i),
+            SegmentError::NotChild(i) => write!(f, "header {} is not the child of the previous header", i),
+            SegmentError::InvalidTimestamp(i) => write!(f, "the timestamp of header {} is out of bounds", i),
+            SegmentError::InvalidGasLimit(i) => write!(f, "the gas of header {} is out of bounds", i),
+            SegmentError::DifficultyOverflow(i) => write!(f, "the difficulty of header {} overflows u64", i),
+            SegmentError::InvalidMerkleProofCount(i) => {
+                write!(f, "header {} does not have {} merkle proofs", i, MAX_MERKLE_PROOFS)
+            }
+            SegmentError::DagsMerkleRootNotFound(i) => write!(f, "no dags merkle root for the epoch of header {}", i),
+            SegmentError::InvalidProofOfWork(i) => write!(f, "the proof of work of header {} is invalid", i),
+        }
+    }
+}
+
+/// Verify that `headers` extend `anchor` one by one, like the headers of a batch update of the client
+/// cell. Every header carries its proofs, `proofs[i]` are the proofs of `headers[i]`.
+pub fn verify_segment(
+    anchor: &TrustedAnchor,
+    headers: &[&[u8]],
+    proofs: &[Vec<DoubleNodeWithMerkleProof>],
+    config: &SegmentConfig,
+) -> Result<VerifiedSegment, SegmentError> {
+    if headers.len() != proofs.len() {
+        return Err(SegmentError::ProofsMissing);
+    }
+    let mut tail = anchor.clone();
+    let mut hashes = Vec::with_capacity(headers.len());
+    // the raw header and the gas limit of the parent, the ones of the anchor are not known.
+    let mut parent: Option<(&[u8], u64)> = None;
+    for (i, (header_raw, proofs)) in headers.iter().zip(proofs).enumerate() {
+        if header_raw.len() > MAX_HEADER_SIZE {
+            return Err(SegmentError::HeaderTooLarge(i));
+        }
+        let header = decode_header(header_raw).map_err(|err| match err {
+            DecodeError::Undecodable => SegmentError::HeaderDecodeFailed(i),
+            DecodeError::NonCanonical => SegmentError::NonCanonicalHeader(i),
+        })?;
+        if header.parent_hash != tail.hash || tail.number.checked_add(1) != Some(header.number) {
+            return Err(SegmentError::NotChild(i));
+        }
+        // the parent is the tail when the header is appended.
+        if !timestamp_in_bounds(
+            header.timestamp,
+            Some(tail.timestamp),
+            tail.timestamp,
+            config.max_timestamp_gap,
+            config.max_future_drift,
+        ) {
+            return Err(SegmentError::InvalidTimestamp(i));
+        }
+        if header.gas_limit.0 > u64::MAX.into() || header.gas_used.0 > u64::MAX.into() {
+            return Err(SegmentError::InvalidGasLimit(i));
+        }
+        let gas_limit = header.gas_limit.0.as_u64();
+        let fork_block = match parent {
+            Some((parent_raw, _)) => london_fork_block(header_raw, parent_raw),
+            None => false,
+        };
+        if !gas_in_bounds(gas_limit, header.gas_used.0.as_u64(), parent.map(|(_, gas_limit)| gas_limit), fork_block) {
+            return Err(SegmentError::InvalidGasLimit(i));
+        }
+        if header.difficulty.0 > u64::MAX.into() {
+            return Err(SegmentError::DifficultyOverflow(i));
+        }
+        let total_difficulty = tail
+            .total_difficulty
+            .checked_add(header.difficulty.0.as_u64())
+            .ok_or(SegmentError::DifficultyOverflow(i))?;
+        if proofs.len() != MAX_MERKLE_PROOFS {
+            return Err(SegmentError::InvalidMerkleProofCount(i));
+        }
+        let merkle_root = config
+            .dag_merkle_roots
+            .get((header.number / EPOCH_LENGTH) as usize)
+            .ok_or(SegmentError::DagsMerkleRootNotFound(i))?;
+        if !verify_header(&header, None, *merkle_root, proofs) {
+            return Err(SegmentError::InvalidProofOfWork(i));
+        }
+        let hash = header_hash(header_raw);
+        hashes.push(hash);
+        parent = Some((header_raw, gas_limit));
+        tail = TrustedAnchor { hash, number: header.number, timestamp: header.timestamp, total_difficulty };
+    }
+    Ok(VerifiedSegment { tail, hashes })
+}
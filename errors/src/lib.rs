@@ -0,0 +1,114 @@
+//! The exit codes of the eth-client contract, for diagnosing rejected transactions.
+//!
+//! A transaction rejected by the contract fails with `ValidationFailure(<code>)`, where the
+//! code is the `Error` of the contract. The table must follow `reader/src/types/error.rs`,
+//! new errors are appended there and here. `test_error_codes` of the tests compares the two.
+
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub struct ErrorCode {
+    pub code: i8,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+const fn error(code: i8, name: &'static str, description: &'static str) -> ErrorCode {
+    ErrorCode { code, name, description }
+}
+
+pub const ERRORS: &[ErrorCode] = &[
+    error(1, "IndexOutOfBound", "a cell, cell dep or witness index is out of bound"),
+    error(2, "ItemMissing", "a loaded item is missing, e.g. the client cell output on creation"),
+    error(3, "LengthNotEnough", "a syscall buffer is too short"),
+    error(4, "Encoding", "the client cell data or a witness args is not valid molecule"),
+    error(5, "TxInvalid", "the tx has more than one client cell input or output, or only an input"),
+    error(6, "InvalidWitness", "the witness is missing, can not be decoded or does not extend the client"),
+    error(7, "InvalidDataChange", "a field of the client cell data changed which the tx can not change"),
+    error(8, "InvalidCellData", "the output headers are not the input headers with the witness applied"),
+    error(9, "DagsMerkleRootsDataInvalid", "the dags merkle roots cell dep is malformed"),
+    error(10, "InvalidMerkleProofData", "the ethash proof does not match the dags merkle root"),
+    error(11, "InvalidTypeArgs", "the type script args are not the unique id of the creating tx"),
+    error(12, "ClientPaused", "the client is paused by the governance"),
+    error(13, "GovernanceLockMissing", "the governance lock is not in the inputs"),
+    error(14, "InvalidHeaderHash", "the header hash does not match the header"),
+    error(15, "InvalidRelayer", "the relayer of the slot is not in the inputs"),
+    error(16, "InvalidConfig", "the client config is invalid"),
+    error(17, "HeaderNotFound", "the header is not on the main chain of the client"),
+    error(18, "InvalidClientCell", "the cell dep is not a client cell"),
+    error(19, "InvalidReceiptProof", "the receipt proof does not match the receipts root"),
+    error(20, "LogNotFound", "the receipt has no log matching the filter"),
+    error(21, "InvalidLogData", "the log data does not match"),
+    error(22, "StaleWitness", "the witness is built on another main chain tail"),
+    error(23, "InvalidTimestamp", "the header timestamp is out of the allowed range"),
+    error(24, "UnsupportedWitness", "the v2 witness uses a reserved field"),
+    error(25, "InvalidDigest", "the digest does not describe the main chain tail"),
+    error(26, "DagsMerkleRootNotFound", "the dags merkle roots cell has no root for the epoch of the header"),
+    error(27, "EventDefinitelyAbsent", "the logs bloom of the header excludes the event"),
+    error(28, "HeaderTooLarge", "the header exceeds the size limit"),
+    error(29, "TooManyMerkleProofs", "the witness has more merkle proofs than ethash reads"),
+    error(30, "BatchTooLong", "the batch has more headers than the limit"),
+    error(31, "HeaderNotFinal", "the header is not buried under enough difficulty"),
+    error(32, "UpdateTooFrequent", "the client cell is younger than the min update interval"),
+    error(33, "UncleTooOld", "the uncle is too far behind the main chain tail"),
+    error(34, "EpochRootMismatch", "the dags merkle root is not the root of the epoch of the header"),
+    error(35, "UntrustedRootsCell", "the dags merkle roots cell does not have the pinned type"),
+    error(36, "ReorgTooDeep", "the reorg replaces more main chain headers than the limit"),
+    error(37, "UserLockMissing", "the current user lockscript is not in the inputs"),
+    error(38, "HeaderAlreadyExists", "the header is already on the main or the uncle chain"),
+    error(39, "NonCanonicalHeader", "the header is not canonically rlp encoded"),
+    error(40, "InvalidUpdateSequence", "the update sequence is not the input one plus one, or not 0 on creation"),
+    error(41, "InvalidOmmers", "the ommer headers do not match the ommers hash of the block"),
+    error(42, "InvalidMerkleProofCount", "the witness does not have exactly one merkle proof for each of the 64 ethash accesses"),
+    error(43, "UnsupportedReceiptType", "the receipt is neither a legacy receipt nor of an eip-2930 or eip-1559 transaction"),
+    error(44, "CapacityDecreased", "the client cell capacity decreased without the user lockscript in the inputs"),
+    error(45, "InvalidChainEvent", "the output_type witness is not the chain event of the update"),
+    error(46, "HeaderDecodeFailed", "the witness header is not a rlp encoded ethereum header"),
+    error(47, "InvalidGasLimit", "the header gas used or gas limit is out of the allowed range"),
+    error(48, "PendingDifficultyTooLow", "the header with an unknown parent has less than half the difficulty of the main chain tail"),
+    error(49, "BuriedDifficultyTooHigh", "a batch header without proofs claims more difficulty than the next header with proofs"),
+];
+
+/// The error of the exit code, `None` for the codes of other scripts or of a newer contract.
+pub fn lookup(code: i8) -> Option<&'static ErrorCode> {
+    ERRORS.iter().find(|error| error.code == code)
+}
+
+/// The exit code in the message of a rejected tx, e.g. `Script(ValidationFailure(22))`.
+pub fn validation_failure(message: &str) -> Option<i8> {
+    const PREFIX: &str = "ValidationFailure(";
+    let start = message.find(PREFIX)? + PREFIX.len();
+    let end = start + message[start..].find(')')?;
+    message[start..end].trim().parse().ok()
+}
+
+/// A rejection by the contract, with the main chain tail of the input client cell if known.
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub code: i8,
+    pub error: Option<&'static ErrorCode>,
+    pub tail_number: Option<u64>,
+}
+
+/// Diagnose the message of a rejected tx, `None` if it is not a script validation failure.
+pub fn diagnose(message: &str, tail_number: Option<u64>) -> Option<Diagnostic> {
+    let code = validation_failure(message)?;
+    Some(Diagnostic { code, error: lookup(code), tail_number })
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.error {
+            Some(error) => write!(f, "{}", error.name)?,
+            None => write!(f, "unknown error code {}", self.code)?,
+        }
+        if let Some(tail_number) = self.tail_number {
+            write!(f, " at tail {}", tail_number)?;
+        }
+        if let Some(error) = self.error {
+            write!(f, ": {} (error code {})", error.description, error.code)?;
+        }
+        Ok(())
+    }
+}
+
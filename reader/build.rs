@@ -0,0 +1,33 @@
+//! regenerate the molecule bindings from the schemas when the `codegen` feature is enabled.
+//! the generated code is checked in, so building the contract does not require `moleculec`.
+use std::{env, fs, process::Command};
+
+const SCHEMA_DIR: &str = "src/types/schemas";
+const GENERATED_DIR: &str = "src/types/generated";
+const SCHEMAS: &[&str] = &["basic", "cell_data", "dags_merkle_roots", "double_node_with_merkle_proof", "witness"];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    for schema in SCHEMAS {
+        println!("cargo:rerun-if-changed={}/{}.mol", SCHEMA_DIR, schema);
+    }
+    if env::var_os("CARGO_FEATURE_CODEGEN").is_none() {
+        return;
+    }
+    for schema in SCHEMAS {
+        let output = Command::new("moleculec")
+            .current_dir(SCHEMA_DIR)
+            .args(&["--language", "rust", "--schema-file", &format!("{}.mol", schema)])
+            .output()
+            .expect("run moleculec");
+        assert!(output.status.success(), "moleculec {}: {}", schema, String::from_utf8_lossy(&output.stderr));
+        let code = String::from_utf8(output.stdout).expect("moleculec output");
+        // the contract and the readers do not use all the generated types.
+        let code = code.replacen("\n\n", "\n#![allow(dead_code)]\n", 1);
+        let path = format!("{}/{}.rs", GENERATED_DIR, schema);
+        // only write on change, otherwise the contract is rebuilt every time.
+        if fs::read_to_string(&path).ok().as_deref() != Some(code.as_str()) {
+            fs::write(&path, code).expect("write generated code");
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! the interface of a force-bridge style mint type script. a lock in the locker contract on
+//! ethereum, proven by a receipt proof against a client cell in the cell deps, is turned into the
+//! token, the amount and the recipient to mint for.
+//!
+//! ``` ignore
+//! // the locker address and the client type hash are in the args of the mint script.
+//! let lock = verify_lock(&client_type_hash, client_dep_index, &receipt_proof, &locker)?;
+//! if lock.depth < CONFIRMATIONS {
+//!     return Err(Error::HeaderNotFinal);
+//! }
+//! // mint `lock.amount` of the sudt of `lock.token` to the lockscript `lock.recipient`.
+//! ```
+
+use crate::types::{Error, CellDataView};
+use crate::proven_log;
+#[cfg(feature = "ckb-std")]
+use crate::load::load_client_data;
+use crate::receipt::{abi_bytes, event_topic, LockEvent, LogFilter};
+use alloc::vec::Vec;
+use eth_spv_lib::eth_types::*;
+
+/// `event Locked(address indexed token, address indexed sender, uint256 lockedAmount, bytes recipientLockscript)`
+/// of the locker contract, the recipient is the molecule `Script` to mint the tokens to.
+pub const LOCKED_EVENT: &str = "Locked(address,address,uint256,bytes)";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BridgeLock {
+    pub token: Address,
+    pub amount: U256,
+    pub recipient: Vec<u8>,
+    /// the depth of the block of the lock on the main chain of the client, 0 for the tail.
+    pub depth: u64,
+}
+
+/// load the client cell like `verify_log`, the log in the receipt proof must be a `Locked` event
+/// emitted by `locker`.
+#[cfg(feature = "ckb-std")]
+pub fn verify_lock(client_type_hash: &[u8; 32], dep_index: usize, proof_raw: &[u8], locker: &Address) -> Result<BridgeLock, Error> {
+    let data = load_client_data(client_type_hash, dep_index)?;
+    proven_lock(&data, proof_raw, locker)
+}
+
+/// the lock in the receipt proof, like `verify_lock` with the client cell data loaded by the caller.
+pub fn proven_lock(data: &CellDataView, proof_raw: &[u8], locker: &Address) -> Result<BridgeLock, Error> {
+    let signature = event_topic(LOCKED_EVENT);
+    let topics = [signature];
+    let filter = LogFilter { address: locker, topics: &topics };
+    let (depth, log) = proven_log(data, proof_raw, &filter)?;
+    let lock = LockEvent::decode(&log, &signature)?;
+    Ok(BridgeLock {
+        token: lock.token,
+        amount: lock.amount,
+        recipient: abi_bytes(&log.data, 1)?.to_vec(),
+        depth,
+    })
+}
@@ -0,0 +1,119 @@
+//! read the client cell data, e.g. the depth of a header on the main chain of the client.
+
+use crate::types::{Error, BlockHash, CellDataView, to_u64, basic::{BytesVecReader, ChainReader, HeaderInfoReader}, witness::ReceiptProofReader};
+use crate::receipt::{find_log, verify_receipt_proof, LogFilter};
+use eth_client_core::header::HeaderView;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use eth_spv_lib::eth_types::*;
+use molecule::prelude::Reader;
+
+/// the depth of the header on the main chain of the client, 0 for the tail.
+pub fn ancestor_depth(data: &CellDataView, hash: &[u8], number: u64) -> Result<u64, Error> {
+    let (depth, _) = locate_header(&data.headers, hash, number)?;
+    Ok(depth)
+}
+
+/// the main chain is continuous, so the header is located by its number and checked by its hash.
+pub(crate) fn locate_header<'a>(headers: &'a [u8], hash: &[u8], number: u64) -> Result<(u64, HeaderInfoReader<'a>), Error> {
+    let (main_reader, tail_number) = main_chain(headers)?;
+    let header_info_reader = main_header(main_reader, tail_number, number)?.ok_or(Error::HeaderNotFound)?;
+    if !BlockHash::from(header_info_reader.hash()).matches(hash) {
+        return Err(Error::HeaderNotFound);
+    }
+    Ok((tail_number - number, header_info_reader))
+}
+
+/// the main chain of the client, and the block number of its tail.
+fn main_chain(headers: &[u8]) -> Result<(BytesVecReader<'_>, u64), Error> {
+    if ChainReader::verify(headers, false).is_err() {
+        return Err(Error::InvalidCellData);
+    }
+    let main_reader = ChainReader::new_unchecked(headers).main();
+    if main_reader.is_empty() {
+        return Err(Error::InvalidCellData);
+    }
+    let tail_info_reader = header_info(main_reader.get_unchecked(main_reader.len() - 1).raw_data())?;
+    let tail_number = HeaderView::new(tail_info_reader.header().raw_data()).and_then(|header| header.number()).map_err(|_| Error::InvalidCellData)?;
+    Ok((main_reader, tail_number))
+}
+
+fn main_header(main_reader: BytesVecReader<'_>, tail_number: u64, number: u64) -> Result<Option<HeaderInfoReader<'_>>, Error> {
+    if number > tail_number || tail_number - number >= main_reader.len() as u64 {
+        return Ok(None);
+    }
+    let index = main_reader.len() - 1 - (tail_number - number) as usize;
+    Ok(Some(header_info(main_reader.get_unchecked(index).raw_data())?))
+}
+
+/// the main chain header of the block number. the numbers are contiguous, so it is found by its
+/// offset from the tail without scanning the chain.
+pub fn find_by_number(data: &CellDataView, number: u64) -> Result<Option<HeaderInfoReader<'_>>, Error> {
+    let (main_reader, tail_number) = main_chain(&data.headers)?;
+    main_header(main_reader, tail_number, number)
+}
+
+/// the main chain headers with their block numbers, from the oldest to the tail. the numbers are
+/// counted from the tail, the headers are not decoded.
+pub fn iter_main_chain(data: &CellDataView) -> Result<impl Iterator<Item = Result<(u64, HeaderInfoReader<'_>), Error>>, Error> {
+    let (main_reader, tail_number) = main_chain(&data.headers)?;
+    // a cell with a non contiguous main chain would underflow, see `verify_init_chain` of the logic.
+    let oldest_number = (tail_number + 1).checked_sub(main_reader.len() as u64).ok_or(Error::InvalidCellData)?;
+    Ok((0..main_reader.len()).map(move |i| Ok((oldest_number + i as u64, header_info(main_reader.get_unchecked(i).raw_data())?))))
+}
+
+/// the block number of the header if it is an uncle of the client. the uncle chain is sorted by
+/// hash. only the cached uncles are known, an evicted uncle can not be told from an unknown header.
+pub fn uncle_number(data: &CellDataView, hash: &[u8]) -> Result<Option<u64>, Error> {
+    if ChainReader::verify(&data.headers, false).is_err() {
+        return Err(Error::InvalidCellData);
+    }
+    let uncle_reader = ChainReader::new_unchecked(&data.headers).uncle();
+    let mut low = 0;
+    let mut high = uncle_reader.len();
+    while low < high {
+        let mid = (low + high) / 2;
+        let uncle_info_reader = uncle_reader.get_unchecked(mid);
+        match uncle_info_reader.hash().raw_data().cmp(hash) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => {
+                let number = HeaderView::new(uncle_info_reader.header().raw_data()).and_then(|header| header.number()).map_err(|_| Error::InvalidCellData)?;
+                return Ok(Some(number));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// the depth of the block and the log in the receipt proof, like `verify_log` with the client cell
+/// data loaded by the caller.
+pub fn proven_log(data: &CellDataView, proof_raw: &[u8], filter: &LogFilter) -> Result<(u64, LogEntry), Error> {
+    if ReceiptProofReader::verify(proof_raw, false).is_err() {
+        return Err(Error::InvalidWitness);
+    }
+    let proof_reader = ReceiptProofReader::new_unchecked(proof_raw);
+    let header_reader = proof_reader.header();
+    let (depth, header_info_reader) = locate_header(&data.headers, header_reader.hash().raw_data(), to_u64(&header_reader.number().to_entity()))?;
+    let header = HeaderView::new(header_info_reader.header().raw_data()).map_err(|_| Error::InvalidCellData)?;
+    // the bloom check is much cheaper than the receipt proof.
+    if !filter.may_match(header.logs_bloom().map_err(|_| Error::InvalidCellData)?) {
+        return Err(Error::EventDefinitelyAbsent);
+    }
+    let receipts_root = header.receipts_root().map_err(|_| Error::InvalidCellData)?;
+    let mut proof = Vec::with_capacity(proof_reader.proof().len());
+    for i in 0..proof_reader.proof().len() {
+        proof.push(proof_reader.proof().get_unchecked(i).raw_data());
+    }
+    let receipt = verify_receipt_proof(receipts_root, to_u64(&proof_reader.tx_index().to_entity()), &proof)?;
+    let log = find_log(&receipt, to_u64(&proof_reader.log_index().to_entity()) as usize, filter)?;
+    Ok((depth, log.clone()))
+}
+
+// the client cell had been verified by the client type script, so the stored hash is trusted.
+fn header_info(header_info_raw: &[u8]) -> Result<HeaderInfoReader<'_>, Error> {
+    if HeaderInfoReader::verify(header_info_raw, false).is_err() {
+        return Err(Error::InvalidCellData);
+    }
+    Ok(HeaderInfoReader::new_unchecked(header_info_raw))
+}
@@ -0,0 +1,21 @@
+//! Read the client cell from other scripts, e.g. a mint script which requires a number of
+//! confirmations of an ethereum block before releasing the tokens.
+//!
+//! The cell data and witness types are shared with the eth-client contract, which writes the client
+//! cell. The `verify_*` functions load the client cell from the cell deps and require the `ckb-std`
+//! feature, the functions over a `CellDataView` work off-chain too.
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod bridge_adapter;
+pub mod receipt;
+pub mod types;
+mod chain;
+#[cfg(feature = "ckb-std")]
+mod load;
+
+pub use chain::*;
+#[cfg(feature = "ckb-std")]
+pub use load::*;
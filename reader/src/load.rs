@@ -0,0 +1,115 @@
+//! load the client cell from the cell deps of a script, with the `ckb-std` feature.
+
+use crate::types::{Error, BlockHash, CellDataView, ChainDigest, DIGEST_LEN, to_u64, witness::AncestryProofReader};
+use crate::chain::{ancestor_depth, locate_header, proven_log, uncle_number};
+use crate::receipt::LogFilter;
+use eth_client_core::header::{ommers_hash, HeaderView};
+use eth_spv_lib::eth_types::*;
+use ckb_std::{ckb_constants::Source, error::SysError, high_level::{load_cell_data, load_cell_type_hash}, syscalls};
+use molecule::prelude::Reader;
+
+/// load the client cell like `verify_ancestry`, and return the block number of the header if it is an uncle,
+/// so bridges can treat blocks which are only included as uncles differently.
+pub fn verify_uncle(client_type_hash: &[u8; 32], dep_index: usize, hash: &[u8]) -> Result<Option<u64>, Error> {
+    let data = load_client_data(client_type_hash, dep_index)?;
+    uncle_number(&data, hash)
+}
+
+/// load the client cell from the cell deps, and return the depth of the header in the ancestry proof.
+/// the type hash of the client cell is checked, since anyone can create a cell with the same data.
+pub fn verify_ancestry(client_type_hash: &[u8; 32], dep_index: usize, proof_raw: &[u8]) -> Result<u64, Error> {
+    let data = load_client_data(client_type_hash, dep_index)?;
+    if AncestryProofReader::verify(proof_raw, false).is_err() {
+        return Err(Error::InvalidWitness);
+    }
+    let proof_reader = AncestryProofReader::new_unchecked(proof_raw);
+    ancestor_depth(&data, proof_reader.hash().raw_data(), to_u64(&proof_reader.number().to_entity()))
+}
+
+/// load the client cell like `verify_ancestry`, and check `ommers` are the ommer headers included by the
+/// block of the ancestry proof, e.g. for uncle rewards accounting. returns the depth of the block.
+pub fn verify_ommers(client_type_hash: &[u8; 32], dep_index: usize, proof_raw: &[u8], ommers: &[&[u8]]) -> Result<u64, Error> {
+    let data = load_client_data(client_type_hash, dep_index)?;
+    if AncestryProofReader::verify(proof_raw, false).is_err() {
+        return Err(Error::InvalidWitness);
+    }
+    let proof_reader = AncestryProofReader::new_unchecked(proof_raw);
+    let (depth, header_info_reader) = locate_header(&data.headers, proof_reader.hash().raw_data(), to_u64(&proof_reader.number().to_entity()))?;
+    let header = HeaderView::new(header_info_reader.header().raw_data()).map_err(|_| Error::InvalidCellData)?;
+    let hash = ommers_hash(ommers).map_err(|_| Error::InvalidOmmers)?;
+    if !BlockHash::from(&hash).matches(header.uncles_hash().map_err(|_| Error::InvalidCellData)?) {
+        return Err(Error::InvalidOmmers);
+    }
+    Ok(depth)
+}
+
+/// load the client cell like `verify_ancestry`, and return the depth of the header in the ancestry proof
+/// and the ckb timestamp in milliseconds stored with it when it was accepted, the timestamp of the first
+/// header dep of the accepting tx. the header was not known by the client before that time, 0 if the
+/// accepting tx had no header deps.
+pub fn verify_known_by(client_type_hash: &[u8; 32], dep_index: usize, proof_raw: &[u8]) -> Result<(u64, u64), Error> {
+    let data = load_client_data(client_type_hash, dep_index)?;
+    if AncestryProofReader::verify(proof_raw, false).is_err() {
+        return Err(Error::InvalidWitness);
+    }
+    let proof_reader = AncestryProofReader::new_unchecked(proof_raw);
+    let (depth, header_info_reader) = locate_header(&data.headers, proof_reader.hash().raw_data(), to_u64(&proof_reader.number().to_entity()))?;
+    Ok((depth, to_u64(&header_info_reader.ckb_timestamp().to_entity())))
+}
+
+/// load only the digest of the client cell like `load_digest`, and return its finalized number: the
+/// headers up to it have at least the finality weight of the client config accumulated on top of them.
+pub fn finalized_number(client_type_hash: &[u8; 32], dep_index: usize) -> Result<u64, Error> {
+    Ok(load_digest(client_type_hash, dep_index)?.finalized_number)
+}
+
+/// load the client cell like `verify_ancestry`, the header in the ancestry proof must have at least
+/// `min_weight` difficulty on top of it. returns the weight, for bridges which prefer weight based
+/// finality over a number of confirmations.
+pub fn verify_finality(client_type_hash: &[u8; 32], dep_index: usize, proof_raw: &[u8], min_weight: u64) -> Result<u64, Error> {
+    let data = load_client_data(client_type_hash, dep_index)?;
+    if AncestryProofReader::verify(proof_raw, false).is_err() {
+        return Err(Error::InvalidWitness);
+    }
+    let proof_reader = AncestryProofReader::new_unchecked(proof_raw);
+    let (_, header_info_reader) = locate_header(&data.headers, proof_reader.hash().raw_data(), to_u64(&proof_reader.number().to_entity()))?;
+    let weight = data
+        .digest
+        .total_difficulty
+        .checked_sub(to_u64(&header_info_reader.total_difficulty().to_entity()))
+        .ok_or(Error::InvalidCellData)?;
+    if weight < min_weight {
+        return Err(Error::HeaderNotFinal);
+    }
+    Ok(weight)
+}
+
+/// load the client cell like `verify_ancestry`, and return the depth of the block and the log in the
+/// receipt proof. the log must pass the filter, so bridges only need to decode its fields.
+pub fn verify_log(client_type_hash: &[u8; 32], dep_index: usize, proof_raw: &[u8], filter: &LogFilter) -> Result<(u64, LogEntry), Error> {
+    let data = load_client_data(client_type_hash, dep_index)?;
+    proven_log(&data, proof_raw, filter)
+}
+
+/// load only the digest of the client cell in the cell deps, which is cheaper than `load_client_data`.
+pub fn load_digest(client_type_hash: &[u8; 32], dep_index: usize) -> Result<ChainDigest, Error> {
+    if load_cell_type_hash(dep_index, Source::CellDep)?.as_ref() != Some(client_type_hash) {
+        return Err(Error::InvalidClientCell);
+    }
+    let mut digest = [0u8; DIGEST_LEN];
+    match syscalls::load_cell_data(&mut digest, 0, dep_index, Source::CellDep) {
+        Ok(len) if len >= DIGEST_LEN => {}
+        // the rest of the cell data is not loaded.
+        Err(SysError::LengthNotEnough(_)) => {}
+        Ok(_) => return Err(Error::Encoding),
+        Err(err) => return Err(err.into()),
+    }
+    ChainDigest::from_slice(&digest)
+}
+
+pub(crate) fn load_client_data(client_type_hash: &[u8; 32], dep_index: usize) -> Result<CellDataView, Error> {
+    if load_cell_type_hash(dep_index, Source::CellDep)?.as_ref() != Some(client_type_hash) {
+        return Err(Error::InvalidClientCell);
+    }
+    CellDataView::from_slice(&load_cell_data(dep_index, Source::CellDep)?)
+}
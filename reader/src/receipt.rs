@@ -0,0 +1,239 @@
+//! verify receipts against the receipts root of a header, and find the logs bridges are interested in.
+
+use crate::types::Error;
+use alloc::vec::Vec;
+use eth_spv_lib::eth_types::*;
+use rlp::Rlp;
+
+/// the typed receipts of eip-2718 envelopes, eip-2930 access list and eip-1559 dynamic fee
+/// transactions. the later types are only in post-merge blocks, which have no proof of work.
+pub use eth_client_constants::RECEIPT_TYPES;
+
+/// verify the merkle patricia proof of the receipt of the `tx_index`th transaction in the block.
+/// the proof is the list of rlp encoded trie nodes from the root to the leaf, nodes shorter than
+/// 32 bytes are embedded in their parents, so they are not in the proof.
+pub fn verify_receipt_proof(receipts_root: &[u8], tx_index: u64, proof: &[&[u8]]) -> Result<Receipt, Error> {
+    let key = rlp::encode(&tx_index);
+    let value = verify_trie_proof(receipts_root, &key, proof)?;
+    decode_receipt(&value)
+}
+
+/// a receipt in the trie is either a legacy rlp list, or the type byte followed by the rlp list of
+/// the same fields. both decode to the same `Receipt`, the type doesn't change the logs.
+pub fn decode_receipt(value: &[u8]) -> Result<Receipt, Error> {
+    let receipt_raw = match value.first() {
+        Some(first) if *first >= 0xc0 => value,
+        Some(first) if RECEIPT_TYPES.contains(first) => &value[1..],
+        Some(_) => return Err(Error::UnsupportedReceiptType),
+        None => return Err(Error::InvalidReceiptProof),
+    };
+    let receipt = Rlp::new(receipt_raw);
+    if !receipt.is_list() || receipt.payload_info().map_err(|_| Error::InvalidReceiptProof)?.total() != receipt_raw.len() {
+        return Err(Error::InvalidReceiptProof);
+    }
+    receipt.as_val().map_err(|_| Error::InvalidReceiptProof)
+}
+
+fn verify_trie_proof(root: &[u8], key: &[u8], proof: &[&[u8]]) -> Result<Vec<u8>, Error> {
+    let mut nibbles = Vec::with_capacity(key.len() * 2);
+    for byte in key {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+    let mut expected_hash = root.to_vec();
+    let mut position = 0;
+    for node_raw in proof {
+        if my_keccak256(node_raw)[..] != expected_hash[..] {
+            return Err(Error::InvalidReceiptProof);
+        }
+        let mut node = Rlp::new(node_raw);
+        // walk through the embedded nodes until a child is referenced by hash.
+        loop {
+            let child = match node.item_count().map_err(|_| Error::InvalidReceiptProof)? {
+                17 => {
+                    if position == nibbles.len() {
+                        return node_data(&node, 16);
+                    }
+                    position += 1;
+                    node.at(nibbles[position - 1] as usize).map_err(|_| Error::InvalidReceiptProof)?
+                }
+                2 => {
+                    let path = node_data(&node, 0)?;
+                    if path.is_empty() {
+                        return Err(Error::InvalidReceiptProof);
+                    }
+                    // hex prefix encoding, the high nibble of the first byte is 2 for leaf and 1 for odd length.
+                    let flag = path[0] >> 4;
+                    let mut path_nibbles = Vec::with_capacity(path.len() * 2);
+                    if flag & 1 == 1 {
+                        path_nibbles.push(path[0] & 0x0f);
+                    }
+                    for byte in &path[1..] {
+                        path_nibbles.push(byte >> 4);
+                        path_nibbles.push(byte & 0x0f);
+                    }
+                    if !nibbles[position..].starts_with(&path_nibbles) {
+                        return Err(Error::InvalidReceiptProof);
+                    }
+                    position += path_nibbles.len();
+                    if flag & 2 == 2 {
+                        if position != nibbles.len() {
+                            return Err(Error::InvalidReceiptProof);
+                        }
+                        return node_data(&node, 1);
+                    }
+                    node.at(1).map_err(|_| Error::InvalidReceiptProof)?
+                }
+                _ => return Err(Error::InvalidReceiptProof),
+            };
+            if child.is_list() {
+                node = child;
+                continue;
+            }
+            expected_hash = child.data().map_err(|_| Error::InvalidReceiptProof)?.to_vec();
+            if expected_hash.len() != 32 {
+                return Err(Error::InvalidReceiptProof);
+            }
+            break;
+        }
+    }
+    // the proof ends before reaching the value.
+    Err(Error::InvalidReceiptProof)
+}
+
+fn node_data(node: &Rlp, index: usize) -> Result<Vec<u8>, Error> {
+    let item = node.at(index).map_err(|_| Error::InvalidReceiptProof)?;
+    Ok(item.data().map_err(|_| Error::InvalidReceiptProof)?.to_vec())
+}
+
+/// filter logs by the emitting contract and the leading topics.
+pub struct LogFilter<'a> {
+    pub address: &'a Address,
+    pub topics: &'a [H256],
+}
+
+impl<'a> LogFilter<'a> {
+    pub fn matches(&self, log: &LogEntry) -> bool {
+        log.address == *self.address && log.topics.starts_with(self.topics)
+    }
+
+    /// false if no log of the block can match, checked against the logs bloom of the header.
+    /// a bloom filter has false positives, so true does not mean there is such a log.
+    pub fn may_match(&self, logs_bloom: &[u8]) -> bool {
+        bloom_contains(logs_bloom, self.address.0.as_bytes())
+            && self.topics.iter().all(|topic| bloom_contains(logs_bloom, topic.0.as_bytes()))
+    }
+}
+
+/// the address and the topics of every log are added to the 2048 bits bloom of the block,
+/// 3 bits per item, taken from the low 11 bits of the first 3 byte pairs of its keccak256.
+pub fn bloom_contains(logs_bloom: &[u8], item: &[u8]) -> bool {
+    if logs_bloom.len() != 256 {
+        return false;
+    }
+    let hash = my_keccak256(item);
+    (0..3).all(|i| {
+        let bit = (((hash[i * 2] as usize) << 8) | hash[i * 2 + 1] as usize) & 2047;
+        logs_bloom[255 - bit / 8] & (1 << (bit % 8)) != 0
+    })
+}
+
+/// the log at `log_index` of the receipt, it must pass the filter.
+pub fn find_log<'a>(receipt: &'a Receipt, log_index: usize, filter: &LogFilter) -> Result<&'a LogEntry, Error> {
+    let log = receipt.logs.get(log_index).ok_or(Error::LogNotFound)?;
+    if !filter.matches(log) {
+        return Err(Error::LogNotFound);
+    }
+    Ok(log)
+}
+
+/// the first topic of an event is the keccak256 of its signature, e.g. `Transfer(address,address,uint256)`.
+pub fn event_topic(signature: &str) -> H256 {
+    H256(my_keccak256(signature.as_bytes()).into())
+}
+
+/// the `index`th 32 bytes word of abi encoded data.
+pub fn abi_word(data: &[u8], index: usize) -> Result<&[u8], Error> {
+    data.get(index * 32..(index + 1) * 32).ok_or(Error::InvalidLogData)
+}
+
+/// the dynamic `bytes` of abi encoded data, the `index`th word is the offset of its length word.
+pub fn abi_bytes(data: &[u8], index: usize) -> Result<&[u8], Error> {
+    let offset = abi_usize(abi_word(data, index)?)?;
+    let len = abi_usize(data.get(offset..offset.checked_add(32).ok_or(Error::InvalidLogData)?).ok_or(Error::InvalidLogData)?)?;
+    let start = offset + 32;
+    data.get(start..start.checked_add(len).ok_or(Error::InvalidLogData)?).ok_or(Error::InvalidLogData)
+}
+
+fn abi_usize(word: &[u8]) -> Result<usize, Error> {
+    if word.len() != 32 || word[..24].iter().any(|b| *b != 0) {
+        return Err(Error::InvalidLogData);
+    }
+    let mut value = [0u8; 8];
+    value.copy_from_slice(&word[24..]);
+    Ok(u64::from_be_bytes(value) as usize)
+}
+
+/// addresses are left padded to 32 bytes in topics and abi encoded data.
+pub fn abi_address(word: &[u8]) -> Result<Address, Error> {
+    if word.len() != 32 || word[..12].iter().any(|b| *b != 0) {
+        return Err(Error::InvalidLogData);
+    }
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&word[12..]);
+    Ok(address.into())
+}
+
+pub fn abi_uint(word: &[u8]) -> Result<U256, Error> {
+    if word.len() != 32 {
+        return Err(Error::InvalidLogData);
+    }
+    Ok(U256(primitive_types::U256::from_big_endian(word)))
+}
+
+/// `event Transfer(address indexed from, address indexed to, uint256 value)` of erc20 tokens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferEvent {
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+}
+
+impl TransferEvent {
+    pub fn signature() -> H256 {
+        event_topic("Transfer(address,address,uint256)")
+    }
+
+    pub fn decode(log: &LogEntry) -> Result<TransferEvent, Error> {
+        if log.topics.len() != 3 || log.topics[0] != Self::signature() {
+            return Err(Error::InvalidLogData);
+        }
+        Ok(TransferEvent {
+            from: abi_address(log.topics[1].0.as_bytes())?,
+            to: abi_address(log.topics[2].0.as_bytes())?,
+            value: abi_uint(abi_word(&log.data, 0)?)?,
+        })
+    }
+}
+
+/// `event Locked(address indexed token, address indexed sender, uint256 amount, ...)` of the bridge
+/// contract. the trailing non indexed fields are bridge specific and left to the caller.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockEvent {
+    pub token: Address,
+    pub sender: Address,
+    pub amount: U256,
+}
+
+impl LockEvent {
+    pub fn decode(log: &LogEntry, signature: &H256) -> Result<LockEvent, Error> {
+        if log.topics.len() != 3 || log.topics[0] != *signature {
+            return Err(Error::InvalidLogData);
+        }
+        Ok(LockEvent {
+            token: abi_address(log.topics[1].0.as_bytes())?,
+            sender: abi_address(log.topics[2].0.as_bytes())?,
+            amount: abi_uint(abi_word(&log.data, 0)?)?,
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use super::generated::basic::Byte32Reader;
+use core::convert::TryFrom;
+use eth_spv_lib::eth_types::H256;
+
+/// a block hash of exactly 32 bytes. the hashes from the molecule readers and the witness are unsized
+/// slices, comparing them through `BlockHash` checks the length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockHash([u8; 32]);
+
+impl BlockHash {
+    /// false if the slice is not 32 bytes. the bytes are compared in constant time.
+    pub fn matches(&self, slice: &[u8]) -> bool {
+        slice.len() == 32 && self.0.iter().zip(slice).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+    }
+}
+
+impl From<Byte32Reader<'_>> for BlockHash {
+    fn from(reader: Byte32Reader) -> Self {
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(reader.raw_data());
+        BlockHash(hash)
+    }
+}
+
+impl From<&H256> for BlockHash {
+    fn from(hash: &H256) -> Self {
+        let mut ret = [0u8; 32];
+        ret.copy_from_slice(hash.0.as_bytes());
+        BlockHash(ret)
+    }
+}
+
+impl TryFrom<&[u8]> for BlockHash {
+    type Error = ();
+
+    fn try_from(slice: &[u8]) -> Result<Self, ()> {
+        if slice.len() != 32 {
+            return Err(());
+        }
+        let mut ret = [0u8; 32];
+        ret.copy_from_slice(slice);
+        Ok(BlockHash(ret))
+    }
+}
@@ -0,0 +1,90 @@
+use molecule::bytes::Bytes;
+use super::error::Error;
+use super::generated::cell_data::CellDataReader;
+use core::result::Result;
+use molecule::prelude::*;
+
+pub use eth_client_constants::DIGEST_LEN;
+
+/// a digest of the main chain at a fixed offset, so other scripts can read it with a partial load
+/// instead of parsing the whole cell data. numbers are little endian like molecule.
+/// layout: number (8) | hash (32) | total difficulty (8) | mmr root (32) | finalized number (8). no mmr
+/// of the main chain is maintained, the mmr root is reserved and always zero. the finalized number is the
+/// newest main chain header with at least the finality weight of the config accumulated on top of it,
+/// 0 while no cached header is heavy enough.
+#[derive(Debug, Default, PartialEq)]
+pub struct ChainDigest {
+    pub number: u64,
+    pub hash: [u8; 32],
+    pub total_difficulty: u64,
+    pub mmr_root: [u8; 32],
+    pub finalized_number: u64,
+}
+
+impl ChainDigest {
+    pub fn from_slice(slice: &[u8]) -> Result<ChainDigest, Error> {
+        if slice.len() < DIGEST_LEN {
+            return Err(Error::Encoding);
+        }
+        let mut number = [0u8; 8];
+        number.copy_from_slice(&slice[0..8]);
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&slice[8..40]);
+        let mut total_difficulty = [0u8; 8];
+        total_difficulty.copy_from_slice(&slice[40..48]);
+        let mut mmr_root = [0u8; 32];
+        mmr_root.copy_from_slice(&slice[48..80]);
+        let mut finalized_number = [0u8; 8];
+        finalized_number.copy_from_slice(&slice[80..88]);
+        Ok(ChainDigest {
+            number: u64::from_le_bytes(number),
+            hash,
+            total_difficulty: u64::from_le_bytes(total_difficulty),
+            mmr_root,
+            finalized_number: u64::from_le_bytes(finalized_number),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CellDataView {
+    pub digest: ChainDigest,
+    pub user_lockscript: Bytes,
+    pub headers: Bytes,
+    pub paused: bool,
+    pub governance_lock_hash: Bytes,
+    pub relayers: Bytes,
+    pub config: Bytes,
+    pub update_sequence: u64,
+}
+
+impl CellDataView {
+    pub fn from_slice(slice: &[u8]) -> Result<CellDataView, Error> {
+        let digest = ChainDigest::from_slice(slice)?;
+        let slice = &slice[DIGEST_LEN..];
+        CellDataReader::verify(slice, false).map_err(|_| Error::Encoding)?;
+        let data_reader = CellDataReader::new_unchecked(slice);
+        let headers = data_reader.headers().to_entity().as_bytes();
+        let user_lockscript = data_reader.user_lockscript().to_entity().as_bytes();
+        let paused = match data_reader.paused().as_slice()[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::Encoding),
+        };
+        let governance_lock_hash = data_reader.governance_lock_hash().to_entity().as_bytes();
+        let relayers = data_reader.relayers().to_entity().as_bytes();
+        let config = data_reader.config().to_entity().as_bytes();
+        let mut update_sequence = [0u8; 8];
+        update_sequence.copy_from_slice(data_reader.update_sequence().as_slice());
+        Ok(CellDataView {
+            digest,
+            headers,
+            user_lockscript,
+            paused,
+            governance_lock_hash,
+            relayers,
+            config,
+            update_sequence: u64::from_le_bytes(update_sequence),
+        })
+    }
+}
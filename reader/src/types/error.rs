@@ -0,0 +1,71 @@
+#[cfg(feature = "ckb-std")]
+use ckb_std::error::SysError;
+/// Error
+#[derive(Debug, PartialEq)]
+#[repr(i8)]
+pub enum Error {
+    IndexOutOfBound = 1,
+    ItemMissing,
+    LengthNotEnough,
+    Encoding,
+    // Add customized errors here...
+    TxInvalid,
+    InvalidWitness,
+    InvalidDataChange,
+    InvalidCellData,
+    DagsMerkleRootsDataInvalid,
+    InvalidMerkleProofData,
+    InvalidTypeArgs,
+    ClientPaused,
+    GovernanceLockMissing,
+    InvalidHeaderHash,
+    InvalidRelayer,
+    InvalidConfig,
+    HeaderNotFound,
+    InvalidClientCell,
+    InvalidReceiptProof,
+    LogNotFound,
+    InvalidLogData,
+    StaleWitness,
+    InvalidTimestamp,
+    UnsupportedWitness,
+    InvalidDigest,
+    DagsMerkleRootNotFound,
+    EventDefinitelyAbsent,
+    HeaderTooLarge,
+    TooManyMerkleProofs,
+    BatchTooLong,
+    HeaderNotFinal,
+    UpdateTooFrequent,
+    UncleTooOld,
+    EpochRootMismatch,
+    UntrustedRootsCell,
+    ReorgTooDeep,
+    UserLockMissing,
+    HeaderAlreadyExists,
+    NonCanonicalHeader,
+    InvalidUpdateSequence,
+    InvalidOmmers,
+    InvalidMerkleProofCount,
+    UnsupportedReceiptType,
+    CapacityDecreased,
+    InvalidChainEvent,
+    HeaderDecodeFailed,
+    InvalidGasLimit,
+    PendingDifficultyTooLow,
+    BuriedDifficultyTooHigh,
+}
+
+#[cfg(feature = "ckb-std")]
+impl From<SysError> for Error {
+    fn from(err: SysError) -> Self {
+        use SysError::*;
+        match err {
+            IndexOutOfBound => Self::IndexOutOfBound,
+            ItemMissing => Self::ItemMissing,
+            LengthNotEnough(_) => Self::LengthNotEnough,
+            Encoding => Self::Encoding,
+            Unknown(err_code) => panic!("unexpected sys error {}", err_code),
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,17 @@
+// Generated by Molecule 0.6.1
+#![allow(dead_code)]
+use molecule :: prelude :: * ;
+use super :: basic :: * ;
+# [ derive ( Clone ) ] pub struct DagsMerkleRoots ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for DagsMerkleRoots { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for DagsMerkleRoots { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for DagsMerkleRoots { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "dags_merkle_roots" , self . dags_merkle_roots ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for DagsMerkleRoots { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 12 , 0 , 0 , 0 , 8 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , ] ; DagsMerkleRoots :: new_unchecked ( v . into ( ) ) } } impl DagsMerkleRoots { pub const FIELD_COUNT : usize = 1 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn dags_merkle_roots ( & self ) -> BytesVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; BytesVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { BytesVec :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> DagsMerkleRootsReader < 'r > { DagsMerkleRootsReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for DagsMerkleRoots { type Builder = DagsMerkleRootsBuilder ; const NAME : & 'static str = "DagsMerkleRoots" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { DagsMerkleRoots ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { DagsMerkleRootsReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { DagsMerkleRootsReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . dags_merkle_roots ( self . dags_merkle_roots ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct DagsMerkleRootsReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for DagsMerkleRootsReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for DagsMerkleRootsReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for DagsMerkleRootsReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "dags_merkle_roots" , self . dags_merkle_roots ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > DagsMerkleRootsReader < 'r > { pub const FIELD_COUNT : usize = 1 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn dags_merkle_roots ( & self ) -> BytesVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for DagsMerkleRootsReader < 'r > { type Entity = DagsMerkleRoots ; const NAME : & 'static str = "DagsMerkleRootsReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { DagsMerkleRootsReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } BytesVecReader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct DagsMerkleRootsBuilder { pub ( crate ) dags_merkle_roots : BytesVec , } impl DagsMerkleRootsBuilder { pub const FIELD_COUNT : usize = 1 ; pub fn dags_merkle_roots ( mut self , v : BytesVec ) -> Self { self . dags_merkle_roots = v ; self } } impl molecule :: prelude :: Builder for DagsMerkleRootsBuilder { type Entity = DagsMerkleRoots ; const NAME : & 'static str = "DagsMerkleRootsBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . dags_merkle_roots . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . dags_merkle_roots . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . dags_merkle_roots . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; DagsMerkleRoots :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct EpochMerkleRoot ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for EpochMerkleRoot { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for EpochMerkleRoot { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for EpochMerkleRoot { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "epoch" , self . epoch ( ) ) ? ; write ! ( f , ", {}: {}" , "root" , self . root ( ) ) ? ; write ! ( f , " }}" ) } } impl :: core :: default :: Default for EpochMerkleRoot { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; EpochMerkleRoot :: new_unchecked ( v . into ( ) ) } } impl EpochMerkleRoot { pub const TOTAL_SIZE : usize = 24 ; pub const FIELD_SIZES : [ usize ; 2 ] = [ 8 , 16 , ] ; pub const FIELD_COUNT : usize = 2 ; pub fn epoch ( & self ) -> Uint64 { Uint64 :: new_unchecked ( self . 0 . slice ( 0 .. 8 ) ) } pub fn root ( & self ) -> Byte16 { Byte16 :: new_unchecked ( self . 0 . slice ( 8 .. 24 ) ) } pub fn as_reader < 'r > ( & 'r self ) -> EpochMerkleRootReader < 'r > { EpochMerkleRootReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for EpochMerkleRoot { type Builder = EpochMerkleRootBuilder ; const NAME : & 'static str = "EpochMerkleRoot" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { EpochMerkleRoot ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { EpochMerkleRootReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { EpochMerkleRootReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . epoch ( self . epoch ( ) ) . root ( self . root ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct EpochMerkleRootReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for EpochMerkleRootReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for EpochMerkleRootReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for EpochMerkleRootReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "epoch" , self . epoch ( ) ) ? ; write ! ( f , ", {}: {}" , "root" , self . root ( ) ) ? ; write ! ( f , " }}" ) } } impl < 'r > EpochMerkleRootReader < 'r > { pub const TOTAL_SIZE : usize = 24 ; pub const FIELD_SIZES : [ usize ; 2 ] = [ 8 , 16 , ] ; pub const FIELD_COUNT : usize = 2 ; pub fn epoch ( & self ) -> Uint64Reader < 'r > { Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ 0 .. 8 ] ) } pub fn root ( & self ) -> Byte16Reader < 'r > { Byte16Reader :: new_unchecked ( & self . as_slice ( ) [ 8 .. 24 ] ) } } impl < 'r > molecule :: prelude :: Reader < 'r > for EpochMerkleRootReader < 'r > { type Entity = EpochMerkleRoot ; const NAME : & 'static str = "EpochMerkleRootReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { EpochMerkleRootReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , _compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len != Self :: TOTAL_SIZE { return ve ! ( Self , TotalSizeNotMatch , Self :: TOTAL_SIZE , slice_len ) ; } Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct EpochMerkleRootBuilder { pub ( crate ) epoch : Uint64 , pub ( crate ) root : Byte16 , } impl EpochMerkleRootBuilder { pub const TOTAL_SIZE : usize = 24 ; pub const FIELD_SIZES : [ usize ; 2 ] = [ 8 , 16 , ] ; pub const FIELD_COUNT : usize = 2 ; pub fn epoch ( mut self , v : Uint64 ) -> Self { self . epoch = v ; self } pub fn root ( mut self , v : Byte16 ) -> Self { self . root = v ; self } } impl molecule :: prelude :: Builder for EpochMerkleRootBuilder { type Entity = EpochMerkleRoot ; const NAME : & 'static str = "EpochMerkleRootBuilder" ; fn expected_length ( & self ) -> usize { Self :: TOTAL_SIZE } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( self . epoch . as_slice ( ) ) ? ; writer . write_all ( self . root . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; EpochMerkleRoot :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct EpochMerkleRootVec ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for EpochMerkleRootVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for EpochMerkleRootVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for EpochMerkleRootVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} [" , Self :: NAME ) ? ; for i in 0 .. self . len ( ) { if i == 0 { write ! ( f , "{}" , self . get_unchecked ( i ) ) ? ; } else { write ! ( f , ", {}" , self . get_unchecked ( i ) ) ? ; } } write ! ( f , "]" ) } } impl :: core :: default :: Default for EpochMerkleRootVec { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , ] ; EpochMerkleRootVec :: new_unchecked ( v . into ( ) ) } } impl EpochMerkleRootVec { pub const ITEM_SIZE : usize = 24 ; pub fn total_size ( & self ) -> usize { molecule :: NUMBER_SIZE * ( self . item_count ( ) + 1 ) } pub fn item_count ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < EpochMerkleRoot > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> EpochMerkleRoot { let start = molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * idx ; let end = start + Self :: ITEM_SIZE ; EpochMerkleRoot :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn as_reader < 'r > ( & 'r self ) -> EpochMerkleRootVecReader < 'r > { EpochMerkleRootVecReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for EpochMerkleRootVec { type Builder = EpochMerkleRootVecBuilder ; const NAME : & 'static str = "EpochMerkleRootVec" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { EpochMerkleRootVec ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { EpochMerkleRootVecReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { EpochMerkleRootVecReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . extend ( self . into_iter ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct EpochMerkleRootVecReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for EpochMerkleRootVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for EpochMerkleRootVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for EpochMerkleRootVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} [" , Self :: NAME ) ? ; for i in 0 .. self . len ( ) { if i == 0 { write ! ( f , "{}" , self . get_unchecked ( i ) ) ? ; } else { write ! ( f , ", {}" , self . get_unchecked ( i ) ) ? ; } } write ! ( f , "]" ) } } impl < 'r > EpochMerkleRootVecReader < 'r > { pub const ITEM_SIZE : usize = 24 ; pub fn total_size ( & self ) -> usize { molecule :: NUMBER_SIZE * ( self . item_count ( ) + 1 ) } pub fn item_count ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < EpochMerkleRootReader < 'r > > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> EpochMerkleRootReader < 'r > { let start = molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * idx ; let end = start + Self :: ITEM_SIZE ; EpochMerkleRootReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } } impl < 'r > molecule :: prelude :: Reader < 'r > for EpochMerkleRootVecReader < 'r > { type Entity = EpochMerkleRootVec ; const NAME : & 'static str = "EpochMerkleRootVecReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { EpochMerkleRootVecReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , _compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let item_count = molecule :: unpack_number ( slice ) as usize ; if item_count == 0 { if slice_len != molecule :: NUMBER_SIZE { return ve ! ( Self , TotalSizeNotMatch , molecule :: NUMBER_SIZE , slice_len ) ; } return Ok ( ( ) ) ; } let total_size = molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * item_count ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct EpochMerkleRootVecBuilder ( pub ( crate ) Vec < EpochMerkleRoot > ) ; impl EpochMerkleRootVecBuilder { pub const ITEM_SIZE : usize = 24 ; pub fn set ( mut self , v : Vec < EpochMerkleRoot > ) -> Self { self . 0 = v ; self } pub fn push ( mut self , v : EpochMerkleRoot ) -> Self { self . 0 . push ( v ) ; self } pub fn extend < T : :: core :: iter :: IntoIterator < Item = EpochMerkleRoot >> ( mut self , iter : T ) -> Self { for elem in iter { self . 0 . push ( elem ) ; } self } } impl molecule :: prelude :: Builder for EpochMerkleRootVecBuilder { type Entity = EpochMerkleRootVec ; const NAME : & 'static str = "EpochMerkleRootVecBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * self . 0 . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( & molecule :: pack_number ( self . 0 . len ( ) as molecule :: Number ) ) ? ; for inner in & self . 0 [ .. ] { writer . write_all ( inner . as_slice ( ) ) ? ; } Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; EpochMerkleRootVec :: new_unchecked ( inner . into ( ) ) } }
+pub struct EpochMerkleRootVecIterator ( EpochMerkleRootVec , usize , usize ) ; impl :: core :: iter :: Iterator for EpochMerkleRootVecIterator { type Item = EpochMerkleRoot ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl :: core :: iter :: ExactSizeIterator for EpochMerkleRootVecIterator { fn len ( & self ) -> usize { self . 2 - self . 1 } } impl :: core :: iter :: IntoIterator for EpochMerkleRootVec { type Item = EpochMerkleRoot ; type IntoIter = EpochMerkleRootVecIterator ; fn into_iter ( self ) -> Self :: IntoIter { let len = self . len ( ) ; EpochMerkleRootVecIterator ( self , 0 , len ) } } impl < 'r > EpochMerkleRootVecReader < 'r > { pub fn iter < 't > ( & 't self ) -> EpochMerkleRootVecReaderIterator < 't , 'r > { EpochMerkleRootVecReaderIterator ( & self , 0 , self . len ( ) ) } } pub struct EpochMerkleRootVecReaderIterator < 't , 'r > ( & 't EpochMerkleRootVecReader < 'r > , usize , usize ) ; impl < 't : 'r , 'r > :: core :: iter :: Iterator for EpochMerkleRootVecReaderIterator < 't , 'r > { type Item = EpochMerkleRootReader < 't > ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl < 't : 'r , 'r > :: core :: iter :: ExactSizeIterator for EpochMerkleRootVecReaderIterator < 't , 'r > { fn len ( & self ) -> usize { self . 2 - self . 1 } }
+# [ derive ( Clone ) ] pub struct EpochMerkleRoots ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for EpochMerkleRoots { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for EpochMerkleRoots { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for EpochMerkleRoots { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "roots" , self . roots ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for EpochMerkleRoots { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 12 , 0 , 0 , 0 , 8 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; EpochMerkleRoots :: new_unchecked ( v . into ( ) ) } } impl EpochMerkleRoots { pub const FIELD_COUNT : usize = 1 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn roots ( & self ) -> EpochMerkleRootVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; EpochMerkleRootVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { EpochMerkleRootVec :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> EpochMerkleRootsReader < 'r > { EpochMerkleRootsReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for EpochMerkleRoots { type Builder = EpochMerkleRootsBuilder ; const NAME : & 'static str = "EpochMerkleRoots" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { EpochMerkleRoots ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { EpochMerkleRootsReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { EpochMerkleRootsReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . roots ( self . roots ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct EpochMerkleRootsReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for EpochMerkleRootsReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for EpochMerkleRootsReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for EpochMerkleRootsReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "roots" , self . roots ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > EpochMerkleRootsReader < 'r > { pub const FIELD_COUNT : usize = 1 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn roots ( & self ) -> EpochMerkleRootVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; EpochMerkleRootVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { EpochMerkleRootVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for EpochMerkleRootsReader < 'r > { type Entity = EpochMerkleRoots ; const NAME : & 'static str = "EpochMerkleRootsReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { EpochMerkleRootsReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } EpochMerkleRootVecReader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct EpochMerkleRootsBuilder { pub ( crate ) roots : EpochMerkleRootVec , } impl EpochMerkleRootsBuilder { pub const FIELD_COUNT : usize = 1 ; pub fn roots ( mut self , v : EpochMerkleRootVec ) -> Self { self . roots = v ; self } } impl molecule :: prelude :: Builder for EpochMerkleRootsBuilder { type Entity = EpochMerkleRoots ; const NAME : & 'static str = "EpochMerkleRootsBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . roots . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . roots . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . roots . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; EpochMerkleRoots :: new_unchecked ( inner . into ( ) ) } }
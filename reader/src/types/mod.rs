@@ -2,7 +2,10 @@ mod error;
 mod cell_data_view;
 mod generated;
 mod convert;
+mod block_hash;
 
 pub use error::Error;
 pub use cell_data_view::*;
+pub use block_hash::BlockHash;
+pub use convert::to_u64;
 pub use generated::{basic, cell_data, witness, dags_merkle_roots, double_node_with_merkle_proof};
\ No newline at end of file
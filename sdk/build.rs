@@ -0,0 +1,30 @@
+//! regenerate the molecule bindings of the sdk from the contract schemas when the `codegen`
+//! feature is enabled, so both sides always agree on the cell data and witness layout.
+use std::{env, fs, process::Command};
+
+const SCHEMA_DIR: &str = "../reader/src/types/schemas";
+const GENERATED_DIR: &str = "src/types/generated";
+const SCHEMAS: &[&str] = &["basic", "cell_data", "dags_merkle_roots", "double_node_with_merkle_proof", "witness"];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    for schema in SCHEMAS {
+        println!("cargo:rerun-if-changed={}/{}.mol", SCHEMA_DIR, schema);
+    }
+    if env::var_os("CARGO_FEATURE_CODEGEN").is_none() {
+        return;
+    }
+    for schema in SCHEMAS {
+        let output = Command::new("moleculec")
+            .current_dir(SCHEMA_DIR)
+            .args(&["--language", "rust", "--schema-file", &format!("{}.mol", schema)])
+            .output()
+            .expect("run moleculec");
+        assert!(output.status.success(), "moleculec {}: {}", schema, String::from_utf8_lossy(&output.stderr));
+        let code = String::from_utf8(output.stdout).expect("moleculec output");
+        let path = format!("{}/{}.rs", GENERATED_DIR, schema);
+        if fs::read_to_string(&path).ok().as_deref() != Some(code.as_str()) {
+            fs::write(&path, code).expect("write generated code");
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Batch submissions: the v2 witness extending the main chain by several headers, and the choice
+//! of the batch length under the size and cycle limits of a transaction.
+//!
+//! The cycles of a batch are estimated from a linear cost model, a fixed cost per header and the
+//! cost of a proof of work check. The costs depend on the contract binary, measure them by running
+//! batches of the deployed contract, e.g. with `ckb-testtool`, rather than guessing them.
+
+use crate::relay::{BlockWithProofs, RelayError};
+use crate::types::{
+    basic::{Bytes, BytesVec},
+    witness::{VersionedWitness, WitnessHeader, WitnessHeaderVec, WitnessV2},
+};
+pub use eth_client_constants::MAX_BATCH_LEN;
+use molecule::prelude::*;
+
+/// The limits a batch submission must fit in, and the cost model of the contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchBudget {
+    /// The max length of the molecule encoded `VersionedWitness`, the `input_type` of the client cell.
+    pub max_witness_bytes: usize,
+    /// The cycles left to the client type script by the relayer, below the block cycles limit.
+    pub max_cycles: u64,
+    /// The cycles of a batch header without its proof of work check.
+    pub header_cycles: u64,
+    /// The cycles of the proof of work check of a header.
+    pub pow_cycles: u64,
+}
+
+/// Whether each header of the batch carries its proofs. The headers buried under at least
+/// `pow_skip_depth` later headers of the batch may omit them, 0 keeps them all.
+///
+/// The contract bounds the difficulty of a header without proofs by the difficulty of the next
+/// header with proofs, so a buried header above that bound keeps its proofs.
+fn carries_proofs(blocks: &[BlockWithProofs], pow_skip_depth: u64) -> Result<Vec<bool>, RelayError> {
+    let mut carries = vec![true; blocks.len()];
+    let mut verified_difficulty = 0;
+    for (i, block) in blocks.iter().enumerate().rev() {
+        let difficulty: u64 = rlp::Rlp::new(&block.header_rlp).val_at(7).map_err(|_| RelayError::InvalidHeader)?;
+        let buried = pow_skip_depth != 0 && ((blocks.len() - 1 - i) as u64) >= pow_skip_depth;
+        if buried && difficulty <= verified_difficulty {
+            carries[i] = false;
+        } else {
+            verified_difficulty = difficulty;
+        }
+    }
+    Ok(carries)
+}
+
+/// Build the v2 witness submitting `blocks` in order on top of the main chain tail `expected_tail_hash`.
+///
+/// `pow_skip_depth` is the one of the client type script args, the buried headers are submitted
+/// without their proofs, see `carries_proofs`. They add no difficulty to the total difficulty of
+/// their `HeaderInfo`, only the headers with proofs do.
+pub fn build_batch_witness(
+    blocks: &[BlockWithProofs],
+    cell_dep_index: u8,
+    expected_tail_hash: [u8; 32],
+    pow_skip_depth: u64,
+) -> Result<VersionedWitness, RelayError> {
+    let carries = carries_proofs(blocks, pow_skip_depth)?;
+    let mut headers = Vec::with_capacity(blocks.len());
+    for (block, carries) in blocks.iter().zip(carries) {
+        let proofs: Vec<Bytes> = if carries {
+            block.merkle_proofs()?.iter().map(|proof| proof.as_slice().to_vec().into()).collect()
+        } else {
+            vec![]
+        };
+        headers.push(
+            WitnessHeader::new_builder()
+                .header(block.header_rlp.clone().into())
+                .merkle_proof(BytesVec::new_builder().set(proofs).build())
+                .build(),
+        );
+    }
+    let witness = WitnessV2::new_builder()
+        .headers(WitnessHeaderVec::new_builder().set(headers).build())
+        .cell_dep_index_list(vec![cell_dep_index].into())
+        .expected_tail_hash(expected_tail_hash.to_vec().into())
+        .build();
+    Ok(VersionedWitness::new_builder().set(witness).build())
+}
+
+/// The estimated cycles of a batch submitting `blocks`.
+pub fn estimate_cycles(blocks: &[BlockWithProofs], pow_skip_depth: u64, budget: &BatchBudget) -> Result<u64, RelayError> {
+    let pow_headers = carries_proofs(blocks, pow_skip_depth)?.into_iter().filter(|carries| *carries).count() as u64;
+    Ok((blocks.len() as u64).saturating_mul(budget.header_cycles).saturating_add(pow_headers.saturating_mul(budget.pow_cycles)))
+}
+
+/// The largest number of the leading `blocks` submitted in one batch within the budget, at most
+/// `MAX_BATCH_LEN`. 0 if even a single header doesn't fit.
+pub fn max_batch_len(
+    blocks: &[BlockWithProofs],
+    cell_dep_index: u8,
+    expected_tail_hash: [u8; 32],
+    pow_skip_depth: u64,
+    budget: &BatchBudget,
+) -> Result<usize, RelayError> {
+    let mut best = 0;
+    // a longer batch may fit when a shorter one doesn't, as more of its headers omit their proofs.
+    for len in 1..=blocks.len().min(MAX_BATCH_LEN) {
+        if estimate_cycles(&blocks[..len], pow_skip_depth, budget)? > budget.max_cycles {
+            continue;
+        }
+        let witness = build_batch_witness(&blocks[..len], cell_dep_index, expected_tail_hash, pow_skip_depth)?;
+        if witness.as_slice().len() <= budget.max_witness_bytes {
+            best = len;
+        }
+    }
+    Ok(best)
+}
@@ -0,0 +1,119 @@
+use crate::types::{basic::HeaderInfo, cell_data::CellData};
+use molecule::{error::VerificationError, prelude::*};
+use std::{convert::TryInto, fmt};
+
+pub use eth_client_constants::DIGEST_LEN;
+
+/// The digest of the main chain tail at the head of the client cell data.
+///
+/// Layout: number (8) | hash (32) | total difficulty (8) | mmr root (32) | finalized number (8),
+/// numbers are little endian like molecule. The contract maintains no merkle mountain range of
+/// the main chain, so the mmr root is reserved and always zero. Proofs against older headers can
+/// not use it. The finalized number is computed by `finalized_number`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChainDigest {
+    pub number: u64,
+    pub hash: [u8; 32],
+    pub total_difficulty: u64,
+    pub mmr_root: [u8; 32],
+    pub finalized_number: u64,
+}
+
+impl ChainDigest {
+    pub fn from_slice(slice: &[u8]) -> Result<ChainDigest, CellDataError> {
+        if slice.len() < DIGEST_LEN {
+            return Err(CellDataError::TooShort(slice.len()));
+        }
+        let mut number = [0u8; 8];
+        number.copy_from_slice(&slice[0..8]);
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&slice[8..40]);
+        let mut total_difficulty = [0u8; 8];
+        total_difficulty.copy_from_slice(&slice[40..48]);
+        let mut mmr_root = [0u8; 32];
+        mmr_root.copy_from_slice(&slice[48..80]);
+        let mut finalized_number = [0u8; 8];
+        finalized_number.copy_from_slice(&slice[80..88]);
+        Ok(ChainDigest {
+            number: u64::from_le_bytes(number),
+            hash,
+            total_difficulty: u64::from_le_bytes(total_difficulty),
+            mmr_root,
+            finalized_number: u64::from_le_bytes(finalized_number),
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; DIGEST_LEN] {
+        let mut ret = [0u8; DIGEST_LEN];
+        ret[0..8].copy_from_slice(&self.number.to_le_bytes());
+        ret[8..40].copy_from_slice(&self.hash);
+        ret[40..48].copy_from_slice(&self.total_difficulty.to_le_bytes());
+        ret[48..80].copy_from_slice(&self.mmr_root);
+        ret[80..88].copy_from_slice(&self.finalized_number.to_le_bytes());
+        ret
+    }
+}
+
+#[derive(Debug)]
+pub enum CellDataError {
+    TooShort(usize),
+    Molecule(VerificationError),
+}
+
+impl fmt::Display for CellDataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CellDataError::TooShort(len) => write!(f, "cell data is {} bytes, shorter than the digest", len),
+            CellDataError::Molecule(err) => write!(f, "invalid cell data: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CellDataError {}
+
+/// Split the client cell data into the digest and the molecule encoded `CellData`.
+pub fn parse_cell_data(data: &[u8]) -> Result<(ChainDigest, CellData), CellDataError> {
+    let digest = ChainDigest::from_slice(data)?;
+    let cell_data = CellData::from_slice(&data[DIGEST_LEN..]).map_err(CellDataError::Molecule)?;
+    Ok((digest, cell_data))
+}
+
+/// Serialize the client cell data, the digest followed by the molecule encoded `CellData`.
+pub fn build_cell_data(digest: &ChainDigest, cell_data: &CellData) -> Vec<u8> {
+    let mut ret = digest.to_bytes().to_vec();
+    ret.extend_from_slice(cell_data.as_slice());
+    ret
+}
+
+/// The finalized number of the digest, the number of the newest main chain header with at least
+/// the finality weight of the config accumulated on top of it.
+///
+/// The difficulty of the header itself is not counted. It is 0 while no cached header is heavy
+/// enough, the contract rejects any other value.
+pub fn finalized_number(cell_data: &CellData, tail_number: u64, tail_total_difficulty: u64) -> Result<u64, CellDataError> {
+    let finality_weight = u64::from_le_bytes(cell_data.config().finality_weight().as_slice().try_into().expect("Uint64 is 8 bytes"));
+    let main = cell_data.headers().main();
+    for depth in 0..main.len() {
+        let item = main.get(main.len() - 1 - depth).expect("index in bounds");
+        let header_info = HeaderInfo::from_slice(&item.raw_data()).map_err(CellDataError::Molecule)?;
+        let total_difficulty = u64::from_le_bytes(header_info.total_difficulty().as_slice().try_into().expect("Uint64 is 8 bytes"));
+        if tail_total_difficulty.saturating_sub(total_difficulty) >= finality_weight {
+            return Ok(tail_number - depth as u64);
+        }
+    }
+    Ok(0)
+}
+
+/// One byte of a cell occupies one CKB, 10^8 shannons.
+pub const SHANNONS_PER_BYTE: u64 = 100_000_000;
+
+/// The minimal capacity in shannons of a cell with `data_len` bytes of data, a lock script with
+/// `lock_args_len` bytes of args and an optional type script with `type_args_len` bytes of args.
+///
+/// The capacity field takes 8 bytes, a script takes its 32 bytes code hash, 1 byte hash type and
+/// its args, as counted by CKB.
+pub fn occupied_capacity(data_len: usize, lock_args_len: usize, type_args_len: Option<usize>) -> u64 {
+    let script_len = |args_len: usize| 32 + 1 + args_len;
+    let bytes = 8 + script_len(lock_args_len) + type_args_len.map_or(0, script_len) + data_len;
+    bytes as u64 * SHANNONS_PER_BYTE
+}
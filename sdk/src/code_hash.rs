@@ -0,0 +1,5 @@
+//! Generated by `cargo xtask build --update`, do not edit.
+
+/// The data hash of the pinned release binary of the contract, `None` until a release
+/// is pinned in `contracts/eth-client/release.manifest`.
+pub const ETH_CLIENT_DATA_HASH: Option<[u8; 32]> = None;
@@ -0,0 +1,195 @@
+//! Decode the logs of ethereum receipts into typed events, and build the receipt proof
+//! witness which `eth_client_reader` verifies.
+//!
+//! A bridge lock script calls `eth_client_reader::verify_log` with a filter on the
+//! emitting contract and the event topic, then decodes the data of the log. The same
+//! filter and decoding are done off-chain with [`EthEvent`].
+
+use crate::types::{
+    basic::{Byte32, BytesVec},
+    witness::{AncestryProof, ReceiptProof},
+};
+use ethabi::{Event, RawLog, Token};
+use molecule::prelude::*;
+use std::fmt;
+
+/// A log of a receipt, as emitted by the `address` contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceiptLog {
+    pub address: [u8; 20],
+    pub topics: Vec<[u8; 32]>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum EventError {
+    InvalidReceipt,
+    /// The receipt is an eip-2718 envelope of a type the contract doesn't accept.
+    UnsupportedReceiptType(u8),
+    /// The log is not the event, e.g. another event of the contract.
+    SignatureMismatch,
+    Abi(ethabi::Error),
+    /// A param of the event is missing or has another type.
+    InvalidParam(String),
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventError::InvalidReceipt => write!(f, "invalid receipt rlp"),
+            EventError::UnsupportedReceiptType(receipt_type) => write!(f, "unsupported receipt type {}", receipt_type),
+            EventError::SignatureMismatch => write!(f, "the log is not the expected event"),
+            EventError::Abi(err) => write!(f, "{}", err),
+            EventError::InvalidParam(name) => write!(f, "invalid event param {}", name),
+        }
+    }
+}
+
+impl std::error::Error for EventError {}
+
+impl From<ethabi::Error> for EventError {
+    fn from(err: ethabi::Error) -> Self {
+        EventError::Abi(err)
+    }
+}
+
+/// An event which bridges listen to.
+///
+/// ``` ignore
+/// struct Locked { recipient: Vec<u8>, amount: Uint }
+///
+/// impl EthEvent for Locked {
+///     fn event() -> Event {
+///         // e.g. `Contract::load(abi_json)?.event("Locked")?.clone()`
+///     }
+///
+///     fn from_tokens(mut tokens: EventTokens) -> Result<Self, EventError> {
+///         Ok(Locked {
+///             recipient: tokens.take("recipient")?.to_bytes().ok_or_else(|| tokens.invalid("recipient"))?,
+///             amount: tokens.take("amount")?.to_uint().ok_or_else(|| tokens.invalid("amount"))?,
+///         })
+///     }
+/// }
+/// ```
+pub trait EthEvent: Sized {
+    /// The ABI of the event.
+    fn event() -> Event;
+
+    fn from_tokens(tokens: EventTokens) -> Result<Self, EventError>;
+
+    /// The topics a log of the event starts with, i.e. the `topics` of the `LogFilter` of
+    /// `eth_client_reader`. Anonymous events have no signature topic.
+    fn filter_topics() -> Vec<[u8; 32]> {
+        let event = Self::event();
+        if event.anonymous {
+            return vec![];
+        }
+        vec![event.signature().0]
+    }
+
+    /// Decode a log, the signature topic must match.
+    fn decode(log: &ReceiptLog) -> Result<Self, EventError> {
+        let event = Self::event();
+        if !event.anonymous && log.topics.first() != Some(&event.signature().0) {
+            return Err(EventError::SignatureMismatch);
+        }
+        let decoded = event.parse_log(RawLog {
+            topics: log.topics.iter().map(|topic| topic.into()).collect(),
+            data: log.data.clone(),
+        })?;
+        Self::from_tokens(EventTokens(
+            decoded.params.into_iter().map(|param| (param.name, param.value)).collect(),
+        ))
+    }
+}
+
+/// The decoded params of an event, by name.
+pub struct EventTokens(Vec<(String, Token)>);
+
+impl EventTokens {
+    pub fn take(&mut self, name: &str) -> Result<Token, EventError> {
+        let index = self
+            .0
+            .iter()
+            .position(|(param, _)| param == name)
+            .ok_or_else(|| self.invalid(name))?;
+        Ok(self.0.remove(index).1)
+    }
+
+    pub fn invalid(&self, name: &str) -> EventError {
+        EventError::InvalidParam(name.to_string())
+    }
+}
+
+pub use eth_client_constants::RECEIPT_TYPES;
+
+/// The logs of a receipt, `rlp([status, cumulative_gas_used, logs_bloom, logs])`.
+///
+/// Typed receipts are the type byte followed by the same list, as they are stored in the
+/// receipts trie.
+pub fn receipt_logs(receipt_rlp: &[u8]) -> Result<Vec<ReceiptLog>, EventError> {
+    let receipt_rlp = match receipt_rlp.first() {
+        Some(first) if *first >= 0xc0 => receipt_rlp,
+        Some(first) if RECEIPT_TYPES.contains(first) => &receipt_rlp[1..],
+        Some(first) => return Err(EventError::UnsupportedReceiptType(*first)),
+        None => return Err(EventError::InvalidReceipt),
+    };
+    let receipt = rlp::Rlp::new(receipt_rlp);
+    let logs = receipt.at(3).map_err(|_| EventError::InvalidReceipt)?;
+    logs.iter()
+        .map(|log| {
+            let address: Vec<u8> = log.val_at(0).map_err(|_| EventError::InvalidReceipt)?;
+            let topics: Vec<Vec<u8>> = log.list_at(1).map_err(|_| EventError::InvalidReceipt)?;
+            Ok(ReceiptLog {
+                address: fixed_bytes(&address)?,
+                topics: topics.iter().map(|topic| fixed_bytes(topic)).collect::<Result<_, _>>()?,
+                data: log.val_at(2).map_err(|_| EventError::InvalidReceipt)?,
+            })
+        })
+        .collect()
+}
+
+/// Decode every log of the receipt which is the event `E` emitted by `address`.
+pub fn decode_receipt_events<E: EthEvent>(receipt_rlp: &[u8], address: &[u8; 20]) -> Result<Vec<(usize, E)>, EventError> {
+    let topics = E::filter_topics();
+    receipt_logs(receipt_rlp)?
+        .iter()
+        .enumerate()
+        .filter(|(_, log)| log.address == *address && log.topics.starts_with(&topics))
+        .map(|(log_index, log)| Ok((log_index, E::decode(log)?)))
+        .collect()
+}
+
+/// Build the `ReceiptProof` witness of the `log_index`th log of the receipt of the `tx_index`th
+/// transaction in the block. `proof` is the list of trie nodes from the receipts root to the receipt.
+pub fn build_receipt_proof(
+    block_hash: [u8; 32],
+    block_number: u64,
+    tx_index: u64,
+    proof: &[Vec<u8>],
+    log_index: u64,
+) -> ReceiptProof {
+    let header = AncestryProof::new_builder()
+        .hash(Byte32::from_slice(&block_hash).expect("32 bytes"))
+        .number(block_number.into())
+        .build();
+    ReceiptProof::new_builder()
+        .header(header)
+        .tx_index(tx_index.into())
+        .proof(
+            BytesVec::new_builder()
+                .set(proof.iter().map(|node| node.clone().into()).collect())
+                .build(),
+        )
+        .log_index(log_index.into())
+        .build()
+}
+
+fn fixed_bytes<T: Default + AsMut<[u8]>>(data: &[u8]) -> Result<T, EventError> {
+    let mut ret = T::default();
+    if ret.as_mut().len() != data.len() {
+        return Err(EventError::InvalidReceipt);
+    }
+    ret.as_mut().copy_from_slice(data);
+    Ok(ret)
+}
@@ -0,0 +1,127 @@
+//! The json files the relayer and the tests read, produced by external tools like ethashproof.
+//!
+//! The formats are versioned. A file without `version` is version 1, the output of ethashproof.
+//! Unknown fields are rejected when parsing, and `validate` checks the lengths and the proof shape,
+//! so a malformed file fails before it is turned into a witness.
+
+use crate::relay::BlockWithProofs;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub use eth_client_constants::ETHASH_ACCESSES;
+
+/// The fixture format version this sdk reads and writes.
+pub const FIXTURE_VERSION: u32 = 1;
+
+fn default_version() -> u32 {
+    FIXTURE_VERSION
+}
+
+/// A header with the proofs of its ethash dataset lookups, the numbers are hex strings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockWithProofsJson {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub header_rlp: String,
+    pub merkle_root: String,
+    pub elements: Vec<String>,
+    pub merkle_proofs: Vec<String>,
+    pub proof_length: u64,
+}
+
+/// The dags merkle roots of the contiguous epochs from epoch 0.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RootsCollectionJson {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub dag_merkle_roots: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixtureError {
+    UnsupportedVersion(u32),
+    InvalidWord { field: &'static str, word: String },
+    InvalidHeader,
+    InvalidProofShape { elements: usize, merkle_proofs: usize, proof_length: u64 },
+}
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FixtureError::UnsupportedVersion(version) => {
+                write!(f, "unsupported fixture version {}, expected {}", version, FIXTURE_VERSION)
+            }
+            FixtureError::InvalidWord { field, word } => write!(f, "invalid {} word: {}", field, word),
+            FixtureError::InvalidHeader => write!(f, "header_rlp is not a single rlp encoded header"),
+            FixtureError::InvalidProofShape { elements, merkle_proofs, proof_length } => write!(
+                f,
+                "{} elements and {} merkle proofs of length {} do not cover {} ethash accesses",
+                elements, merkle_proofs, proof_length, ETHASH_ACCESSES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {}
+
+/// Decode a hex word of at most `len` bytes, left padded with zeros as ethashproof drops them.
+fn decode_word(field: &'static str, word: &str, len: usize) -> Result<Vec<u8>, FixtureError> {
+    let digits = word.trim_start_matches("0x");
+    let invalid = || FixtureError::InvalidWord { field, word: word.to_string() };
+    if digits.len() > len * 2 {
+        return Err(invalid());
+    }
+    hex::decode(format!("{:0>width$}", digits, width = len * 2)).map_err(|_| invalid())
+}
+
+fn check_version(version: u32) -> Result<(), FixtureError> {
+    if version != FIXTURE_VERSION {
+        return Err(FixtureError::UnsupportedVersion(version));
+    }
+    Ok(())
+}
+
+impl BlockWithProofsJson {
+    /// Check the file and decode it for building the witness.
+    pub fn validate(&self) -> Result<BlockWithProofs, FixtureError> {
+        check_version(self.version)?;
+        let header_rlp = hex::decode(self.header_rlp.trim_start_matches("0x")).map_err(|_| FixtureError::InvalidHeader)?;
+        let header = rlp::Rlp::new(&header_rlp);
+        if !header.is_list() || header.payload_info().map(|info| info.total()) != Ok(header_rlp.len()) {
+            return Err(FixtureError::InvalidHeader);
+        }
+        decode_word("merkle_root", &self.merkle_root, 16)?;
+        let elements = self
+            .elements
+            .iter()
+            .map(|word| decode_word("elements", word, 32))
+            .collect::<Result<Vec<_>, _>>()?;
+        let merkle_proofs = self
+            .merkle_proofs
+            .iter()
+            .map(|word| decode_word("merkle_proofs", word, 16))
+            .collect::<Result<Vec<_>, _>>()?;
+        // each pair of dag nodes is 4 words of `elements`.
+        if elements.len() != ETHASH_ACCESSES * 4
+            || self.proof_length == 0
+            || merkle_proofs.len() as u64 != ETHASH_ACCESSES as u64 * self.proof_length
+        {
+            return Err(FixtureError::InvalidProofShape {
+                elements: elements.len(),
+                merkle_proofs: merkle_proofs.len(),
+                proof_length: self.proof_length,
+            });
+        }
+        Ok(BlockWithProofs { header_rlp, elements, merkle_proofs, proof_length: self.proof_length })
+    }
+}
+
+impl RootsCollectionJson {
+    /// Check the file and decode the 16 bytes roots.
+    pub fn validate(&self) -> Result<Vec<Vec<u8>>, FixtureError> {
+        check_version(self.version)?;
+        self.dag_merkle_roots.iter().map(|word| decode_word("dag_merkle_roots", word, 16)).collect()
+    }
+}
@@ -0,0 +1,12 @@
+//! Off-chain helpers for building transactions against the eth-client contract.
+
+pub mod batch;
+pub mod cell_data;
+pub mod code_hash;
+pub mod event;
+pub mod fixture;
+pub mod relay;
+pub mod relay_tx;
+pub mod types;
+pub mod unique_id;
+pub mod upgrade;
@@ -0,0 +1,270 @@
+use crate::cell_data::{build_cell_data, finalized_number, parse_cell_data, CellDataError, ChainDigest};
+use crate::types::{
+    basic::{Bytes, BytesVec, HeaderInfo},
+    cell_data::CellData,
+    double_node_with_merkle_proof::DoubleNodeWithMerkleProof,
+    witness::{Append, ChainEvent, Reorg, Unchanged, Witness},
+};
+use molecule::prelude::*;
+use std::{convert::TryInto, fmt};
+use tiny_keccak::{Hasher, Keccak};
+
+/// An ethereum header with the ethash proofs of its dataset lookups, in the json format
+/// of the ethashproof tool.
+///
+/// `elements` are 32 bytes words, two of them form a 64 bytes dag node. Every pair of
+/// dag nodes shares `proof_length` 16 bytes `merkle_proofs` against the epoch root.
+#[derive(Debug, Clone)]
+pub struct BlockWithProofs {
+    pub header_rlp: Vec<u8>,
+    pub elements: Vec<Vec<u8>>,
+    pub merkle_proofs: Vec<Vec<u8>>,
+    pub proof_length: u64,
+}
+
+impl BlockWithProofs {
+    /// Group the proofs into the `DoubleNodeWithMerkleProof` entries of the witness.
+    pub fn merkle_proofs(&self) -> Result<Vec<DoubleNodeWithMerkleProof>, RelayError> {
+        let proof_length = self.proof_length as usize;
+        let nodes: Vec<Vec<u8>> = self.elements.chunks(2).map(|pair| pair.concat()).collect();
+        if nodes.iter().any(|node| node.len() != 64) || nodes.len() % 2 != 0 {
+            return Err(RelayError::InvalidProof);
+        }
+        if proof_length == 0 || self.merkle_proofs.len() != nodes.len() / 2 * proof_length {
+            return Err(RelayError::InvalidProof);
+        }
+        Ok(nodes
+            .chunks(2)
+            .zip(self.merkle_proofs.chunks(proof_length))
+            .map(|(dag_nodes, proof)| {
+                DoubleNodeWithMerkleProof::new_builder()
+                    .dag_nodes(bytes_vec(dag_nodes))
+                    .proof(bytes_vec(proof))
+                    .build()
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug)]
+pub enum RelayError {
+    CellData(CellDataError),
+    InvalidHeader,
+    InvalidProof,
+    /// The header is not the child of the main chain tail.
+    NotTailChild,
+    DifficultyOverflow,
+    /// The input and the output main chains share no cached header.
+    NoCommonAncestor,
+}
+
+impl fmt::Display for RelayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RelayError::CellData(err) => write!(f, "{}", err),
+            RelayError::InvalidHeader => write!(f, "invalid header rlp"),
+            RelayError::InvalidProof => write!(f, "the dag nodes and merkle proofs do not pair up"),
+            RelayError::NotTailChild => write!(f, "the header does not extend the main chain tail"),
+            RelayError::DifficultyOverflow => write!(f, "the total difficulty overflows u64"),
+            RelayError::NoCommonAncestor => write!(f, "the input and the output main chains share no header"),
+        }
+    }
+}
+
+impl std::error::Error for RelayError {}
+
+impl From<CellDataError> for RelayError {
+    fn from(err: CellDataError) -> Self {
+        RelayError::CellData(err)
+    }
+}
+
+/// The keccak256 of the rlp encoded header, i.e. the ethereum block hash.
+pub fn header_hash(header_rlp: &[u8]) -> [u8; 32] {
+    let mut keccak = Keccak::v256();
+    keccak.update(header_rlp);
+    let mut ret = [0u8; 32];
+    keccak.finalize(&mut ret);
+    ret
+}
+
+/// Build the v1 witness submitting a single header on top of the main chain tail `expected_tail_hash`.
+pub fn build_witness(
+    header_rlp: &[u8],
+    merkle_proofs: &[DoubleNodeWithMerkleProof],
+    cell_dep_index: u8,
+    expected_tail_hash: [u8; 32],
+) -> Witness {
+    let proofs: Vec<Bytes> = merkle_proofs.iter().map(|proof| proof.as_slice().to_vec().into()).collect();
+    Witness::new_builder()
+        .header(header_rlp.to_vec().into())
+        .merkle_proof(BytesVec::new_builder().set(proofs).build())
+        .cell_dep_index_list(vec![cell_dep_index].into())
+        .expected_tail_hash(expected_tail_hash.to_vec().into())
+        .build()
+}
+
+/// The chain event of an update from the `input` to the `output` client cell data, which the
+/// contract requires as the `output_type` witness of the client cell.
+pub fn chain_event(input: &[u8], output: &[u8]) -> Result<ChainEvent, RelayError> {
+    let (input_digest, input_data) = parse_cell_data(input)?;
+    let (output_digest, output_data) = parse_cell_data(output)?;
+    if input_digest.hash == output_digest.hash {
+        return Ok(ChainEvent::new_builder().set(Unchanged::default()).build());
+    }
+    let input_main = main_hashes(&input_data)?;
+    let output_main = main_hashes(&output_data)?;
+    // the main chain numbers are contiguous up to the tail.
+    let hash_at = |main: &[Vec<u8>], tail: u64, number: u64| {
+        main.len().checked_sub(1 + (tail - number) as usize).map(|index| main[index].clone())
+    };
+    let mut number = input_digest.number.min(output_digest.number);
+    let depth = loop {
+        match (
+            hash_at(&input_main, input_digest.number, number),
+            hash_at(&output_main, output_digest.number, number),
+        ) {
+            (Some(input_hash), Some(output_hash)) if input_hash == output_hash => break input_digest.number - number,
+            (Some(_), Some(_)) if number > 0 => number -= 1,
+            _ => return Err(RelayError::NoCommonAncestor),
+        }
+    };
+    let event = if depth == 0 {
+        ChainEvent::new_builder()
+            .set(
+                Append::new_builder()
+                    .number(output_digest.number.into())
+                    .hash(output_digest.hash.to_vec().into())
+                    .build(),
+            )
+            .build()
+    } else {
+        ChainEvent::new_builder()
+            .set(
+                Reorg::new_builder()
+                    .depth(depth.into())
+                    .new_tail_number(output_digest.number.into())
+                    .new_tail_hash(output_digest.hash.to_vec().into())
+                    .build(),
+            )
+            .build()
+    };
+    Ok(event)
+}
+
+/// The hashes of the main chain headers, from the oldest to the tail.
+fn main_hashes(cell_data: &CellData) -> Result<Vec<Vec<u8>>, RelayError> {
+    cell_data
+        .headers()
+        .main()
+        .into_iter()
+        .map(|item| {
+            let header_info = HeaderInfo::from_slice(&item.raw_data()).map_err(|err| RelayError::CellData(CellDataError::Molecule(err)))?;
+            Ok(header_info.hash().raw_data().to_vec())
+        })
+        .collect()
+}
+
+/// Name the root of the dags merkle roots cell the witness is checked against, by its index
+/// in the cell. The contract fails with `EpochRootMismatch` instead of checking the proofs
+/// against another root when the cell is updated before the witness is committed.
+pub fn target_root_index(witness: Witness, root_index: u32) -> Witness {
+    let mut cell_dep_index_list = witness.cell_dep_index_list().raw_data()[..1].to_vec();
+    cell_dep_index_list.extend_from_slice(&root_index.to_le_bytes());
+    witness.as_builder().cell_dep_index_list(cell_dep_index_list.into()).build()
+}
+
+/// Append a header to the main chain of the client cell data and update the digest.
+///
+/// Only the linear extension is supported, the header must be the child of the main
+/// chain tail. The oldest main chain header is dropped when the cache is full, the uncle
+/// and the pending headers are kept as is. The update sequence is incremented.
+///
+/// The header is stamped with a ckb timestamp of 0, for a relay transaction without header
+/// deps, see `append_header_with_timestamp`.
+pub fn append_header(data: &[u8], header_rlp: &[u8]) -> Result<Vec<u8>, RelayError> {
+    append_header_with_timestamp(data, header_rlp, 0)
+}
+
+/// Append a header like `append_header`, stamped with the timestamp in milliseconds of the
+/// first header dep of the relay transaction.
+///
+/// The contract rejects the output when `ckb_timestamp` is not the timestamp of that block.
+pub fn append_header_with_timestamp(data: &[u8], header_rlp: &[u8], ckb_timestamp: u64) -> Result<Vec<u8>, RelayError> {
+    let (digest, cell_data) = parse_cell_data(data)?;
+    let header = rlp::Rlp::new(header_rlp);
+    let parent_hash: Vec<u8> = header.val_at(0).map_err(|_| RelayError::InvalidHeader)?;
+    let difficulty: u64 = header.val_at(7).map_err(|_| RelayError::InvalidHeader)?;
+    let number: u64 = header.val_at(8).map_err(|_| RelayError::InvalidHeader)?;
+    if parent_hash[..] != digest.hash[..] || Some(number) != digest.number.checked_add(1) {
+        return Err(RelayError::NotTailChild);
+    }
+    let total_difficulty = digest
+        .total_difficulty
+        .checked_add(difficulty)
+        .ok_or(RelayError::DifficultyOverflow)?;
+    let hash = header_hash(header_rlp);
+    let header_info = HeaderInfo::new_builder()
+        .header(header_rlp.to_vec().into())
+        .total_difficulty(total_difficulty.into())
+        .hash(hash.to_vec().into())
+        .ckb_timestamp(ckb_timestamp.into())
+        .build();
+
+    let headers = cell_data.headers();
+    let limit = u32::from_le_bytes(
+        cell_data.config().main_cache_limit().as_slice().try_into().expect("Uint32 is 4 bytes"),
+    ) as usize;
+    let mut main: Vec<Bytes> = headers.main().into_iter().collect();
+    main.push(header_info.as_slice().to_vec().into());
+    if main.len() > limit {
+        main.drain(..main.len() - limit);
+    }
+    let headers = headers.as_builder().main(BytesVec::new_builder().set(main).build()).build();
+    let update_sequence = u64::from_le_bytes(cell_data.update_sequence().as_slice().try_into().expect("Uint64 is 8 bytes"));
+    let cell_data = cell_data
+        .as_builder()
+        .headers(headers)
+        .update_sequence(update_sequence.saturating_add(1).into())
+        .build();
+    let digest = ChainDigest {
+        number,
+        hash,
+        total_difficulty,
+        mmr_root: digest.mmr_root,
+        finalized_number: finalized_number(&cell_data, number, total_difficulty)?,
+    };
+    Ok(build_cell_data(&digest, &cell_data))
+}
+
+/// The outcome of rebasing a header submission on a newer client cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rebase {
+    /// The output cell data appending the header to the new main chain tail.
+    Output(Vec<u8>),
+    /// The header is already on the new main chain, nothing is left to submit.
+    AlreadyRelayed,
+}
+
+/// Re-derive the output of a header submission after the client cell was updated by another
+/// relayer, from the data of the new live client cell.
+///
+/// A relayer whose transaction spent a dead client cell fetches the new live cell, rebases its
+/// submission with this, swaps the client input with `relay_tx::replace_client_input` and builds
+/// the witness again against the new main chain tail, the hash of the new digest. Fails with
+/// `NotTailChild` when the header no longer extends the new main chain, it is then submitted as
+/// a reorg or dropped.
+pub fn rebase_append(data: &[u8], header_rlp: &[u8]) -> Result<Rebase, RelayError> {
+    let (_, cell_data) = parse_cell_data(data)?;
+    let hash = header_hash(header_rlp);
+    if main_hashes(&cell_data)?.iter().any(|main_hash| main_hash[..] == hash[..]) {
+        return Ok(Rebase::AlreadyRelayed);
+    }
+    append_header(data, header_rlp).map(Rebase::Output)
+}
+
+fn bytes_vec(items: &[Vec<u8>]) -> BytesVec {
+    BytesVec::new_builder()
+        .set(items.iter().map(|item| item.clone().into()).collect())
+        .build()
+}
@@ -0,0 +1,202 @@
+use crate::cell_data::occupied_capacity;
+use crate::relay::build_witness;
+use crate::types::{double_node_with_merkle_proof::DoubleNodeWithMerkleProof, witness::ChainEvent};
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{DepType, TransactionBuilder, TransactionView},
+    packed::{self, CellDep, CellInput, OutPoint, WitnessArgs, WitnessArgsBuilder},
+    prelude::*,
+};
+use eth_client_constants::{MIN_UPDATE_INTERVAL_LEN, UNIQUE_ID_LEN};
+use molecule::prelude::Entity as _;
+use std::{convert::TryInto, fmt};
+
+#[derive(Debug, PartialEq)]
+pub enum RelayTxError {
+    /// The `DagsMerkleRoots` cell is not a cell dep of the transaction.
+    DagsMerkleRootsDepMissing,
+    /// The contract indexes the resolved cell deps, which are not known for dep groups.
+    DepGroupBeforeDagsMerkleRoots,
+    /// The contract takes the index as a single byte.
+    CellDepIndexTooLarge,
+    ClientInputMissing,
+    ClientOutputMissing,
+    InvalidWitnessArgs,
+}
+
+impl fmt::Display for RelayTxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RelayTxError::DagsMerkleRootsDepMissing => write!(f, "the dags merkle roots cell is not a cell dep"),
+            RelayTxError::DepGroupBeforeDagsMerkleRoots => {
+                write!(f, "a dep group precedes the dags merkle roots cell dep")
+            }
+            RelayTxError::CellDepIndexTooLarge => write!(f, "the dags merkle roots cell dep index exceeds 255"),
+            RelayTxError::ClientInputMissing => write!(f, "the client cell is not an input"),
+            RelayTxError::ClientOutputMissing => write!(f, "the client cell is not an output"),
+            RelayTxError::InvalidWitnessArgs => write!(f, "the witness of the client cell is not a WitnessArgs"),
+        }
+    }
+}
+
+impl std::error::Error for RelayTxError {}
+
+/// The live client cell spent by a relay transaction.
+#[derive(Debug, Clone)]
+pub struct ClientCell {
+    pub out_point: OutPoint,
+    pub output: packed::CellOutput,
+    pub data: Bytes,
+}
+
+/// The unsigned transaction skeleton updating the client cell to `output_data`.
+///
+/// It spends the client cell and recreates it with the same lock, type and capacity, with the
+/// `DagsMerkleRoots` cell and the contract cell as code deps, in that order. The since of the
+/// input is the min update interval of the client type args, if any. The witness, the chain event
+/// and the capacity are then set by `set_relay_witness`, `set_chain_event` and
+/// `top_up_client_capacity`. The relayer adds the fee inputs, the change output and the deps of
+/// their locks, and signs the transaction.
+pub fn relay_tx_skeleton(client: &ClientCell, contract: &OutPoint, dags_merkle_roots: &OutPoint, output_data: &[u8]) -> TransactionView {
+    let code_dep = |out_point: &OutPoint| CellDep::new_builder().out_point(out_point.clone()).dep_type(DepType::Code.into()).build();
+    TransactionBuilder::default()
+        .input(CellInput::new(client.out_point.clone(), min_update_since(&client.output)))
+        .cell_dep(code_dep(dags_merkle_roots))
+        .cell_dep(code_dep(contract))
+        .output(client.output.clone())
+        .output_data(Bytes::from(output_data.to_vec()).pack())
+        .build()
+}
+
+/// The relative block number since of the min update interval in the type args of the client cell,
+/// 0 when the client has none.
+fn min_update_since(output: &packed::CellOutput) -> u64 {
+    let args = match output.type_().to_opt() {
+        Some(script) => script.args().raw_data(),
+        None => return 0,
+    };
+    if args.len() < UNIQUE_ID_LEN + MIN_UPDATE_INTERVAL_LEN {
+        return 0;
+    }
+    let interval = u64::from_le_bytes(args[UNIQUE_ID_LEN..UNIQUE_ID_LEN + MIN_UPDATE_INTERVAL_LEN].try_into().expect("8 bytes"));
+    match interval {
+        0 => 0,
+        // the relative flag is the highest bit, the metric flags after it are 0 for block numbers.
+        interval => 0x8000_0000_0000_0000 | interval,
+    }
+}
+
+/// The index of the `DagsMerkleRoots` cell in the cell deps, as the contract loads it.
+///
+/// The dep groups are expanded when the transaction is verified, so only code deps may
+/// precede the `DagsMerkleRoots` cell, which must be a code dep itself.
+pub fn dags_merkle_roots_index(tx: &TransactionView, dags_merkle_roots: &OutPoint) -> Result<u8, RelayTxError> {
+    for (i, cell_dep) in tx.cell_deps().into_iter().enumerate() {
+        let is_code = cell_dep.dep_type() == DepType::Code.into();
+        if cell_dep.out_point().as_slice() == dags_merkle_roots.as_slice() && is_code {
+            return if i > u8::MAX as usize {
+                Err(RelayTxError::CellDepIndexTooLarge)
+            } else {
+                Ok(i as u8)
+            };
+        }
+        if !is_code {
+            return Err(RelayTxError::DepGroupBeforeDagsMerkleRoots);
+        }
+    }
+    Err(RelayTxError::DagsMerkleRootsDepMissing)
+}
+
+/// Put the witness of a single header submission into a transaction skeleton.
+///
+/// The transaction already spends the client cell at `client_out_point` and refers to the
+/// `DagsMerkleRoots` cell. The witness is the `input_type` of the `WitnessArgs` at the index
+/// of the client input, the lock and the output type of an existing `WitnessArgs` are kept.
+pub fn set_relay_witness(
+    tx: &TransactionView,
+    client_out_point: &OutPoint,
+    dags_merkle_roots: &OutPoint,
+    header_rlp: &[u8],
+    merkle_proofs: &[DoubleNodeWithMerkleProof],
+    expected_tail_hash: [u8; 32],
+) -> Result<TransactionView, RelayTxError> {
+    let cell_dep_index = dags_merkle_roots_index(tx, dags_merkle_roots)?;
+    let input_index = tx
+        .inputs()
+        .into_iter()
+        .position(|input| input.previous_output().as_slice() == client_out_point.as_slice())
+        .ok_or(RelayTxError::ClientInputMissing)?;
+    let witness = build_witness(header_rlp, merkle_proofs, cell_dep_index, expected_tail_hash);
+    edit_witness_args(tx, input_index, |witness_args| {
+        witness_args.input_type(Some(Bytes::from(witness.as_slice().to_vec())).pack())
+    })
+}
+
+/// Spend the live client cell `new_out_point` instead of the dead `old_out_point`, keeping the
+/// since of the input. The output data and the witnesses are left to the caller, see
+/// `relay::rebase_append`.
+pub fn replace_client_input(
+    tx: &TransactionView,
+    old_out_point: &OutPoint,
+    new_out_point: &OutPoint,
+) -> Result<TransactionView, RelayTxError> {
+    let mut inputs: Vec<packed::CellInput> = tx.inputs().into_iter().collect();
+    let input = inputs
+        .iter_mut()
+        .find(|input| input.previous_output().as_slice() == old_out_point.as_slice())
+        .ok_or(RelayTxError::ClientInputMissing)?;
+    *input = input.clone().as_builder().previous_output(new_out_point.clone()).build();
+    Ok(tx.as_advanced_builder().set_inputs(inputs).build())
+}
+
+/// Put the chain event of the update into the `output_type` of the `WitnessArgs` at the index
+/// of the client output, see `relay::chain_event`. The other fields are kept.
+pub fn set_chain_event(tx: &TransactionView, client_output_index: usize, event: &ChainEvent) -> Result<TransactionView, RelayTxError> {
+    edit_witness_args(tx, client_output_index, |witness_args| {
+        witness_args.output_type(Some(Bytes::from(event.as_slice().to_vec())).pack())
+    })
+}
+
+fn edit_witness_args(
+    tx: &TransactionView,
+    index: usize,
+    edit: impl FnOnce(WitnessArgsBuilder) -> WitnessArgsBuilder,
+) -> Result<TransactionView, RelayTxError> {
+    let mut witnesses: Vec<packed::Bytes> = tx.witnesses().into_iter().collect();
+    if witnesses.len() <= index {
+        witnesses.resize(index + 1, packed::Bytes::default());
+    }
+    let witness_data = witnesses[index].raw_data();
+    let witness_args = if witness_data.is_empty() {
+        WitnessArgs::default()
+    } else {
+        WitnessArgs::from_slice(&witness_data).map_err(|_| RelayTxError::InvalidWitnessArgs)?
+    };
+    witnesses[index] = edit(witness_args.as_builder()).build().as_bytes().pack();
+    Ok(tx.as_advanced_builder().set_witnesses(witnesses).build())
+}
+
+/// The minimal capacity of the client output cell `output` holding `data`, in shannons.
+pub fn min_client_capacity(output: &packed::CellOutput, data: &[u8]) -> u64 {
+    let type_args_len = output.type_().to_opt().map(|script| script.args().raw_data().len());
+    occupied_capacity(data.len(), output.lock().args().raw_data().len(), type_args_len)
+}
+
+/// Raise the capacity of the client output to the minimal capacity of its data, if it is lower.
+///
+/// The cell data grows with the cache limits and the schema, a relayer adds the difference before
+/// the update is rejected by CKB. Anyone may add capacity to the client cell, the relayer funds it
+/// from its own inputs and balances the change output after this call.
+pub fn top_up_client_capacity(tx: &TransactionView, client_output_index: usize) -> Result<TransactionView, RelayTxError> {
+    let mut outputs: Vec<packed::CellOutput> = tx.outputs().into_iter().collect();
+    let output = outputs.get(client_output_index).ok_or(RelayTxError::ClientOutputMissing)?;
+    let data = tx.outputs_data().get(client_output_index).ok_or(RelayTxError::ClientOutputMissing)?;
+    let capacity: u64 = output.capacity().unpack();
+    let min_capacity = min_client_capacity(output, &data.raw_data());
+    if capacity >= min_capacity {
+        return Ok(tx.clone());
+    }
+    let output = output.clone().as_builder().capacity(min_capacity.pack()).build();
+    outputs[client_output_index] = output;
+    Ok(tx.as_advanced_builder().set_outputs(outputs).build())
+}
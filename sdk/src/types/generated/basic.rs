@@ -0,0 +1,43 @@
+// Generated by Molecule 0.6.1
+
+use molecule :: prelude :: * ;
+# [ derive ( Clone ) ] pub struct Byte4 ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Byte4 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Byte4 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Byte4 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl :: core :: default :: Default for Byte4 { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , ] ; Byte4 :: new_unchecked ( v . into ( ) ) } } impl Byte4 { pub const TOTAL_SIZE : usize = 4 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 4 ; pub fn nth0 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 0 .. 1 ) ) } pub fn nth1 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 1 .. 2 ) ) } pub fn nth2 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 2 .. 3 ) ) } pub fn nth3 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 3 .. 4 ) ) } pub fn raw_data ( & self ) -> molecule :: bytes :: Bytes { self . as_bytes ( ) } pub fn as_reader < 'r > ( & 'r self ) -> Byte4Reader < 'r > { Byte4Reader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Byte4 { type Builder = Byte4Builder ; const NAME : & 'static str = "Byte4" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Byte4 ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Byte4Reader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Byte4Reader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . set ( [ self . nth0 ( ) , self . nth1 ( ) , self . nth2 ( ) , self . nth3 ( ) , ] ) } }
+# [ derive ( Clone , Copy ) ] pub struct Byte4Reader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for Byte4Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for Byte4Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for Byte4Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl < 'r > Byte4Reader < 'r > { pub const TOTAL_SIZE : usize = 4 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 4 ; pub fn nth0 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 0 .. 1 ] ) } pub fn nth1 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 1 .. 2 ] ) } pub fn nth2 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 2 .. 3 ] ) } pub fn nth3 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 3 .. 4 ] ) } pub fn raw_data ( & self ) -> & 'r [ u8 ] { self . as_slice ( ) } } impl < 'r > molecule :: prelude :: Reader < 'r > for Byte4Reader < 'r > { type Entity = Byte4 ; const NAME : & 'static str = "Byte4Reader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { Byte4Reader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , _compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len != Self :: TOTAL_SIZE { return ve ! ( Self , TotalSizeNotMatch , Self :: TOTAL_SIZE , slice_len ) ; } Ok ( ( ) ) } }
+pub struct Byte4Builder ( pub ( crate ) [ Byte ; 4 ] ) ; impl :: core :: fmt :: Debug for Byte4Builder { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:?})" , Self :: NAME , & self . 0 [ .. ] ) } } impl :: core :: default :: Default for Byte4Builder { fn default ( ) -> Self { Byte4Builder ( [ Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , ] ) } } impl Byte4Builder { pub const TOTAL_SIZE : usize = 4 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 4 ; pub fn set ( mut self , v : [ Byte ; 4 ] ) -> Self { self . 0 = v ; self } pub fn nth0 ( mut self , v : Byte ) -> Self { self . 0 [ 0 ] = v ; self } pub fn nth1 ( mut self , v : Byte ) -> Self { self . 0 [ 1 ] = v ; self } pub fn nth2 ( mut self , v : Byte ) -> Self { self . 0 [ 2 ] = v ; self } pub fn nth3 ( mut self , v : Byte ) -> Self { self . 0 [ 3 ] = v ; self } } impl molecule :: prelude :: Builder for Byte4Builder { type Entity = Byte4 ; const NAME : & 'static str = "Byte4Builder" ; fn expected_length ( & self ) -> usize { Self :: TOTAL_SIZE } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( self . 0 [ 0 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 1 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 2 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 3 ] . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Byte4 :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct Byte16 ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Byte16 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Byte16 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Byte16 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl :: core :: default :: Default for Byte16 { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; Byte16 :: new_unchecked ( v . into ( ) ) } } impl Byte16 { pub const TOTAL_SIZE : usize = 16 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 16 ; pub fn nth0 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 0 .. 1 ) ) } pub fn nth1 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 1 .. 2 ) ) } pub fn nth2 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 2 .. 3 ) ) } pub fn nth3 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 3 .. 4 ) ) } pub fn nth4 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 4 .. 5 ) ) } pub fn nth5 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 5 .. 6 ) ) } pub fn nth6 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 6 .. 7 ) ) } pub fn nth7 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 7 .. 8 ) ) } pub fn nth8 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 8 .. 9 ) ) } pub fn nth9 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 9 .. 10 ) ) } pub fn nth10 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 10 .. 11 ) ) } pub fn nth11 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 11 .. 12 ) ) } pub fn nth12 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 12 .. 13 ) ) } pub fn nth13 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 13 .. 14 ) ) } pub fn nth14 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 14 .. 15 ) ) } pub fn nth15 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 15 .. 16 ) ) } pub fn raw_data ( & self ) -> molecule :: bytes :: Bytes { self . as_bytes ( ) } pub fn as_reader < 'r > ( & 'r self ) -> Byte16Reader < 'r > { Byte16Reader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Byte16 { type Builder = Byte16Builder ; const NAME : & 'static str = "Byte16" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Byte16 ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Byte16Reader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Byte16Reader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . set ( [ self . nth0 ( ) , self . nth1 ( ) , self . nth2 ( ) , self . nth3 ( ) , self . nth4 ( ) , self . nth5 ( ) , self . nth6 ( ) , self . nth7 ( ) , self . nth8 ( ) , self . nth9 ( ) , self . nth10 ( ) , self . nth11 ( ) , self . nth12 ( ) , self . nth13 ( ) , self . nth14 ( ) , self . nth15 ( ) , ] ) } }
+# [ derive ( Clone , Copy ) ] pub struct Byte16Reader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for Byte16Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for Byte16Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for Byte16Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl < 'r > Byte16Reader < 'r > { pub const TOTAL_SIZE : usize = 16 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 16 ; pub fn nth0 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 0 .. 1 ] ) } pub fn nth1 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 1 .. 2 ] ) } pub fn nth2 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 2 .. 3 ] ) } pub fn nth3 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 3 .. 4 ] ) } pub fn nth4 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 4 .. 5 ] ) } pub fn nth5 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 5 .. 6 ] ) } pub fn nth6 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 6 .. 7 ] ) } pub fn nth7 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 7 .. 8 ] ) } pub fn nth8 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 8 .. 9 ] ) } pub fn nth9 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 9 .. 10 ] ) } pub fn nth10 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 10 .. 11 ] ) } pub fn nth11 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 11 .. 12 ] ) } pub fn nth12 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 12 .. 13 ] ) } pub fn nth13 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 13 .. 14 ] ) } pub fn nth14 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 14 .. 15 ] ) } pub fn nth15 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 15 .. 16 ] ) } pub fn raw_data ( & self ) -> & 'r [ u8 ] { self . as_slice ( ) } } impl < 'r > molecule :: prelude :: Reader < 'r > for Byte16Reader < 'r > { type Entity = Byte16 ; const NAME : & 'static str = "Byte16Reader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { Byte16Reader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , _compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len != Self :: TOTAL_SIZE { return ve ! ( Self , TotalSizeNotMatch , Self :: TOTAL_SIZE , slice_len ) ; } Ok ( ( ) ) } }
+pub struct Byte16Builder ( pub ( crate ) [ Byte ; 16 ] ) ; impl :: core :: fmt :: Debug for Byte16Builder { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:?})" , Self :: NAME , & self . 0 [ .. ] ) } } impl :: core :: default :: Default for Byte16Builder { fn default ( ) -> Self { Byte16Builder ( [ Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , ] ) } } impl Byte16Builder { pub const TOTAL_SIZE : usize = 16 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 16 ; pub fn set ( mut self , v : [ Byte ; 16 ] ) -> Self { self . 0 = v ; self } pub fn nth0 ( mut self , v : Byte ) -> Self { self . 0 [ 0 ] = v ; self } pub fn nth1 ( mut self , v : Byte ) -> Self { self . 0 [ 1 ] = v ; self } pub fn nth2 ( mut self , v : Byte ) -> Self { self . 0 [ 2 ] = v ; self } pub fn nth3 ( mut self , v : Byte ) -> Self { self . 0 [ 3 ] = v ; self } pub fn nth4 ( mut self , v : Byte ) -> Self { self . 0 [ 4 ] = v ; self } pub fn nth5 ( mut self , v : Byte ) -> Self { self . 0 [ 5 ] = v ; self } pub fn nth6 ( mut self , v : Byte ) -> Self { self . 0 [ 6 ] = v ; self } pub fn nth7 ( mut self , v : Byte ) -> Self { self . 0 [ 7 ] = v ; self } pub fn nth8 ( mut self , v : Byte ) -> Self { self . 0 [ 8 ] = v ; self } pub fn nth9 ( mut self , v : Byte ) -> Self { self . 0 [ 9 ] = v ; self } pub fn nth10 ( mut self , v : Byte ) -> Self { self . 0 [ 10 ] = v ; self } pub fn nth11 ( mut self , v : Byte ) -> Self { self . 0 [ 11 ] = v ; self } pub fn nth12 ( mut self , v : Byte ) -> Self { self . 0 [ 12 ] = v ; self } pub fn nth13 ( mut self , v : Byte ) -> Self { self . 0 [ 13 ] = v ; self } pub fn nth14 ( mut self , v : Byte ) -> Self { self . 0 [ 14 ] = v ; self } pub fn nth15 ( mut self , v : Byte ) -> Self { self . 0 [ 15 ] = v ; self } } impl molecule :: prelude :: Builder for Byte16Builder { type Entity = Byte16 ; const NAME : & 'static str = "Byte16Builder" ; fn expected_length ( & self ) -> usize { Self :: TOTAL_SIZE } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( self . 0 [ 0 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 1 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 2 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 3 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 4 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 5 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 6 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 7 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 8 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 9 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 10 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 11 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 12 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 13 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 14 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 15 ] . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Byte16 :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct Byte32 ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Byte32 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Byte32 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Byte32 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl :: core :: default :: Default for Byte32 { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; Byte32 :: new_unchecked ( v . into ( ) ) } } impl Byte32 { pub const TOTAL_SIZE : usize = 32 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 32 ; pub fn nth0 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 0 .. 1 ) ) } pub fn nth1 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 1 .. 2 ) ) } pub fn nth2 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 2 .. 3 ) ) } pub fn nth3 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 3 .. 4 ) ) } pub fn nth4 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 4 .. 5 ) ) } pub fn nth5 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 5 .. 6 ) ) } pub fn nth6 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 6 .. 7 ) ) } pub fn nth7 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 7 .. 8 ) ) } pub fn nth8 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 8 .. 9 ) ) } pub fn nth9 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 9 .. 10 ) ) } pub fn nth10 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 10 .. 11 ) ) } pub fn nth11 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 11 .. 12 ) ) } pub fn nth12 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 12 .. 13 ) ) } pub fn nth13 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 13 .. 14 ) ) } pub fn nth14 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 14 .. 15 ) ) } pub fn nth15 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 15 .. 16 ) ) } pub fn nth16 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 16 .. 17 ) ) } pub fn nth17 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 17 .. 18 ) ) } pub fn nth18 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 18 .. 19 ) ) } pub fn nth19 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 19 .. 20 ) ) } pub fn nth20 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 20 .. 21 ) ) } pub fn nth21 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 21 .. 22 ) ) } pub fn nth22 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 22 .. 23 ) ) } pub fn nth23 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 23 .. 24 ) ) } pub fn nth24 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 24 .. 25 ) ) } pub fn nth25 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 25 .. 26 ) ) } pub fn nth26 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 26 .. 27 ) ) } pub fn nth27 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 27 .. 28 ) ) } pub fn nth28 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 28 .. 29 ) ) } pub fn nth29 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 29 .. 30 ) ) } pub fn nth30 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 30 .. 31 ) ) } pub fn nth31 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 31 .. 32 ) ) } pub fn raw_data ( & self ) -> molecule :: bytes :: Bytes { self . as_bytes ( ) } pub fn as_reader < 'r > ( & 'r self ) -> Byte32Reader < 'r > { Byte32Reader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Byte32 { type Builder = Byte32Builder ; const NAME : & 'static str = "Byte32" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Byte32 ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Byte32Reader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Byte32Reader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . set ( [ self . nth0 ( ) , self . nth1 ( ) , self . nth2 ( ) , self . nth3 ( ) , self . nth4 ( ) , self . nth5 ( ) , self . nth6 ( ) , self . nth7 ( ) , self . nth8 ( ) , self . nth9 ( ) , self . nth10 ( ) , self . nth11 ( ) , self . nth12 ( ) , self . nth13 ( ) , self . nth14 ( ) , self . nth15 ( ) , self . nth16 ( ) , self . nth17 ( ) , self . nth18 ( ) , self . nth19 ( ) , self . nth20 ( ) , self . nth21 ( ) , self . nth22 ( ) , self . nth23 ( ) , self . nth24 ( ) , self . nth25 ( ) , self . nth26 ( ) , self . nth27 ( ) , self . nth28 ( ) , self . nth29 ( ) , self . nth30 ( ) , self . nth31 ( ) , ] ) } }
+# [ derive ( Clone , Copy ) ] pub struct Byte32Reader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for Byte32Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for Byte32Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for Byte32Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl < 'r > Byte32Reader < 'r > { pub const TOTAL_SIZE : usize = 32 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 32 ; pub fn nth0 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 0 .. 1 ] ) } pub fn nth1 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 1 .. 2 ] ) } pub fn nth2 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 2 .. 3 ] ) } pub fn nth3 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 3 .. 4 ] ) } pub fn nth4 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 4 .. 5 ] ) } pub fn nth5 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 5 .. 6 ] ) } pub fn nth6 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 6 .. 7 ] ) } pub fn nth7 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 7 .. 8 ] ) } pub fn nth8 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 8 .. 9 ] ) } pub fn nth9 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 9 .. 10 ] ) } pub fn nth10 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 10 .. 11 ] ) } pub fn nth11 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 11 .. 12 ] ) } pub fn nth12 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 12 .. 13 ] ) } pub fn nth13 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 13 .. 14 ] ) } pub fn nth14 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 14 .. 15 ] ) } pub fn nth15 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 15 .. 16 ] ) } pub fn nth16 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 16 .. 17 ] ) } pub fn nth17 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 17 .. 18 ] ) } pub fn nth18 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 18 .. 19 ] ) } pub fn nth19 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 19 .. 20 ] ) } pub fn nth20 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 20 .. 21 ] ) } pub fn nth21 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 21 .. 22 ] ) } pub fn nth22 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 22 .. 23 ] ) } pub fn nth23 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 23 .. 24 ] ) } pub fn nth24 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 24 .. 25 ] ) } pub fn nth25 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 25 .. 26 ] ) } pub fn nth26 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 26 .. 27 ] ) } pub fn nth27 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 27 .. 28 ] ) } pub fn nth28 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 28 .. 29 ] ) } pub fn nth29 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 29 .. 30 ] ) } pub fn nth30 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 30 .. 31 ] ) } pub fn nth31 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 31 .. 32 ] ) } pub fn raw_data ( & self ) -> & 'r [ u8 ] { self . as_slice ( ) } } impl < 'r > molecule :: prelude :: Reader < 'r > for Byte32Reader < 'r > { type Entity = Byte32 ; const NAME : & 'static str = "Byte32Reader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { Byte32Reader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , _compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len != Self :: TOTAL_SIZE { return ve ! ( Self , TotalSizeNotMatch , Self :: TOTAL_SIZE , slice_len ) ; } Ok ( ( ) ) } }
+pub struct Byte32Builder ( pub ( crate ) [ Byte ; 32 ] ) ; impl :: core :: fmt :: Debug for Byte32Builder { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:?})" , Self :: NAME , & self . 0 [ .. ] ) } } impl :: core :: default :: Default for Byte32Builder { fn default ( ) -> Self { Byte32Builder ( [ Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , ] ) } } impl Byte32Builder { pub const TOTAL_SIZE : usize = 32 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 32 ; pub fn set ( mut self , v : [ Byte ; 32 ] ) -> Self { self . 0 = v ; self } pub fn nth0 ( mut self , v : Byte ) -> Self { self . 0 [ 0 ] = v ; self } pub fn nth1 ( mut self , v : Byte ) -> Self { self . 0 [ 1 ] = v ; self } pub fn nth2 ( mut self , v : Byte ) -> Self { self . 0 [ 2 ] = v ; self } pub fn nth3 ( mut self , v : Byte ) -> Self { self . 0 [ 3 ] = v ; self } pub fn nth4 ( mut self , v : Byte ) -> Self { self . 0 [ 4 ] = v ; self } pub fn nth5 ( mut self , v : Byte ) -> Self { self . 0 [ 5 ] = v ; self } pub fn nth6 ( mut self , v : Byte ) -> Self { self . 0 [ 6 ] = v ; self } pub fn nth7 ( mut self , v : Byte ) -> Self { self . 0 [ 7 ] = v ; self } pub fn nth8 ( mut self , v : Byte ) -> Self { self . 0 [ 8 ] = v ; self } pub fn nth9 ( mut self , v : Byte ) -> Self { self . 0 [ 9 ] = v ; self } pub fn nth10 ( mut self , v : Byte ) -> Self { self . 0 [ 10 ] = v ; self } pub fn nth11 ( mut self , v : Byte ) -> Self { self . 0 [ 11 ] = v ; self } pub fn nth12 ( mut self , v : Byte ) -> Self { self . 0 [ 12 ] = v ; self } pub fn nth13 ( mut self , v : Byte ) -> Self { self . 0 [ 13 ] = v ; self } pub fn nth14 ( mut self , v : Byte ) -> Self { self . 0 [ 14 ] = v ; self } pub fn nth15 ( mut self , v : Byte ) -> Self { self . 0 [ 15 ] = v ; self } pub fn nth16 ( mut self , v : Byte ) -> Self { self . 0 [ 16 ] = v ; self } pub fn nth17 ( mut self , v : Byte ) -> Self { self . 0 [ 17 ] = v ; self } pub fn nth18 ( mut self , v : Byte ) -> Self { self . 0 [ 18 ] = v ; self } pub fn nth19 ( mut self , v : Byte ) -> Self { self . 0 [ 19 ] = v ; self } pub fn nth20 ( mut self , v : Byte ) -> Self { self . 0 [ 20 ] = v ; self } pub fn nth21 ( mut self , v : Byte ) -> Self { self . 0 [ 21 ] = v ; self } pub fn nth22 ( mut self , v : Byte ) -> Self { self . 0 [ 22 ] = v ; self } pub fn nth23 ( mut self , v : Byte ) -> Self { self . 0 [ 23 ] = v ; self } pub fn nth24 ( mut self , v : Byte ) -> Self { self . 0 [ 24 ] = v ; self } pub fn nth25 ( mut self , v : Byte ) -> Self { self . 0 [ 25 ] = v ; self } pub fn nth26 ( mut self , v : Byte ) -> Self { self . 0 [ 26 ] = v ; self } pub fn nth27 ( mut self , v : Byte ) -> Self { self . 0 [ 27 ] = v ; self } pub fn nth28 ( mut self , v : Byte ) -> Self { self . 0 [ 28 ] = v ; self } pub fn nth29 ( mut self , v : Byte ) -> Self { self . 0 [ 29 ] = v ; self } pub fn nth30 ( mut self , v : Byte ) -> Self { self . 0 [ 30 ] = v ; self } pub fn nth31 ( mut self , v : Byte ) -> Self { self . 0 [ 31 ] = v ; self } } impl molecule :: prelude :: Builder for Byte32Builder { type Entity = Byte32 ; const NAME : & 'static str = "Byte32Builder" ; fn expected_length ( & self ) -> usize { Self :: TOTAL_SIZE } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( self . 0 [ 0 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 1 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 2 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 3 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 4 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 5 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 6 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 7 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 8 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 9 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 10 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 11 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 12 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 13 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 14 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 15 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 16 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 17 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 18 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 19 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 20 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 21 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 22 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 23 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 24 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 25 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 26 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 27 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 28 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 29 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 30 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 31 ] . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Byte32 :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct Bytes ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Bytes { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Bytes { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Bytes { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl :: core :: default :: Default for Bytes { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , ] ; Bytes :: new_unchecked ( v . into ( ) ) } } impl Bytes { pub const ITEM_SIZE : usize = 1 ; pub fn total_size ( & self ) -> usize { molecule :: NUMBER_SIZE * ( self . item_count ( ) + 1 ) } pub fn item_count ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < Byte > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> Byte { let start = molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * idx ; let end = start + Self :: ITEM_SIZE ; Byte :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn raw_data ( & self ) -> molecule :: bytes :: Bytes { self . 0 . slice ( molecule :: NUMBER_SIZE .. ) } pub fn as_reader < 'r > ( & 'r self ) -> BytesReader < 'r > { BytesReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Bytes { type Builder = BytesBuilder ; const NAME : & 'static str = "Bytes" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Bytes ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { BytesReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { BytesReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . extend ( self . into_iter ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct BytesReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for BytesReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for BytesReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for BytesReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl < 'r > BytesReader < 'r > { pub const ITEM_SIZE : usize = 1 ; pub fn total_size ( & self ) -> usize { molecule :: NUMBER_SIZE * ( self . item_count ( ) + 1 ) } pub fn item_count ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < ByteReader < 'r > > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> ByteReader < 'r > { let start = molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * idx ; let end = start + Self :: ITEM_SIZE ; ByteReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn raw_data ( & self ) -> & 'r [ u8 ] { & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] } } impl < 'r > molecule :: prelude :: Reader < 'r > for BytesReader < 'r > { type Entity = Bytes ; const NAME : & 'static str = "BytesReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { BytesReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , _compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let item_count = molecule :: unpack_number ( slice ) as usize ; if item_count == 0 { if slice_len != molecule :: NUMBER_SIZE { return ve ! ( Self , TotalSizeNotMatch , molecule :: NUMBER_SIZE , slice_len ) ; } return Ok ( ( ) ) ; } let total_size = molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * item_count ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct BytesBuilder ( pub ( crate ) Vec < Byte > ) ; impl BytesBuilder { pub const ITEM_SIZE : usize = 1 ; pub fn set ( mut self , v : Vec < Byte > ) -> Self { self . 0 = v ; self } pub fn push ( mut self , v : Byte ) -> Self { self . 0 . push ( v ) ; self } pub fn extend < T : :: core :: iter :: IntoIterator < Item = Byte >> ( mut self , iter : T ) -> Self { for elem in iter { self . 0 . push ( elem ) ; } self } } impl molecule :: prelude :: Builder for BytesBuilder { type Entity = Bytes ; const NAME : & 'static str = "BytesBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * self . 0 . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( & molecule :: pack_number ( self . 0 . len ( ) as molecule :: Number ) ) ? ; for inner in & self . 0 [ .. ] { writer . write_all ( inner . as_slice ( ) ) ? ; } Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Bytes :: new_unchecked ( inner . into ( ) ) } }
+pub struct BytesIterator ( Bytes , usize , usize ) ; impl :: core :: iter :: Iterator for BytesIterator { type Item = Byte ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl :: core :: iter :: ExactSizeIterator for BytesIterator { fn len ( & self ) -> usize { self . 2 - self . 1 } } impl :: core :: iter :: IntoIterator for Bytes { type Item = Byte ; type IntoIter = BytesIterator ; fn into_iter ( self ) -> Self :: IntoIter { let len = self . len ( ) ; BytesIterator ( self , 0 , len ) } }
+# [ derive ( Clone ) ] pub struct Uint32 ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Uint32 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Uint32 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Uint32 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl :: core :: default :: Default for Uint32 { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , ] ; Uint32 :: new_unchecked ( v . into ( ) ) } } impl Uint32 { pub const TOTAL_SIZE : usize = 4 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 4 ; pub fn nth0 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 0 .. 1 ) ) } pub fn nth1 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 1 .. 2 ) ) } pub fn nth2 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 2 .. 3 ) ) } pub fn nth3 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 3 .. 4 ) ) } pub fn raw_data ( & self ) -> molecule :: bytes :: Bytes { self . as_bytes ( ) } pub fn as_reader < 'r > ( & 'r self ) -> Uint32Reader < 'r > { Uint32Reader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Uint32 { type Builder = Uint32Builder ; const NAME : & 'static str = "Uint32" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Uint32 ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Uint32Reader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Uint32Reader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . set ( [ self . nth0 ( ) , self . nth1 ( ) , self . nth2 ( ) , self . nth3 ( ) , ] ) } }
+# [ derive ( Clone , Copy ) ] pub struct Uint32Reader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for Uint32Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for Uint32Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for Uint32Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl < 'r > Uint32Reader < 'r > { pub const TOTAL_SIZE : usize = 4 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 4 ; pub fn nth0 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 0 .. 1 ] ) } pub fn nth1 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 1 .. 2 ] ) } pub fn nth2 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 2 .. 3 ] ) } pub fn nth3 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 3 .. 4 ] ) } pub fn raw_data ( & self ) -> & 'r [ u8 ] { self . as_slice ( ) } } impl < 'r > molecule :: prelude :: Reader < 'r > for Uint32Reader < 'r > { type Entity = Uint32 ; const NAME : & 'static str = "Uint32Reader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { Uint32Reader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , _compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len != Self :: TOTAL_SIZE { return ve ! ( Self , TotalSizeNotMatch , Self :: TOTAL_SIZE , slice_len ) ; } Ok ( ( ) ) } }
+pub struct Uint32Builder ( pub ( crate ) [ Byte ; 4 ] ) ; impl :: core :: fmt :: Debug for Uint32Builder { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:?})" , Self :: NAME , & self . 0 [ .. ] ) } } impl :: core :: default :: Default for Uint32Builder { fn default ( ) -> Self { Uint32Builder ( [ Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , ] ) } } impl Uint32Builder { pub const TOTAL_SIZE : usize = 4 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 4 ; pub fn set ( mut self , v : [ Byte ; 4 ] ) -> Self { self . 0 = v ; self } pub fn nth0 ( mut self , v : Byte ) -> Self { self . 0 [ 0 ] = v ; self } pub fn nth1 ( mut self , v : Byte ) -> Self { self . 0 [ 1 ] = v ; self } pub fn nth2 ( mut self , v : Byte ) -> Self { self . 0 [ 2 ] = v ; self } pub fn nth3 ( mut self , v : Byte ) -> Self { self . 0 [ 3 ] = v ; self } } impl molecule :: prelude :: Builder for Uint32Builder { type Entity = Uint32 ; const NAME : & 'static str = "Uint32Builder" ; fn expected_length ( & self ) -> usize { Self :: TOTAL_SIZE } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( self . 0 [ 0 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 1 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 2 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 3 ] . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Uint32 :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct Uint64 ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Uint64 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Uint64 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Uint64 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl :: core :: default :: Default for Uint64 { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; Uint64 :: new_unchecked ( v . into ( ) ) } } impl Uint64 { pub const TOTAL_SIZE : usize = 8 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 8 ; pub fn nth0 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 0 .. 1 ) ) } pub fn nth1 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 1 .. 2 ) ) } pub fn nth2 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 2 .. 3 ) ) } pub fn nth3 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 3 .. 4 ) ) } pub fn nth4 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 4 .. 5 ) ) } pub fn nth5 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 5 .. 6 ) ) } pub fn nth6 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 6 .. 7 ) ) } pub fn nth7 ( & self ) -> Byte { Byte :: new_unchecked ( self . 0 . slice ( 7 .. 8 ) ) } pub fn raw_data ( & self ) -> molecule :: bytes :: Bytes { self . as_bytes ( ) } pub fn as_reader < 'r > ( & 'r self ) -> Uint64Reader < 'r > { Uint64Reader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Uint64 { type Builder = Uint64Builder ; const NAME : & 'static str = "Uint64" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Uint64 ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Uint64Reader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Uint64Reader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . set ( [ self . nth0 ( ) , self . nth1 ( ) , self . nth2 ( ) , self . nth3 ( ) , self . nth4 ( ) , self . nth5 ( ) , self . nth6 ( ) , self . nth7 ( ) , ] ) } }
+# [ derive ( Clone , Copy ) ] pub struct Uint64Reader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for Uint64Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for Uint64Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for Uint64Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; let raw_data = hex_string ( & self . raw_data ( ) ) ; write ! ( f , "{}(0x{})" , Self :: NAME , raw_data ) } } impl < 'r > Uint64Reader < 'r > { pub const TOTAL_SIZE : usize = 8 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 8 ; pub fn nth0 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 0 .. 1 ] ) } pub fn nth1 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 1 .. 2 ] ) } pub fn nth2 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 2 .. 3 ] ) } pub fn nth3 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 3 .. 4 ] ) } pub fn nth4 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 4 .. 5 ] ) } pub fn nth5 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 5 .. 6 ] ) } pub fn nth6 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 6 .. 7 ] ) } pub fn nth7 ( & self ) -> ByteReader < 'r > { ByteReader :: new_unchecked ( & self . as_slice ( ) [ 7 .. 8 ] ) } pub fn raw_data ( & self ) -> & 'r [ u8 ] { self . as_slice ( ) } } impl < 'r > molecule :: prelude :: Reader < 'r > for Uint64Reader < 'r > { type Entity = Uint64 ; const NAME : & 'static str = "Uint64Reader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { Uint64Reader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , _compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len != Self :: TOTAL_SIZE { return ve ! ( Self , TotalSizeNotMatch , Self :: TOTAL_SIZE , slice_len ) ; } Ok ( ( ) ) } }
+pub struct Uint64Builder ( pub ( crate ) [ Byte ; 8 ] ) ; impl :: core :: fmt :: Debug for Uint64Builder { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:?})" , Self :: NAME , & self . 0 [ .. ] ) } } impl :: core :: default :: Default for Uint64Builder { fn default ( ) -> Self { Uint64Builder ( [ Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , Byte :: default ( ) , ] ) } } impl Uint64Builder { pub const TOTAL_SIZE : usize = 8 ; pub const ITEM_SIZE : usize = 1 ; pub const ITEM_COUNT : usize = 8 ; pub fn set ( mut self , v : [ Byte ; 8 ] ) -> Self { self . 0 = v ; self } pub fn nth0 ( mut self , v : Byte ) -> Self { self . 0 [ 0 ] = v ; self } pub fn nth1 ( mut self , v : Byte ) -> Self { self . 0 [ 1 ] = v ; self } pub fn nth2 ( mut self , v : Byte ) -> Self { self . 0 [ 2 ] = v ; self } pub fn nth3 ( mut self , v : Byte ) -> Self { self . 0 [ 3 ] = v ; self } pub fn nth4 ( mut self , v : Byte ) -> Self { self . 0 [ 4 ] = v ; self } pub fn nth5 ( mut self , v : Byte ) -> Self { self . 0 [ 5 ] = v ; self } pub fn nth6 ( mut self , v : Byte ) -> Self { self . 0 [ 6 ] = v ; self } pub fn nth7 ( mut self , v : Byte ) -> Self { self . 0 [ 7 ] = v ; self } } impl molecule :: prelude :: Builder for Uint64Builder { type Entity = Uint64 ; const NAME : & 'static str = "Uint64Builder" ; fn expected_length ( & self ) -> usize { Self :: TOTAL_SIZE } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( self . 0 [ 0 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 1 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 2 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 3 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 4 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 5 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 6 ] . as_slice ( ) ) ? ; writer . write_all ( self . 0 [ 7 ] . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Uint64 :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct BytesVec ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for BytesVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for BytesVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for BytesVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} [" , Self :: NAME ) ? ; for i in 0 .. self . len ( ) { if i == 0 { write ! ( f , "{}" , self . get_unchecked ( i ) ) ? ; } else { write ! ( f , ", {}" , self . get_unchecked ( i ) ) ? ; } } write ! ( f , "]" ) } } impl :: core :: default :: Default for BytesVec { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 4 , 0 , 0 , 0 , ] ; BytesVec :: new_unchecked ( v . into ( ) ) } } impl BytesVec { pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn item_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < Bytes > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> Bytes { let slice = self . as_slice ( ) ; let start_idx = molecule :: NUMBER_SIZE * ( 1 + idx ) ; let start = molecule :: unpack_number ( & slice [ start_idx .. ] ) as usize ; if idx == self . len ( ) - 1 { Bytes :: new_unchecked ( self . 0 . slice ( start .. ) ) } else { let end_idx = start_idx + molecule :: NUMBER_SIZE ; let end = molecule :: unpack_number ( & slice [ end_idx .. ] ) as usize ; Bytes :: new_unchecked ( self . 0 . slice ( start .. end ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> BytesVecReader < 'r > { BytesVecReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for BytesVec { type Builder = BytesVecBuilder ; const NAME : & 'static str = "BytesVec" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { BytesVec ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { BytesVecReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { BytesVecReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . extend ( self . into_iter ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct BytesVecReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for BytesVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for BytesVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for BytesVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} [" , Self :: NAME ) ? ; for i in 0 .. self . len ( ) { if i == 0 { write ! ( f , "{}" , self . get_unchecked ( i ) ) ? ; } else { write ! ( f , ", {}" , self . get_unchecked ( i ) ) ? ; } } write ! ( f , "]" ) } } impl < 'r > BytesVecReader < 'r > { pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn item_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < BytesReader < 'r > > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> BytesReader < 'r > { let slice = self . as_slice ( ) ; let start_idx = molecule :: NUMBER_SIZE * ( 1 + idx ) ; let start = molecule :: unpack_number ( & slice [ start_idx .. ] ) as usize ; if idx == self . len ( ) - 1 { BytesReader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } else { let end_idx = start_idx + molecule :: NUMBER_SIZE ; let end = molecule :: unpack_number ( & slice [ end_idx .. ] ) as usize ; BytesReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for BytesVecReader < 'r > { type Entity = BytesVec ; const NAME : & 'static str = "BytesVecReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { BytesVecReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , TotalSizeNotMatch , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let item_count = offset_first / 4 - 1 ; let header_size = molecule :: NUMBER_SIZE * ( item_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( item_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } for pair in offsets . windows ( 2 ) { let start = pair [ 0 ] ; let end = pair [ 1 ] ; BytesReader :: verify ( & slice [ start .. end ] , compatible ) ? ; } Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct BytesVecBuilder ( pub ( crate ) Vec < Bytes > ) ; impl BytesVecBuilder { pub fn set ( mut self , v : Vec < Bytes > ) -> Self { self . 0 = v ; self } pub fn push ( mut self , v : Bytes ) -> Self { self . 0 . push ( v ) ; self } pub fn extend < T : :: core :: iter :: IntoIterator < Item = Bytes >> ( mut self , iter : T ) -> Self { for elem in iter { self . 0 . push ( elem ) ; } self } } impl molecule :: prelude :: Builder for BytesVecBuilder { type Entity = BytesVec ; const NAME : & 'static str = "BytesVecBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( self . 0 . len ( ) + 1 ) + self . 0 . iter ( ) . map ( | inner | inner . as_slice ( ) . len ( ) ) . sum :: < usize > ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W , ) -> :: molecule :: io :: Result < ( ) > { let item_count = self . 0 . len ( ) ; if item_count == 0 { writer . write_all ( & molecule :: pack_number ( molecule :: NUMBER_SIZE as molecule :: Number , ) ) ? ; } else { let ( total_size , offsets ) = self . 0 . iter ( ) . fold ( ( molecule :: NUMBER_SIZE * ( item_count + 1 ) , Vec :: with_capacity ( item_count ) , ) , | ( start , mut offsets ) , inner | { offsets . push ( start ) ; ( start + inner . as_slice ( ) . len ( ) , offsets ) } , ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } for inner in self . 0 . iter ( ) { writer . write_all ( inner . as_slice ( ) ) ? ; } } Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; BytesVec :: new_unchecked ( inner . into ( ) ) } }
+pub struct BytesVecIterator ( BytesVec , usize , usize ) ; impl :: core :: iter :: Iterator for BytesVecIterator { type Item = Bytes ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl :: core :: iter :: ExactSizeIterator for BytesVecIterator { fn len ( & self ) -> usize { self . 2 - self . 1 } } impl :: core :: iter :: IntoIterator for BytesVec { type Item = Bytes ; type IntoIter = BytesVecIterator ; fn into_iter ( self ) -> Self :: IntoIter { let len = self . len ( ) ; BytesVecIterator ( self , 0 , len ) } } impl < 'r > BytesVecReader < 'r > { pub fn iter < 't > ( & 't self ) -> BytesVecReaderIterator < 't , 'r > { BytesVecReaderIterator ( & self , 0 , self . len ( ) ) } } pub struct BytesVecReaderIterator < 't , 'r > ( & 't BytesVecReader < 'r > , usize , usize ) ; impl < 't : 'r , 'r > :: core :: iter :: Iterator for BytesVecReaderIterator < 't , 'r > { type Item = BytesReader < 't > ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl < 't : 'r , 'r > :: core :: iter :: ExactSizeIterator for BytesVecReaderIterator < 't , 'r > { fn len ( & self ) -> usize { self . 2 - self . 1 } }
+# [ derive ( Clone ) ] pub struct Byte32Vec ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Byte32Vec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Byte32Vec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Byte32Vec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} [" , Self :: NAME ) ? ; for i in 0 .. self . len ( ) { if i == 0 { write ! ( f , "{}" , self . get_unchecked ( i ) ) ? ; } else { write ! ( f , ", {}" , self . get_unchecked ( i ) ) ? ; } } write ! ( f , "]" ) } } impl :: core :: default :: Default for Byte32Vec { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , ] ; Byte32Vec :: new_unchecked ( v . into ( ) ) } } impl Byte32Vec { pub const ITEM_SIZE : usize = 32 ; pub fn total_size ( & self ) -> usize { molecule :: NUMBER_SIZE * ( self . item_count ( ) + 1 ) } pub fn item_count ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < Byte32 > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> Byte32 { let start = molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * idx ; let end = start + Self :: ITEM_SIZE ; Byte32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn as_reader < 'r > ( & 'r self ) -> Byte32VecReader < 'r > { Byte32VecReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Byte32Vec { type Builder = Byte32VecBuilder ; const NAME : & 'static str = "Byte32Vec" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Byte32Vec ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Byte32VecReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { Byte32VecReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . extend ( self . into_iter ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct Byte32VecReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for Byte32VecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for Byte32VecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for Byte32VecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} [" , Self :: NAME ) ? ; for i in 0 .. self . len ( ) { if i == 0 { write ! ( f , "{}" , self . get_unchecked ( i ) ) ? ; } else { write ! ( f , ", {}" , self . get_unchecked ( i ) ) ? ; } } write ! ( f , "]" ) } } impl < 'r > Byte32VecReader < 'r > { pub const ITEM_SIZE : usize = 32 ; pub fn total_size ( & self ) -> usize { molecule :: NUMBER_SIZE * ( self . item_count ( ) + 1 ) } pub fn item_count ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < Byte32Reader < 'r > > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> Byte32Reader < 'r > { let start = molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * idx ; let end = start + Self :: ITEM_SIZE ; Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } } impl < 'r > molecule :: prelude :: Reader < 'r > for Byte32VecReader < 'r > { type Entity = Byte32Vec ; const NAME : & 'static str = "Byte32VecReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { Byte32VecReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , _compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let item_count = molecule :: unpack_number ( slice ) as usize ; if item_count == 0 { if slice_len != molecule :: NUMBER_SIZE { return ve ! ( Self , TotalSizeNotMatch , molecule :: NUMBER_SIZE , slice_len ) ; } return Ok ( ( ) ) ; } let total_size = molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * item_count ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct Byte32VecBuilder ( pub ( crate ) Vec < Byte32 > ) ; impl Byte32VecBuilder { pub const ITEM_SIZE : usize = 32 ; pub fn set ( mut self , v : Vec < Byte32 > ) -> Self { self . 0 = v ; self } pub fn push ( mut self , v : Byte32 ) -> Self { self . 0 . push ( v ) ; self } pub fn extend < T : :: core :: iter :: IntoIterator < Item = Byte32 >> ( mut self , iter : T ) -> Self { for elem in iter { self . 0 . push ( elem ) ; } self } } impl molecule :: prelude :: Builder for Byte32VecBuilder { type Entity = Byte32Vec ; const NAME : & 'static str = "Byte32VecBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE + Self :: ITEM_SIZE * self . 0 . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( & molecule :: pack_number ( self . 0 . len ( ) as molecule :: Number ) ) ? ; for inner in & self . 0 [ .. ] { writer . write_all ( inner . as_slice ( ) ) ? ; } Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Byte32Vec :: new_unchecked ( inner . into ( ) ) } }
+pub struct Byte32VecIterator ( Byte32Vec , usize , usize ) ; impl :: core :: iter :: Iterator for Byte32VecIterator { type Item = Byte32 ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl :: core :: iter :: ExactSizeIterator for Byte32VecIterator { fn len ( & self ) -> usize { self . 2 - self . 1 } } impl :: core :: iter :: IntoIterator for Byte32Vec { type Item = Byte32 ; type IntoIter = Byte32VecIterator ; fn into_iter ( self ) -> Self :: IntoIter { let len = self . len ( ) ; Byte32VecIterator ( self , 0 , len ) } } impl < 'r > Byte32VecReader < 'r > { pub fn iter < 't > ( & 't self ) -> Byte32VecReaderIterator < 't , 'r > { Byte32VecReaderIterator ( & self , 0 , self . len ( ) ) } } pub struct Byte32VecReaderIterator < 't , 'r > ( & 't Byte32VecReader < 'r > , usize , usize ) ; impl < 't : 'r , 'r > :: core :: iter :: Iterator for Byte32VecReaderIterator < 't , 'r > { type Item = Byte32Reader < 't > ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl < 't : 'r , 'r > :: core :: iter :: ExactSizeIterator for Byte32VecReaderIterator < 't , 'r > { fn len ( & self ) -> usize { self . 2 - self . 1 } }
+# [ derive ( Clone ) ] pub struct Script ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Script { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Script { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Script { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "code_hash" , self . code_hash ( ) ) ? ; write ! ( f , ", {}: {}" , "hash_type" , self . hash_type ( ) ) ? ; write ! ( f , ", {}: {}" , "args" , self . args ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for Script { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 53 , 0 , 0 , 0 , 16 , 0 , 0 , 0 , 48 , 0 , 0 , 0 , 49 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; Script :: new_unchecked ( v . into ( ) ) } } impl Script { pub const FIELD_COUNT : usize = 3 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn code_hash ( & self ) -> Byte32 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Byte32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn hash_type ( & self ) -> Byte { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Byte :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn args ( & self ) -> Bytes { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Bytes :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { Bytes :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> ScriptReader < 'r > { ScriptReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Script { type Builder = ScriptBuilder ; const NAME : & 'static str = "Script" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Script ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ScriptReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ScriptReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . code_hash ( self . code_hash ( ) ) . hash_type ( self . hash_type ( ) ) . args ( self . args ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct ScriptReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for ScriptReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for ScriptReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for ScriptReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "code_hash" , self . code_hash ( ) ) ? ; write ! ( f , ", {}: {}" , "hash_type" , self . hash_type ( ) ) ? ; write ! ( f , ", {}: {}" , "args" , self . args ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > ScriptReader < 'r > { pub const FIELD_COUNT : usize = 3 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn code_hash ( & self ) -> Byte32Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn hash_type ( & self ) -> ByteReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; ByteReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn args ( & self ) -> BytesReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; BytesReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { BytesReader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for ScriptReader < 'r > { type Entity = Script ; const NAME : & 'static str = "ScriptReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { ScriptReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } Byte32Reader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; ByteReader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; BytesReader :: verify ( & slice [ offsets [ 2 ] .. offsets [ 3 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct ScriptBuilder { pub ( crate ) code_hash : Byte32 , pub ( crate ) hash_type : Byte , pub ( crate ) args : Bytes , } impl ScriptBuilder { pub const FIELD_COUNT : usize = 3 ; pub fn code_hash ( mut self , v : Byte32 ) -> Self { self . code_hash = v ; self } pub fn hash_type ( mut self , v : Byte ) -> Self { self . hash_type = v ; self } pub fn args ( mut self , v : Bytes ) -> Self { self . args = v ; self } } impl molecule :: prelude :: Builder for ScriptBuilder { type Entity = Script ; const NAME : & 'static str = "ScriptBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . code_hash . as_slice ( ) . len ( ) + self . hash_type . as_slice ( ) . len ( ) + self . args . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . code_hash . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . hash_type . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . args . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . code_hash . as_slice ( ) ) ? ; writer . write_all ( self . hash_type . as_slice ( ) ) ? ; writer . write_all ( self . args . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Script :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct HeaderInfo ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for HeaderInfo { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for HeaderInfo { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for HeaderInfo { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "header" , self . header ( ) ) ? ; write ! ( f , ", {}: {}" , "total_difficulty" , self . total_difficulty ( ) ) ? ; write ! ( f , ", {}: {}" , "hash" , self . hash ( ) ) ? ; write ! ( f , ", {}: {}" , "ckb_timestamp" , self . ckb_timestamp ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for HeaderInfo { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 72 , 0 , 0 , 0 , 20 , 0 , 0 , 0 , 24 , 0 , 0 , 0 , 32 , 0 , 0 , 0 , 64 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; HeaderInfo :: new_unchecked ( v . into ( ) ) } } impl HeaderInfo { pub const FIELD_COUNT : usize = 4 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn header ( & self ) -> Bytes { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Bytes :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn total_difficulty ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn hash ( & self ) -> Byte32 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Byte32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn ckb_timestamp ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { Uint64 :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> HeaderInfoReader < 'r > { HeaderInfoReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for HeaderInfo { type Builder = HeaderInfoBuilder ; const NAME : & 'static str = "HeaderInfo" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { HeaderInfo ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { HeaderInfoReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { HeaderInfoReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . header ( self . header ( ) ) . total_difficulty ( self . total_difficulty ( ) ) . hash ( self . hash ( ) ) . ckb_timestamp ( self . ckb_timestamp ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct HeaderInfoReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for HeaderInfoReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for HeaderInfoReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for HeaderInfoReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "header" , self . header ( ) ) ? ; write ! ( f , ", {}: {}" , "total_difficulty" , self . total_difficulty ( ) ) ? ; write ! ( f , ", {}: {}" , "hash" , self . hash ( ) ) ? ; write ! ( f , ", {}: {}" , "ckb_timestamp" , self . ckb_timestamp ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > HeaderInfoReader < 'r > { pub const FIELD_COUNT : usize = 4 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn header ( & self ) -> BytesReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; BytesReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn total_difficulty ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn hash ( & self ) -> Byte32Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn ckb_timestamp ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for HeaderInfoReader < 'r > { type Entity = HeaderInfo ; const NAME : & 'static str = "HeaderInfoReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { HeaderInfoReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } BytesReader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; Byte32Reader :: verify ( & slice [ offsets [ 2 ] .. offsets [ 3 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 3 ] .. offsets [ 4 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct HeaderInfoBuilder { pub ( crate ) header : Bytes , pub ( crate ) total_difficulty : Uint64 , pub ( crate ) hash : Byte32 , pub ( crate ) ckb_timestamp : Uint64 , } impl HeaderInfoBuilder { pub const FIELD_COUNT : usize = 4 ; pub fn header ( mut self , v : Bytes ) -> Self { self . header = v ; self } pub fn total_difficulty ( mut self , v : Uint64 ) -> Self { self . total_difficulty = v ; self } pub fn hash ( mut self , v : Byte32 ) -> Self { self . hash = v ; self } pub fn ckb_timestamp ( mut self , v : Uint64 ) -> Self { self . ckb_timestamp = v ; self } } impl molecule :: prelude :: Builder for HeaderInfoBuilder { type Entity = HeaderInfo ; const NAME : & 'static str = "HeaderInfoBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . header . as_slice ( ) . len ( ) + self . total_difficulty . as_slice ( ) . len ( ) + self . hash . as_slice ( ) . len ( ) + self . ckb_timestamp . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . header . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . total_difficulty . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . hash . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . ckb_timestamp . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . header . as_slice ( ) ) ? ; writer . write_all ( self . total_difficulty . as_slice ( ) ) ? ; writer . write_all ( self . hash . as_slice ( ) ) ? ; writer . write_all ( self . ckb_timestamp . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; HeaderInfo :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct HeaderInfoVec ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for HeaderInfoVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for HeaderInfoVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for HeaderInfoVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} [" , Self :: NAME ) ? ; for i in 0 .. self . len ( ) { if i == 0 { write ! ( f , "{}" , self . get_unchecked ( i ) ) ? ; } else { write ! ( f , ", {}" , self . get_unchecked ( i ) ) ? ; } } write ! ( f , "]" ) } } impl :: core :: default :: Default for HeaderInfoVec { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 4 , 0 , 0 , 0 , ] ; HeaderInfoVec :: new_unchecked ( v . into ( ) ) } } impl HeaderInfoVec { pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn item_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < HeaderInfo > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> HeaderInfo { let slice = self . as_slice ( ) ; let start_idx = molecule :: NUMBER_SIZE * ( 1 + idx ) ; let start = molecule :: unpack_number ( & slice [ start_idx .. ] ) as usize ; if idx == self . len ( ) - 1 { HeaderInfo :: new_unchecked ( self . 0 . slice ( start .. ) ) } else { let end_idx = start_idx + molecule :: NUMBER_SIZE ; let end = molecule :: unpack_number ( & slice [ end_idx .. ] ) as usize ; HeaderInfo :: new_unchecked ( self . 0 . slice ( start .. end ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> HeaderInfoVecReader < 'r > { HeaderInfoVecReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for HeaderInfoVec { type Builder = HeaderInfoVecBuilder ; const NAME : & 'static str = "HeaderInfoVec" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { HeaderInfoVec ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { HeaderInfoVecReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { HeaderInfoVecReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . extend ( self . into_iter ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct HeaderInfoVecReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for HeaderInfoVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for HeaderInfoVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for HeaderInfoVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} [" , Self :: NAME ) ? ; for i in 0 .. self . len ( ) { if i == 0 { write ! ( f , "{}" , self . get_unchecked ( i ) ) ? ; } else { write ! ( f , ", {}" , self . get_unchecked ( i ) ) ? ; } } write ! ( f , "]" ) } } impl < 'r > HeaderInfoVecReader < 'r > { pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn item_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < HeaderInfoReader < 'r > > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> HeaderInfoReader < 'r > { let slice = self . as_slice ( ) ; let start_idx = molecule :: NUMBER_SIZE * ( 1 + idx ) ; let start = molecule :: unpack_number ( & slice [ start_idx .. ] ) as usize ; if idx == self . len ( ) - 1 { HeaderInfoReader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } else { let end_idx = start_idx + molecule :: NUMBER_SIZE ; let end = molecule :: unpack_number ( & slice [ end_idx .. ] ) as usize ; HeaderInfoReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for HeaderInfoVecReader < 'r > { type Entity = HeaderInfoVec ; const NAME : & 'static str = "HeaderInfoVecReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { HeaderInfoVecReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , TotalSizeNotMatch , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let item_count = offset_first / 4 - 1 ; let header_size = molecule :: NUMBER_SIZE * ( item_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( item_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } for pair in offsets . windows ( 2 ) { let start = pair [ 0 ] ; let end = pair [ 1 ] ; HeaderInfoReader :: verify ( & slice [ start .. end ] , compatible ) ? ; } Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct HeaderInfoVecBuilder ( pub ( crate ) Vec < HeaderInfo > ) ; impl HeaderInfoVecBuilder { pub fn set ( mut self , v : Vec < HeaderInfo > ) -> Self { self . 0 = v ; self } pub fn push ( mut self , v : HeaderInfo ) -> Self { self . 0 . push ( v ) ; self } pub fn extend < T : :: core :: iter :: IntoIterator < Item = HeaderInfo >> ( mut self , iter : T ) -> Self { for elem in iter { self . 0 . push ( elem ) ; } self } } impl molecule :: prelude :: Builder for HeaderInfoVecBuilder { type Entity = HeaderInfoVec ; const NAME : & 'static str = "HeaderInfoVecBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( self . 0 . len ( ) + 1 ) + self . 0 . iter ( ) . map ( | inner | inner . as_slice ( ) . len ( ) ) . sum :: < usize > ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W , ) -> :: molecule :: io :: Result < ( ) > { let item_count = self . 0 . len ( ) ; if item_count == 0 { writer . write_all ( & molecule :: pack_number ( molecule :: NUMBER_SIZE as molecule :: Number , ) ) ? ; } else { let ( total_size , offsets ) = self . 0 . iter ( ) . fold ( ( molecule :: NUMBER_SIZE * ( item_count + 1 ) , Vec :: with_capacity ( item_count ) , ) , | ( start , mut offsets ) , inner | { offsets . push ( start ) ; ( start + inner . as_slice ( ) . len ( ) , offsets ) } , ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } for inner in self . 0 . iter ( ) { writer . write_all ( inner . as_slice ( ) ) ? ; } } Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; HeaderInfoVec :: new_unchecked ( inner . into ( ) ) } }
+pub struct HeaderInfoVecIterator ( HeaderInfoVec , usize , usize ) ; impl :: core :: iter :: Iterator for HeaderInfoVecIterator { type Item = HeaderInfo ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl :: core :: iter :: ExactSizeIterator for HeaderInfoVecIterator { fn len ( & self ) -> usize { self . 2 - self . 1 } } impl :: core :: iter :: IntoIterator for HeaderInfoVec { type Item = HeaderInfo ; type IntoIter = HeaderInfoVecIterator ; fn into_iter ( self ) -> Self :: IntoIter { let len = self . len ( ) ; HeaderInfoVecIterator ( self , 0 , len ) } } impl < 'r > HeaderInfoVecReader < 'r > { pub fn iter < 't > ( & 't self ) -> HeaderInfoVecReaderIterator < 't , 'r > { HeaderInfoVecReaderIterator ( & self , 0 , self . len ( ) ) } } pub struct HeaderInfoVecReaderIterator < 't , 'r > ( & 't HeaderInfoVecReader < 'r > , usize , usize ) ; impl < 't : 'r , 'r > :: core :: iter :: Iterator for HeaderInfoVecReaderIterator < 't , 'r > { type Item = HeaderInfoReader < 't > ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl < 't : 'r , 'r > :: core :: iter :: ExactSizeIterator for HeaderInfoVecReaderIterator < 't , 'r > { fn len ( & self ) -> usize { self . 2 - self . 1 } }
+# [ derive ( Clone ) ] pub struct Chain ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Chain { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Chain { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Chain { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "main" , self . main ( ) ) ? ; write ! ( f , ", {}: {}" , "uncle" , self . uncle ( ) ) ? ; write ! ( f , ", {}: {}" , "pending" , self . pending ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for Chain { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 28 , 0 , 0 , 0 , 16 , 0 , 0 , 0 , 20 , 0 , 0 , 0 , 24 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , ] ; Chain :: new_unchecked ( v . into ( ) ) } } impl Chain { pub const FIELD_COUNT : usize = 3 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn main ( & self ) -> BytesVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; BytesVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn uncle ( & self ) -> HeaderInfoVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; HeaderInfoVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn pending ( & self ) -> BytesVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; BytesVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { BytesVec :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> ChainReader < 'r > { ChainReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Chain { type Builder = ChainBuilder ; const NAME : & 'static str = "Chain" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Chain ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ChainReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ChainReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . main ( self . main ( ) ) . uncle ( self . uncle ( ) ) . pending ( self . pending ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct ChainReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for ChainReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for ChainReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for ChainReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "main" , self . main ( ) ) ? ; write ! ( f , ", {}: {}" , "uncle" , self . uncle ( ) ) ? ; write ! ( f , ", {}: {}" , "pending" , self . pending ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > ChainReader < 'r > { pub const FIELD_COUNT : usize = 3 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn main ( & self ) -> BytesVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn uncle ( & self ) -> HeaderInfoVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; HeaderInfoVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn pending ( & self ) -> BytesVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for ChainReader < 'r > { type Entity = Chain ; const NAME : & 'static str = "ChainReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { ChainReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } BytesVecReader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; HeaderInfoVecReader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; BytesVecReader :: verify ( & slice [ offsets [ 2 ] .. offsets [ 3 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct ChainBuilder { pub ( crate ) main : BytesVec , pub ( crate ) uncle : HeaderInfoVec , pub ( crate ) pending : BytesVec , } impl ChainBuilder { pub const FIELD_COUNT : usize = 3 ; pub fn main ( mut self , v : BytesVec ) -> Self { self . main = v ; self } pub fn uncle ( mut self , v : HeaderInfoVec ) -> Self { self . uncle = v ; self } pub fn pending ( mut self , v : BytesVec ) -> Self { self . pending = v ; self } } impl molecule :: prelude :: Builder for ChainBuilder { type Entity = Chain ; const NAME : & 'static str = "ChainBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . main . as_slice ( ) . len ( ) + self . uncle . as_slice ( ) . len ( ) + self . pending . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . main . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . uncle . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . pending . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . main . as_slice ( ) ) ? ; writer . write_all ( self . uncle . as_slice ( ) ) ? ; writer . write_all ( self . pending . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Chain :: new_unchecked ( inner . into ( ) ) } }
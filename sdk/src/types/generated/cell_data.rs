@@ -0,0 +1,13 @@
+// Generated by Molecule 0.6.1
+
+use molecule :: prelude :: * ;
+use super :: basic :: * ;
+# [ derive ( Clone ) ] pub struct RelayerSet ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for RelayerSet { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for RelayerSet { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for RelayerSet { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "lock_hashes" , self . lock_hashes ( ) ) ? ; write ! ( f , ", {}: {}" , "slot_length" , self . slot_length ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for RelayerSet { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 24 , 0 , 0 , 0 , 12 , 0 , 0 , 0 , 16 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; RelayerSet :: new_unchecked ( v . into ( ) ) } } impl RelayerSet { pub const FIELD_COUNT : usize = 2 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn lock_hashes ( & self ) -> Byte32Vec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Byte32Vec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn slot_length ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { Uint64 :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> RelayerSetReader < 'r > { RelayerSetReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for RelayerSet { type Builder = RelayerSetBuilder ; const NAME : & 'static str = "RelayerSet" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { RelayerSet ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { RelayerSetReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { RelayerSetReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . lock_hashes ( self . lock_hashes ( ) ) . slot_length ( self . slot_length ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct RelayerSetReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for RelayerSetReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for RelayerSetReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for RelayerSetReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "lock_hashes" , self . lock_hashes ( ) ) ? ; write ! ( f , ", {}: {}" , "slot_length" , self . slot_length ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > RelayerSetReader < 'r > { pub const FIELD_COUNT : usize = 2 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn lock_hashes ( & self ) -> Byte32VecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Byte32VecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn slot_length ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for RelayerSetReader < 'r > { type Entity = RelayerSet ; const NAME : & 'static str = "RelayerSetReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { RelayerSetReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } Byte32VecReader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct RelayerSetBuilder { pub ( crate ) lock_hashes : Byte32Vec , pub ( crate ) slot_length : Uint64 , } impl RelayerSetBuilder { pub const FIELD_COUNT : usize = 2 ; pub fn lock_hashes ( mut self , v : Byte32Vec ) -> Self { self . lock_hashes = v ; self } pub fn slot_length ( mut self , v : Uint64 ) -> Self { self . slot_length = v ; self } } impl molecule :: prelude :: Builder for RelayerSetBuilder { type Entity = RelayerSet ; const NAME : & 'static str = "RelayerSetBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . lock_hashes . as_slice ( ) . len ( ) + self . slot_length . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . lock_hashes . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . slot_length . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . lock_hashes . as_slice ( ) ) ? ; writer . write_all ( self . slot_length . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; RelayerSet :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct ClientConfig ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for ClientConfig { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for ClientConfig { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for ClientConfig { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "main_cache_limit" , self . main_cache_limit ( ) ) ? ; write ! ( f , ", {}: {}" , "uncle_cache_limit" , self . uncle_cache_limit ( ) ) ? ; write ! ( f , ", {}: {}" , "max_timestamp_gap" , self . max_timestamp_gap ( ) ) ? ; write ! ( f , ", {}: {}" , "max_future_drift" , self . max_future_drift ( ) ) ? ; write ! ( f , ", {}: {}" , "uncle_retention_depth" , self . uncle_retention_depth ( ) ) ? ; write ! ( f , ", {}: {}" , "max_reorg_depth" , self . max_reorg_depth ( ) ) ? ; write ! ( f , ", {}: {}" , "finality_weight" , self . finality_weight ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for ClientConfig { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 80 , 0 , 0 , 0 , 32 , 0 , 0 , 0 , 36 , 0 , 0 , 0 , 40 , 0 , 0 , 0 , 48 , 0 , 0 , 0 , 56 , 0 , 0 , 0 , 64 , 0 , 0 , 0 , 72 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; ClientConfig :: new_unchecked ( v . into ( ) ) } } impl ClientConfig { pub const FIELD_COUNT : usize = 7 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn main_cache_limit ( & self ) -> Uint32 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Uint32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn uncle_cache_limit ( & self ) -> Uint32 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn max_timestamp_gap ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn max_future_drift ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn uncle_retention_depth ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 24 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn max_reorg_depth ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 24 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 28 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn finality_weight ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 28 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 32 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { Uint64 :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> ClientConfigReader < 'r > { ClientConfigReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for ClientConfig { type Builder = ClientConfigBuilder ; const NAME : & 'static str = "ClientConfig" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { ClientConfig ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ClientConfigReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ClientConfigReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . main_cache_limit ( self . main_cache_limit ( ) ) . uncle_cache_limit ( self . uncle_cache_limit ( ) ) . max_timestamp_gap ( self . max_timestamp_gap ( ) ) . max_future_drift ( self . max_future_drift ( ) ) . uncle_retention_depth ( self . uncle_retention_depth ( ) ) . max_reorg_depth ( self . max_reorg_depth ( ) ) . finality_weight ( self . finality_weight ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct ClientConfigReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for ClientConfigReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for ClientConfigReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for ClientConfigReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "main_cache_limit" , self . main_cache_limit ( ) ) ? ; write ! ( f , ", {}: {}" , "uncle_cache_limit" , self . uncle_cache_limit ( ) ) ? ; write ! ( f , ", {}: {}" , "max_timestamp_gap" , self . max_timestamp_gap ( ) ) ? ; write ! ( f , ", {}: {}" , "max_future_drift" , self . max_future_drift ( ) ) ? ; write ! ( f , ", {}: {}" , "uncle_retention_depth" , self . uncle_retention_depth ( ) ) ? ; write ! ( f , ", {}: {}" , "max_reorg_depth" , self . max_reorg_depth ( ) ) ? ; write ! ( f , ", {}: {}" , "finality_weight" , self . finality_weight ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > ClientConfigReader < 'r > { pub const FIELD_COUNT : usize = 7 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn main_cache_limit ( & self ) -> Uint32Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Uint32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn uncle_cache_limit ( & self ) -> Uint32Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn max_timestamp_gap ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn max_future_drift ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn uncle_retention_depth ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 24 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn max_reorg_depth ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 24 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 28 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn finality_weight ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 28 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 32 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for ClientConfigReader < 'r > { type Entity = ClientConfig ; const NAME : & 'static str = "ClientConfigReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { ClientConfigReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } Uint32Reader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; Uint32Reader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 2 ] .. offsets [ 3 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 3 ] .. offsets [ 4 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 4 ] .. offsets [ 5 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 5 ] .. offsets [ 6 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 6 ] .. offsets [ 7 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct ClientConfigBuilder { pub ( crate ) main_cache_limit : Uint32 , pub ( crate ) uncle_cache_limit : Uint32 , pub ( crate ) max_timestamp_gap : Uint64 , pub ( crate ) max_future_drift : Uint64 , pub ( crate ) uncle_retention_depth : Uint64 , pub ( crate ) max_reorg_depth : Uint64 , pub ( crate ) finality_weight : Uint64 , } impl ClientConfigBuilder { pub const FIELD_COUNT : usize = 7 ; pub fn main_cache_limit ( mut self , v : Uint32 ) -> Self { self . main_cache_limit = v ; self } pub fn uncle_cache_limit ( mut self , v : Uint32 ) -> Self { self . uncle_cache_limit = v ; self } pub fn max_timestamp_gap ( mut self , v : Uint64 ) -> Self { self . max_timestamp_gap = v ; self } pub fn max_future_drift ( mut self , v : Uint64 ) -> Self { self . max_future_drift = v ; self } pub fn uncle_retention_depth ( mut self , v : Uint64 ) -> Self { self . uncle_retention_depth = v ; self } pub fn max_reorg_depth ( mut self , v : Uint64 ) -> Self { self . max_reorg_depth = v ; self } pub fn finality_weight ( mut self , v : Uint64 ) -> Self { self . finality_weight = v ; self } } impl molecule :: prelude :: Builder for ClientConfigBuilder { type Entity = ClientConfig ; const NAME : & 'static str = "ClientConfigBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . main_cache_limit . as_slice ( ) . len ( ) + self . uncle_cache_limit . as_slice ( ) . len ( ) + self . max_timestamp_gap . as_slice ( ) . len ( ) + self . max_future_drift . as_slice ( ) . len ( ) + self . uncle_retention_depth . as_slice ( ) . len ( ) + self . max_reorg_depth . as_slice ( ) . len ( ) + self . finality_weight . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . main_cache_limit . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . uncle_cache_limit . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . max_timestamp_gap . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . max_future_drift . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . uncle_retention_depth . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . max_reorg_depth . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . finality_weight . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . main_cache_limit . as_slice ( ) ) ? ; writer . write_all ( self . uncle_cache_limit . as_slice ( ) ) ? ; writer . write_all ( self . max_timestamp_gap . as_slice ( ) ) ? ; writer . write_all ( self . max_future_drift . as_slice ( ) ) ? ; writer . write_all ( self . uncle_retention_depth . as_slice ( ) ) ? ; writer . write_all ( self . max_reorg_depth . as_slice ( ) ) ? ; writer . write_all ( self . finality_weight . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; ClientConfig :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct CellData ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for CellData { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for CellData { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for CellData { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "headers" , self . headers ( ) ) ? ; write ! ( f , ", {}: {}" , "user_lockscript" , self . user_lockscript ( ) ) ? ; write ! ( f , ", {}: {}" , "paused" , self . paused ( ) ) ? ; write ! ( f , ", {}: {}" , "governance_lock_hash" , self . governance_lock_hash ( ) ) ? ; write ! ( f , ", {}: {}" , "relayers" , self . relayers ( ) ) ? ; write ! ( f , ", {}: {}" , "config" , self . config ( ) ) ? ; write ! ( f , ", {}: {}" , "update_sequence" , self . update_sequence ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for CellData { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 2 , 1 , 0 , 0 , 32 , 0 , 0 , 0 , 60 , 0 , 0 , 0 , 113 , 0 , 0 , 0 , 114 , 0 , 0 , 0 , 146 , 0 , 0 , 0 , 170 , 0 , 0 , 0 , 250 , 0 , 0 , 0 , 28 , 0 , 0 , 0 , 16 , 0 , 0 , 0 , 20 , 0 , 0 , 0 , 24 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , 53 , 0 , 0 , 0 , 16 , 0 , 0 , 0 , 48 , 0 , 0 , 0 , 49 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 24 , 0 , 0 , 0 , 12 , 0 , 0 , 0 , 16 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 80 , 0 , 0 , 0 , 32 , 0 , 0 , 0 , 36 , 0 , 0 , 0 , 40 , 0 , 0 , 0 , 48 , 0 , 0 , 0 , 56 , 0 , 0 , 0 , 64 , 0 , 0 , 0 , 72 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; CellData :: new_unchecked ( v . into ( ) ) } } impl CellData { pub const FIELD_COUNT : usize = 7 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn headers ( & self ) -> Chain { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Chain :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn user_lockscript ( & self ) -> Script { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Script :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn paused ( & self ) -> Byte { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Byte :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn governance_lock_hash ( & self ) -> Byte32 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; Byte32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn relayers ( & self ) -> RelayerSet { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 24 .. ] ) as usize ; RelayerSet :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn config ( & self ) -> ClientConfig { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 24 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 28 .. ] ) as usize ; ClientConfig :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn update_sequence ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 28 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 32 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { Uint64 :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> CellDataReader < 'r > { CellDataReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for CellData { type Builder = CellDataBuilder ; const NAME : & 'static str = "CellData" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { CellData ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { CellDataReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { CellDataReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . headers ( self . headers ( ) ) . user_lockscript ( self . user_lockscript ( ) ) . paused ( self . paused ( ) ) . governance_lock_hash ( self . governance_lock_hash ( ) ) . relayers ( self . relayers ( ) ) . config ( self . config ( ) ) . update_sequence ( self . update_sequence ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct CellDataReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for CellDataReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for CellDataReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for CellDataReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "headers" , self . headers ( ) ) ? ; write ! ( f , ", {}: {}" , "user_lockscript" , self . user_lockscript ( ) ) ? ; write ! ( f , ", {}: {}" , "paused" , self . paused ( ) ) ? ; write ! ( f , ", {}: {}" , "governance_lock_hash" , self . governance_lock_hash ( ) ) ? ; write ! ( f , ", {}: {}" , "relayers" , self . relayers ( ) ) ? ; write ! ( f , ", {}: {}" , "config" , self . config ( ) ) ? ; write ! ( f , ", {}: {}" , "update_sequence" , self . update_sequence ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > CellDataReader < 'r > { pub const FIELD_COUNT : usize = 7 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn headers ( & self ) -> ChainReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; ChainReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn user_lockscript ( & self ) -> ScriptReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; ScriptReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn paused ( & self ) -> ByteReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; ByteReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn governance_lock_hash ( & self ) -> Byte32Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn relayers ( & self ) -> RelayerSetReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 24 .. ] ) as usize ; RelayerSetReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn config ( & self ) -> ClientConfigReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 24 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 28 .. ] ) as usize ; ClientConfigReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn update_sequence ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 28 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 32 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for CellDataReader < 'r > { type Entity = CellData ; const NAME : & 'static str = "CellDataReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { CellDataReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } ChainReader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; ScriptReader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; ByteReader :: verify ( & slice [ offsets [ 2 ] .. offsets [ 3 ] ] , compatible ) ? ; Byte32Reader :: verify ( & slice [ offsets [ 3 ] .. offsets [ 4 ] ] , compatible ) ? ; RelayerSetReader :: verify ( & slice [ offsets [ 4 ] .. offsets [ 5 ] ] , compatible ) ? ; ClientConfigReader :: verify ( & slice [ offsets [ 5 ] .. offsets [ 6 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 6 ] .. offsets [ 7 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct CellDataBuilder { pub ( crate ) headers : Chain , pub ( crate ) user_lockscript : Script , pub ( crate ) paused : Byte , pub ( crate ) governance_lock_hash : Byte32 , pub ( crate ) relayers : RelayerSet , pub ( crate ) config : ClientConfig , pub ( crate ) update_sequence : Uint64 , } impl CellDataBuilder { pub const FIELD_COUNT : usize = 7 ; pub fn headers ( mut self , v : Chain ) -> Self { self . headers = v ; self } pub fn user_lockscript ( mut self , v : Script ) -> Self { self . user_lockscript = v ; self } pub fn paused ( mut self , v : Byte ) -> Self { self . paused = v ; self } pub fn governance_lock_hash ( mut self , v : Byte32 ) -> Self { self . governance_lock_hash = v ; self } pub fn relayers ( mut self , v : RelayerSet ) -> Self { self . relayers = v ; self } pub fn config ( mut self , v : ClientConfig ) -> Self { self . config = v ; self } pub fn update_sequence ( mut self , v : Uint64 ) -> Self { self . update_sequence = v ; self } } impl molecule :: prelude :: Builder for CellDataBuilder { type Entity = CellData ; const NAME : & 'static str = "CellDataBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . headers . as_slice ( ) . len ( ) + self . user_lockscript . as_slice ( ) . len ( ) + self . paused . as_slice ( ) . len ( ) + self . governance_lock_hash . as_slice ( ) . len ( ) + self . relayers . as_slice ( ) . len ( ) + self . config . as_slice ( ) . len ( ) + self . update_sequence . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . headers . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . user_lockscript . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . paused . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . governance_lock_hash . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . relayers . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . config . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . update_sequence . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . headers . as_slice ( ) ) ? ; writer . write_all ( self . user_lockscript . as_slice ( ) ) ? ; writer . write_all ( self . paused . as_slice ( ) ) ? ; writer . write_all ( self . governance_lock_hash . as_slice ( ) ) ? ; writer . write_all ( self . relayers . as_slice ( ) ) ? ; writer . write_all ( self . config . as_slice ( ) ) ? ; writer . write_all ( self . update_sequence . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; CellData :: new_unchecked ( inner . into ( ) ) } }
@@ -0,0 +1,8 @@
+pub mod basic;
+pub mod cell_data;
+pub mod dags_merkle_roots;
+pub mod witness;
+pub mod double_node_with_merkle_proof;
+
+pub use basic::*;
+pub use cell_data::*;
\ No newline at end of file
@@ -0,0 +1,40 @@
+// Generated by Molecule 0.6.1
+
+use molecule :: prelude :: * ;
+use super :: basic :: * ;
+# [ derive ( Clone ) ] pub struct Witness ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Witness { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Witness { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Witness { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "header" , self . header ( ) ) ? ; write ! ( f , ", {}: {}" , "merkle_proof" , self . merkle_proof ( ) ) ? ; write ! ( f , ", {}: {}" , "cell_dep_index_list" , self . cell_dep_index_list ( ) ) ? ; write ! ( f , ", {}: {}" , "expected_tail_hash" , self . expected_tail_hash ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for Witness { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 64 , 0 , 0 , 0 , 20 , 0 , 0 , 0 , 24 , 0 , 0 , 0 , 28 , 0 , 0 , 0 , 32 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; Witness :: new_unchecked ( v . into ( ) ) } } impl Witness { pub const FIELD_COUNT : usize = 4 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn header ( & self ) -> Bytes { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Bytes :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn merkle_proof ( & self ) -> BytesVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; BytesVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn cell_dep_index_list ( & self ) -> Bytes { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Bytes :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn expected_tail_hash ( & self ) -> Byte32 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; Byte32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { Byte32 :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> WitnessReader < 'r > { WitnessReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Witness { type Builder = WitnessBuilder ; const NAME : & 'static str = "Witness" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Witness ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { WitnessReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { WitnessReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . header ( self . header ( ) ) . merkle_proof ( self . merkle_proof ( ) ) . cell_dep_index_list ( self . cell_dep_index_list ( ) ) . expected_tail_hash ( self . expected_tail_hash ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct WitnessReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for WitnessReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for WitnessReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for WitnessReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "header" , self . header ( ) ) ? ; write ! ( f , ", {}: {}" , "merkle_proof" , self . merkle_proof ( ) ) ? ; write ! ( f , ", {}: {}" , "cell_dep_index_list" , self . cell_dep_index_list ( ) ) ? ; write ! ( f , ", {}: {}" , "expected_tail_hash" , self . expected_tail_hash ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > WitnessReader < 'r > { pub const FIELD_COUNT : usize = 4 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn header ( & self ) -> BytesReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; BytesReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn merkle_proof ( & self ) -> BytesVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn cell_dep_index_list ( & self ) -> BytesReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; BytesReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn expected_tail_hash ( & self ) -> Byte32Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for WitnessReader < 'r > { type Entity = Witness ; const NAME : & 'static str = "WitnessReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { WitnessReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } BytesReader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; BytesVecReader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; BytesReader :: verify ( & slice [ offsets [ 2 ] .. offsets [ 3 ] ] , compatible ) ? ; Byte32Reader :: verify ( & slice [ offsets [ 3 ] .. offsets [ 4 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct WitnessBuilder { pub ( crate ) header : Bytes , pub ( crate ) merkle_proof : BytesVec , pub ( crate ) cell_dep_index_list : Bytes , pub ( crate ) expected_tail_hash : Byte32 , } impl WitnessBuilder { pub const FIELD_COUNT : usize = 4 ; pub fn header ( mut self , v : Bytes ) -> Self { self . header = v ; self } pub fn merkle_proof ( mut self , v : BytesVec ) -> Self { self . merkle_proof = v ; self } pub fn cell_dep_index_list ( mut self , v : Bytes ) -> Self { self . cell_dep_index_list = v ; self } pub fn expected_tail_hash ( mut self , v : Byte32 ) -> Self { self . expected_tail_hash = v ; self } } impl molecule :: prelude :: Builder for WitnessBuilder { type Entity = Witness ; const NAME : & 'static str = "WitnessBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . header . as_slice ( ) . len ( ) + self . merkle_proof . as_slice ( ) . len ( ) + self . cell_dep_index_list . as_slice ( ) . len ( ) + self . expected_tail_hash . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . header . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . merkle_proof . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . cell_dep_index_list . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . expected_tail_hash . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . header . as_slice ( ) ) ? ; writer . write_all ( self . merkle_proof . as_slice ( ) ) ? ; writer . write_all ( self . cell_dep_index_list . as_slice ( ) ) ? ; writer . write_all ( self . expected_tail_hash . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Witness :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct WitnessHeader ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for WitnessHeader { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for WitnessHeader { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for WitnessHeader { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "header" , self . header ( ) ) ? ; write ! ( f , ", {}: {}" , "merkle_proof" , self . merkle_proof ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for WitnessHeader { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 20 , 0 , 0 , 0 , 12 , 0 , 0 , 0 , 16 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , ] ; WitnessHeader :: new_unchecked ( v . into ( ) ) } } impl WitnessHeader { pub const FIELD_COUNT : usize = 2 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn header ( & self ) -> Bytes { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Bytes :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn merkle_proof ( & self ) -> BytesVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; BytesVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { BytesVec :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> WitnessHeaderReader < 'r > { WitnessHeaderReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for WitnessHeader { type Builder = WitnessHeaderBuilder ; const NAME : & 'static str = "WitnessHeader" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { WitnessHeader ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { WitnessHeaderReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { WitnessHeaderReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . header ( self . header ( ) ) . merkle_proof ( self . merkle_proof ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct WitnessHeaderReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for WitnessHeaderReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for WitnessHeaderReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for WitnessHeaderReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "header" , self . header ( ) ) ? ; write ! ( f , ", {}: {}" , "merkle_proof" , self . merkle_proof ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > WitnessHeaderReader < 'r > { pub const FIELD_COUNT : usize = 2 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn header ( & self ) -> BytesReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; BytesReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn merkle_proof ( & self ) -> BytesVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for WitnessHeaderReader < 'r > { type Entity = WitnessHeader ; const NAME : & 'static str = "WitnessHeaderReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { WitnessHeaderReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } BytesReader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; BytesVecReader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct WitnessHeaderBuilder { pub ( crate ) header : Bytes , pub ( crate ) merkle_proof : BytesVec , } impl WitnessHeaderBuilder { pub const FIELD_COUNT : usize = 2 ; pub fn header ( mut self , v : Bytes ) -> Self { self . header = v ; self } pub fn merkle_proof ( mut self , v : BytesVec ) -> Self { self . merkle_proof = v ; self } } impl molecule :: prelude :: Builder for WitnessHeaderBuilder { type Entity = WitnessHeader ; const NAME : & 'static str = "WitnessHeaderBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . header . as_slice ( ) . len ( ) + self . merkle_proof . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . header . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . merkle_proof . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . header . as_slice ( ) ) ? ; writer . write_all ( self . merkle_proof . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; WitnessHeader :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct WitnessHeaderVec ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for WitnessHeaderVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for WitnessHeaderVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for WitnessHeaderVec { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} [" , Self :: NAME ) ? ; for i in 0 .. self . len ( ) { if i == 0 { write ! ( f , "{}" , self . get_unchecked ( i ) ) ? ; } else { write ! ( f , ", {}" , self . get_unchecked ( i ) ) ? ; } } write ! ( f , "]" ) } } impl :: core :: default :: Default for WitnessHeaderVec { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 4 , 0 , 0 , 0 , ] ; WitnessHeaderVec :: new_unchecked ( v . into ( ) ) } } impl WitnessHeaderVec { pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn item_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < WitnessHeader > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> WitnessHeader { let slice = self . as_slice ( ) ; let start_idx = molecule :: NUMBER_SIZE * ( 1 + idx ) ; let start = molecule :: unpack_number ( & slice [ start_idx .. ] ) as usize ; if idx == self . len ( ) - 1 { WitnessHeader :: new_unchecked ( self . 0 . slice ( start .. ) ) } else { let end_idx = start_idx + molecule :: NUMBER_SIZE ; let end = molecule :: unpack_number ( & slice [ end_idx .. ] ) as usize ; WitnessHeader :: new_unchecked ( self . 0 . slice ( start .. end ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> WitnessHeaderVecReader < 'r > { WitnessHeaderVecReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for WitnessHeaderVec { type Builder = WitnessHeaderVecBuilder ; const NAME : & 'static str = "WitnessHeaderVec" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { WitnessHeaderVec ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { WitnessHeaderVecReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { WitnessHeaderVecReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . extend ( self . into_iter ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct WitnessHeaderVecReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for WitnessHeaderVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for WitnessHeaderVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for WitnessHeaderVecReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} [" , Self :: NAME ) ? ; for i in 0 .. self . len ( ) { if i == 0 { write ! ( f , "{}" , self . get_unchecked ( i ) ) ? ; } else { write ! ( f , ", {}" , self . get_unchecked ( i ) ) ? ; } } write ! ( f , "]" ) } } impl < 'r > WitnessHeaderVecReader < 'r > { pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn item_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn len ( & self ) -> usize { self . item_count ( ) } pub fn is_empty ( & self ) -> bool { self . len ( ) == 0 } pub fn get ( & self , idx : usize ) -> Option < WitnessHeaderReader < 'r > > { if idx >= self . len ( ) { None } else { Some ( self . get_unchecked ( idx ) ) } } pub fn get_unchecked ( & self , idx : usize ) -> WitnessHeaderReader < 'r > { let slice = self . as_slice ( ) ; let start_idx = molecule :: NUMBER_SIZE * ( 1 + idx ) ; let start = molecule :: unpack_number ( & slice [ start_idx .. ] ) as usize ; if idx == self . len ( ) - 1 { WitnessHeaderReader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } else { let end_idx = start_idx + molecule :: NUMBER_SIZE ; let end = molecule :: unpack_number ( & slice [ end_idx .. ] ) as usize ; WitnessHeaderReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for WitnessHeaderVecReader < 'r > { type Entity = WitnessHeaderVec ; const NAME : & 'static str = "WitnessHeaderVecReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { WitnessHeaderVecReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , TotalSizeNotMatch , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let item_count = offset_first / 4 - 1 ; let header_size = molecule :: NUMBER_SIZE * ( item_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( item_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } for pair in offsets . windows ( 2 ) { let start = pair [ 0 ] ; let end = pair [ 1 ] ; WitnessHeaderReader :: verify ( & slice [ start .. end ] , compatible ) ? ; } Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct WitnessHeaderVecBuilder ( pub ( crate ) Vec < WitnessHeader > ) ; impl WitnessHeaderVecBuilder { pub fn set ( mut self , v : Vec < WitnessHeader > ) -> Self { self . 0 = v ; self } pub fn push ( mut self , v : WitnessHeader ) -> Self { self . 0 . push ( v ) ; self } pub fn extend < T : :: core :: iter :: IntoIterator < Item = WitnessHeader >> ( mut self , iter : T ) -> Self { for elem in iter { self . 0 . push ( elem ) ; } self } } impl molecule :: prelude :: Builder for WitnessHeaderVecBuilder { type Entity = WitnessHeaderVec ; const NAME : & 'static str = "WitnessHeaderVecBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( self . 0 . len ( ) + 1 ) + self . 0 . iter ( ) . map ( | inner | inner . as_slice ( ) . len ( ) ) . sum :: < usize > ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W , ) -> :: molecule :: io :: Result < ( ) > { let item_count = self . 0 . len ( ) ; if item_count == 0 { writer . write_all ( & molecule :: pack_number ( molecule :: NUMBER_SIZE as molecule :: Number , ) ) ? ; } else { let ( total_size , offsets ) = self . 0 . iter ( ) . fold ( ( molecule :: NUMBER_SIZE * ( item_count + 1 ) , Vec :: with_capacity ( item_count ) , ) , | ( start , mut offsets ) , inner | { offsets . push ( start ) ; ( start + inner . as_slice ( ) . len ( ) , offsets ) } , ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } for inner in self . 0 . iter ( ) { writer . write_all ( inner . as_slice ( ) ) ? ; } } Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; WitnessHeaderVec :: new_unchecked ( inner . into ( ) ) } }
+pub struct WitnessHeaderVecIterator ( WitnessHeaderVec , usize , usize ) ; impl :: core :: iter :: Iterator for WitnessHeaderVecIterator { type Item = WitnessHeader ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl :: core :: iter :: ExactSizeIterator for WitnessHeaderVecIterator { fn len ( & self ) -> usize { self . 2 - self . 1 } } impl :: core :: iter :: IntoIterator for WitnessHeaderVec { type Item = WitnessHeader ; type IntoIter = WitnessHeaderVecIterator ; fn into_iter ( self ) -> Self :: IntoIter { let len = self . len ( ) ; WitnessHeaderVecIterator ( self , 0 , len ) } } impl < 'r > WitnessHeaderVecReader < 'r > { pub fn iter < 't > ( & 't self ) -> WitnessHeaderVecReaderIterator < 't , 'r > { WitnessHeaderVecReaderIterator ( & self , 0 , self . len ( ) ) } } pub struct WitnessHeaderVecReaderIterator < 't , 'r > ( & 't WitnessHeaderVecReader < 'r > , usize , usize ) ; impl < 't : 'r , 'r > :: core :: iter :: Iterator for WitnessHeaderVecReaderIterator < 't , 'r > { type Item = WitnessHeaderReader < 't > ; fn next ( & mut self ) -> Option < Self :: Item > { if self . 1 >= self . 2 { None } else { let ret = self . 0 . get_unchecked ( self . 1 ) ; self . 1 += 1 ; Some ( ret ) } } } impl < 't : 'r , 'r > :: core :: iter :: ExactSizeIterator for WitnessHeaderVecReaderIterator < 't , 'r > { fn len ( & self ) -> usize { self . 2 - self . 1 } }
+# [ derive ( Clone ) ] pub struct WitnessV2 ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for WitnessV2 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for WitnessV2 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for WitnessV2 { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "headers" , self . headers ( ) ) ? ; write ! ( f , ", {}: {}" , "cell_dep_index_list" , self . cell_dep_index_list ( ) ) ? ; write ! ( f , ", {}: {}" , "expected_tail_hash" , self . expected_tail_hash ( ) ) ? ; write ! ( f , ", {}: {}" , "receipt_proofs" , self . receipt_proofs ( ) ) ? ; write ! ( f , ", {}: {}" , "checkpoint_signatures" , self . checkpoint_signatures ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for WitnessV2 { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 72 , 0 , 0 , 0 , 24 , 0 , 0 , 0 , 28 , 0 , 0 , 0 , 32 , 0 , 0 , 0 , 64 , 0 , 0 , 0 , 68 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , ] ; WitnessV2 :: new_unchecked ( v . into ( ) ) } } impl WitnessV2 { pub const FIELD_COUNT : usize = 5 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn headers ( & self ) -> WitnessHeaderVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; WitnessHeaderVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn cell_dep_index_list ( & self ) -> Bytes { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Bytes :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn expected_tail_hash ( & self ) -> Byte32 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Byte32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn receipt_proofs ( & self ) -> BytesVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; BytesVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn checkpoint_signatures ( & self ) -> BytesVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 24 .. ] ) as usize ; BytesVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { BytesVec :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> WitnessV2Reader < 'r > { WitnessV2Reader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for WitnessV2 { type Builder = WitnessV2Builder ; const NAME : & 'static str = "WitnessV2" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { WitnessV2 ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { WitnessV2Reader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { WitnessV2Reader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . headers ( self . headers ( ) ) . cell_dep_index_list ( self . cell_dep_index_list ( ) ) . expected_tail_hash ( self . expected_tail_hash ( ) ) . receipt_proofs ( self . receipt_proofs ( ) ) . checkpoint_signatures ( self . checkpoint_signatures ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct WitnessV2Reader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for WitnessV2Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for WitnessV2Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for WitnessV2Reader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "headers" , self . headers ( ) ) ? ; write ! ( f , ", {}: {}" , "cell_dep_index_list" , self . cell_dep_index_list ( ) ) ? ; write ! ( f , ", {}: {}" , "expected_tail_hash" , self . expected_tail_hash ( ) ) ? ; write ! ( f , ", {}: {}" , "receipt_proofs" , self . receipt_proofs ( ) ) ? ; write ! ( f , ", {}: {}" , "checkpoint_signatures" , self . checkpoint_signatures ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > WitnessV2Reader < 'r > { pub const FIELD_COUNT : usize = 5 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn headers ( & self ) -> WitnessHeaderVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; WitnessHeaderVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn cell_dep_index_list ( & self ) -> BytesReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; BytesReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn expected_tail_hash ( & self ) -> Byte32Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn receipt_proofs ( & self ) -> BytesVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn checkpoint_signatures ( & self ) -> BytesVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 24 .. ] ) as usize ; BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for WitnessV2Reader < 'r > { type Entity = WitnessV2 ; const NAME : & 'static str = "WitnessV2Reader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { WitnessV2Reader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } WitnessHeaderVecReader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; BytesReader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; Byte32Reader :: verify ( & slice [ offsets [ 2 ] .. offsets [ 3 ] ] , compatible ) ? ; BytesVecReader :: verify ( & slice [ offsets [ 3 ] .. offsets [ 4 ] ] , compatible ) ? ; BytesVecReader :: verify ( & slice [ offsets [ 4 ] .. offsets [ 5 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct WitnessV2Builder { pub ( crate ) headers : WitnessHeaderVec , pub ( crate ) cell_dep_index_list : Bytes , pub ( crate ) expected_tail_hash : Byte32 , pub ( crate ) receipt_proofs : BytesVec , pub ( crate ) checkpoint_signatures : BytesVec , } impl WitnessV2Builder { pub const FIELD_COUNT : usize = 5 ; pub fn headers ( mut self , v : WitnessHeaderVec ) -> Self { self . headers = v ; self } pub fn cell_dep_index_list ( mut self , v : Bytes ) -> Self { self . cell_dep_index_list = v ; self } pub fn expected_tail_hash ( mut self , v : Byte32 ) -> Self { self . expected_tail_hash = v ; self } pub fn receipt_proofs ( mut self , v : BytesVec ) -> Self { self . receipt_proofs = v ; self } pub fn checkpoint_signatures ( mut self , v : BytesVec ) -> Self { self . checkpoint_signatures = v ; self } } impl molecule :: prelude :: Builder for WitnessV2Builder { type Entity = WitnessV2 ; const NAME : & 'static str = "WitnessV2Builder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . headers . as_slice ( ) . len ( ) + self . cell_dep_index_list . as_slice ( ) . len ( ) + self . expected_tail_hash . as_slice ( ) . len ( ) + self . receipt_proofs . as_slice ( ) . len ( ) + self . checkpoint_signatures . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . headers . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . cell_dep_index_list . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . expected_tail_hash . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . receipt_proofs . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . checkpoint_signatures . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . headers . as_slice ( ) ) ? ; writer . write_all ( self . cell_dep_index_list . as_slice ( ) ) ? ; writer . write_all ( self . expected_tail_hash . as_slice ( ) ) ? ; writer . write_all ( self . receipt_proofs . as_slice ( ) ) ? ; writer . write_all ( self . checkpoint_signatures . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; WitnessV2 :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct VersionedWitness ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for VersionedWitness { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for VersionedWitness { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for VersionedWitness { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}(" , Self :: NAME ) ? ; self . to_enum ( ) . display_inner ( f ) ? ; write ! ( f , ")" ) } } impl :: core :: default :: Default for VersionedWitness { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , 64 , 0 , 0 , 0 , 20 , 0 , 0 , 0 , 24 , 0 , 0 , 0 , 28 , 0 , 0 , 0 , 32 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; VersionedWitness :: new_unchecked ( v . into ( ) ) } } impl VersionedWitness { pub const ITEMS_COUNT : usize = 2 ; pub fn item_id ( & self ) -> molecule :: Number { molecule :: unpack_number ( self . as_slice ( ) ) } pub fn to_enum ( & self ) -> VersionedWitnessUnion { let inner = self . 0 . slice ( molecule :: NUMBER_SIZE .. ) ; match self . item_id ( ) { 0 => Witness :: new_unchecked ( inner ) . into ( ) , 1 => WitnessV2 :: new_unchecked ( inner ) . into ( ) , _ => panic ! ( "{}: invalid data" , Self :: NAME ) , } } pub fn as_reader < 'r > ( & 'r self ) -> VersionedWitnessReader < 'r > { VersionedWitnessReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for VersionedWitness { type Builder = VersionedWitnessBuilder ; const NAME : & 'static str = "VersionedWitness" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { VersionedWitness ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { VersionedWitnessReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { VersionedWitnessReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . set ( self . to_enum ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct VersionedWitnessReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for VersionedWitnessReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for VersionedWitnessReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for VersionedWitnessReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}(" , Self :: NAME ) ? ; self . to_enum ( ) . display_inner ( f ) ? ; write ! ( f , ")" ) } } impl < 'r > VersionedWitnessReader < 'r > { pub const ITEMS_COUNT : usize = 2 ; pub fn item_id ( & self ) -> molecule :: Number { molecule :: unpack_number ( self . as_slice ( ) ) } pub fn to_enum ( & self ) -> VersionedWitnessUnionReader < 'r > { let inner = & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ; match self . item_id ( ) { 0 => WitnessReader :: new_unchecked ( inner ) . into ( ) , 1 => WitnessV2Reader :: new_unchecked ( inner ) . into ( ) , _ => panic ! ( "{}: invalid data" , Self :: NAME ) , } } } impl < 'r > molecule :: prelude :: Reader < 'r > for VersionedWitnessReader < 'r > { type Entity = VersionedWitness ; const NAME : & 'static str = "VersionedWitnessReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { VersionedWitnessReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let item_id = molecule :: unpack_number ( slice ) ; let inner_slice = & slice [ molecule :: NUMBER_SIZE .. ] ; match item_id { 0 => WitnessReader :: verify ( inner_slice , compatible ) , 1 => WitnessV2Reader :: verify ( inner_slice , compatible ) , _ => ve ! ( Self , UnknownItem , Self :: ITEMS_COUNT , item_id ) , } ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct VersionedWitnessBuilder ( pub ( crate ) VersionedWitnessUnion ) ; impl VersionedWitnessBuilder { pub const ITEMS_COUNT : usize = 2 ; pub fn set < I > ( mut self , v : I ) -> Self where I : :: core :: convert :: Into < VersionedWitnessUnion > { self . 0 = v . into ( ) ; self } } impl molecule :: prelude :: Builder for VersionedWitnessBuilder { type Entity = VersionedWitness ; const NAME : & 'static str = "VersionedWitnessBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE + self . 0 . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W , ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( & molecule :: pack_number ( self . 0 . item_id ( ) ) ) ? ; writer . write_all ( self . 0 . as_slice ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; VersionedWitness :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Debug , Clone ) ] pub enum VersionedWitnessUnion { Witness ( Witness ) , WitnessV2 ( WitnessV2 ) , } # [ derive ( Debug , Clone , Copy ) ] pub enum VersionedWitnessUnionReader < 'r > { Witness ( WitnessReader < 'r > ) , WitnessV2 ( WitnessV2Reader < 'r > ) , } impl :: core :: default :: Default for VersionedWitnessUnion { fn default ( ) -> Self { VersionedWitnessUnion :: Witness ( :: core :: default :: Default :: default ( ) ) } } impl :: core :: fmt :: Display for VersionedWitnessUnion { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { match self { VersionedWitnessUnion :: Witness ( ref item ) => { write ! ( f , "{}::{}({})" , Self :: NAME , Witness :: NAME , item ) } VersionedWitnessUnion :: WitnessV2 ( ref item ) => { write ! ( f , "{}::{}({})" , Self :: NAME , WitnessV2 :: NAME , item ) } } } } impl < 'r > :: core :: fmt :: Display for VersionedWitnessUnionReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { match self { VersionedWitnessUnionReader :: Witness ( ref item ) => { write ! ( f , "{}::{}({})" , Self :: NAME , Witness :: NAME , item ) } VersionedWitnessUnionReader :: WitnessV2 ( ref item ) => { write ! ( f , "{}::{}({})" , Self :: NAME , WitnessV2 :: NAME , item ) } } } } impl VersionedWitnessUnion { pub ( crate ) fn display_inner ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { match self { VersionedWitnessUnion :: Witness ( ref item ) => write ! ( f , "{}" , item ) , VersionedWitnessUnion :: WitnessV2 ( ref item ) => write ! ( f , "{}" , item ) , } } } impl < 'r > VersionedWitnessUnionReader < 'r > { pub ( crate ) fn display_inner ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { match self { VersionedWitnessUnionReader :: Witness ( ref item ) => write ! ( f , "{}" , item ) , VersionedWitnessUnionReader :: WitnessV2 ( ref item ) => write ! ( f , "{}" , item ) , } } } impl :: core :: convert :: From < Witness > for VersionedWitnessUnion { fn from ( item : Witness ) -> Self { VersionedWitnessUnion :: Witness ( item ) } } impl :: core :: convert :: From < WitnessV2 > for VersionedWitnessUnion { fn from ( item : WitnessV2 ) -> Self { VersionedWitnessUnion :: WitnessV2 ( item ) } } impl < 'r > :: core :: convert :: From < WitnessReader < 'r >> for VersionedWitnessUnionReader < 'r > { fn from ( item : WitnessReader < 'r > ) -> Self { VersionedWitnessUnionReader :: Witness ( item ) } } impl < 'r > :: core :: convert :: From < WitnessV2Reader < 'r >> for VersionedWitnessUnionReader < 'r > { fn from ( item : WitnessV2Reader < 'r > ) -> Self { VersionedWitnessUnionReader :: WitnessV2 ( item ) } } impl VersionedWitnessUnion { pub const NAME : & 'static str = "VersionedWitnessUnion" ; pub fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { match self { VersionedWitnessUnion :: Witness ( item ) => item . as_bytes ( ) , VersionedWitnessUnion :: WitnessV2 ( item ) => item . as_bytes ( ) , } } pub fn as_slice ( & self ) -> & [ u8 ] { match self { VersionedWitnessUnion :: Witness ( item ) => item . as_slice ( ) , VersionedWitnessUnion :: WitnessV2 ( item ) => item . as_slice ( ) , } } pub fn item_id ( & self ) -> molecule :: Number { match self { VersionedWitnessUnion :: Witness ( _ ) => 0 , VersionedWitnessUnion :: WitnessV2 ( _ ) => 1 , } } pub fn item_name ( & self ) -> & str { match self { VersionedWitnessUnion :: Witness ( _ ) => "Witness" , VersionedWitnessUnion :: WitnessV2 ( _ ) => "WitnessV2" , } } pub fn as_reader < 'r > ( & 'r self ) -> VersionedWitnessUnionReader < 'r > { match self { VersionedWitnessUnion :: Witness ( item ) => item . as_reader ( ) . into ( ) , VersionedWitnessUnion :: WitnessV2 ( item ) => item . as_reader ( ) . into ( ) , } } } impl < 'r > VersionedWitnessUnionReader < 'r > { pub const NAME : & 'r str = "VersionedWitnessUnionReader" ; pub fn as_slice ( & self ) -> & 'r [ u8 ] { match self { VersionedWitnessUnionReader :: Witness ( item ) => item . as_slice ( ) , VersionedWitnessUnionReader :: WitnessV2 ( item ) => item . as_slice ( ) , } } pub fn item_id ( & self ) -> molecule :: Number { match self { VersionedWitnessUnionReader :: Witness ( _ ) => 0 , VersionedWitnessUnionReader :: WitnessV2 ( _ ) => 1 , } } pub fn item_name ( & self ) -> & str { match self { VersionedWitnessUnionReader :: Witness ( _ ) => "Witness" , VersionedWitnessUnionReader :: WitnessV2 ( _ ) => "WitnessV2" , } } }
+# [ derive ( Clone ) ] pub struct AncestryProof ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for AncestryProof { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for AncestryProof { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for AncestryProof { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "hash" , self . hash ( ) ) ? ; write ! ( f , ", {}: {}" , "number" , self . number ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for AncestryProof { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 52 , 0 , 0 , 0 , 12 , 0 , 0 , 0 , 44 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; AncestryProof :: new_unchecked ( v . into ( ) ) } } impl AncestryProof { pub const FIELD_COUNT : usize = 2 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn hash ( & self ) -> Byte32 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Byte32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn number ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { Uint64 :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> AncestryProofReader < 'r > { AncestryProofReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for AncestryProof { type Builder = AncestryProofBuilder ; const NAME : & 'static str = "AncestryProof" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { AncestryProof ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { AncestryProofReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { AncestryProofReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . hash ( self . hash ( ) ) . number ( self . number ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct AncestryProofReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for AncestryProofReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for AncestryProofReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for AncestryProofReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "hash" , self . hash ( ) ) ? ; write ! ( f , ", {}: {}" , "number" , self . number ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > AncestryProofReader < 'r > { pub const FIELD_COUNT : usize = 2 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn hash ( & self ) -> Byte32Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn number ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for AncestryProofReader < 'r > { type Entity = AncestryProof ; const NAME : & 'static str = "AncestryProofReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { AncestryProofReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } Byte32Reader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct AncestryProofBuilder { pub ( crate ) hash : Byte32 , pub ( crate ) number : Uint64 , } impl AncestryProofBuilder { pub const FIELD_COUNT : usize = 2 ; pub fn hash ( mut self , v : Byte32 ) -> Self { self . hash = v ; self } pub fn number ( mut self , v : Uint64 ) -> Self { self . number = v ; self } } impl molecule :: prelude :: Builder for AncestryProofBuilder { type Entity = AncestryProof ; const NAME : & 'static str = "AncestryProofBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . hash . as_slice ( ) . len ( ) + self . number . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . hash . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . number . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . hash . as_slice ( ) ) ? ; writer . write_all ( self . number . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; AncestryProof :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct ReceiptProof ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for ReceiptProof { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for ReceiptProof { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for ReceiptProof { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "header" , self . header ( ) ) ? ; write ! ( f , ", {}: {}" , "tx_index" , self . tx_index ( ) ) ? ; write ! ( f , ", {}: {}" , "proof" , self . proof ( ) ) ? ; write ! ( f , ", {}: {}" , "log_index" , self . log_index ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for ReceiptProof { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 92 , 0 , 0 , 0 , 20 , 0 , 0 , 0 , 72 , 0 , 0 , 0 , 80 , 0 , 0 , 0 , 84 , 0 , 0 , 0 , 52 , 0 , 0 , 0 , 12 , 0 , 0 , 0 , 44 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 4 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; ReceiptProof :: new_unchecked ( v . into ( ) ) } } impl ReceiptProof { pub const FIELD_COUNT : usize = 4 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn header ( & self ) -> AncestryProof { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; AncestryProof :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn tx_index ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn proof ( & self ) -> BytesVec { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; BytesVec :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn log_index ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { Uint64 :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> ReceiptProofReader < 'r > { ReceiptProofReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for ReceiptProof { type Builder = ReceiptProofBuilder ; const NAME : & 'static str = "ReceiptProof" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { ReceiptProof ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ReceiptProofReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ReceiptProofReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . header ( self . header ( ) ) . tx_index ( self . tx_index ( ) ) . proof ( self . proof ( ) ) . log_index ( self . log_index ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct ReceiptProofReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for ReceiptProofReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for ReceiptProofReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for ReceiptProofReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "header" , self . header ( ) ) ? ; write ! ( f , ", {}: {}" , "tx_index" , self . tx_index ( ) ) ? ; write ! ( f , ", {}: {}" , "proof" , self . proof ( ) ) ? ; write ! ( f , ", {}: {}" , "log_index" , self . log_index ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > ReceiptProofReader < 'r > { pub const FIELD_COUNT : usize = 4 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn header ( & self ) -> AncestryProofReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; AncestryProofReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn tx_index ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn proof ( & self ) -> BytesVecReader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; BytesVecReader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn log_index ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 20 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for ReceiptProofReader < 'r > { type Entity = ReceiptProof ; const NAME : & 'static str = "ReceiptProofReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { ReceiptProofReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } AncestryProofReader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; BytesVecReader :: verify ( & slice [ offsets [ 2 ] .. offsets [ 3 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 3 ] .. offsets [ 4 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct ReceiptProofBuilder { pub ( crate ) header : AncestryProof , pub ( crate ) tx_index : Uint64 , pub ( crate ) proof : BytesVec , pub ( crate ) log_index : Uint64 , } impl ReceiptProofBuilder { pub const FIELD_COUNT : usize = 4 ; pub fn header ( mut self , v : AncestryProof ) -> Self { self . header = v ; self } pub fn tx_index ( mut self , v : Uint64 ) -> Self { self . tx_index = v ; self } pub fn proof ( mut self , v : BytesVec ) -> Self { self . proof = v ; self } pub fn log_index ( mut self , v : Uint64 ) -> Self { self . log_index = v ; self } } impl molecule :: prelude :: Builder for ReceiptProofBuilder { type Entity = ReceiptProof ; const NAME : & 'static str = "ReceiptProofBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . header . as_slice ( ) . len ( ) + self . tx_index . as_slice ( ) . len ( ) + self . proof . as_slice ( ) . len ( ) + self . log_index . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . header . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . tx_index . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . proof . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . log_index . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . header . as_slice ( ) ) ? ; writer . write_all ( self . tx_index . as_slice ( ) ) ? ; writer . write_all ( self . proof . as_slice ( ) ) ? ; writer . write_all ( self . log_index . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; ReceiptProof :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct Append ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Append { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Append { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Append { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "number" , self . number ( ) ) ? ; write ! ( f , ", {}: {}" , "hash" , self . hash ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for Append { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 52 , 0 , 0 , 0 , 12 , 0 , 0 , 0 , 20 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; Append :: new_unchecked ( v . into ( ) ) } } impl Append { pub const FIELD_COUNT : usize = 2 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn number ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn hash ( & self ) -> Byte32 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Byte32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { Byte32 :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> AppendReader < 'r > { AppendReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Append { type Builder = AppendBuilder ; const NAME : & 'static str = "Append" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Append ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { AppendReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { AppendReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . number ( self . number ( ) ) . hash ( self . hash ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct AppendReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for AppendReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for AppendReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for AppendReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "number" , self . number ( ) ) ? ; write ! ( f , ", {}: {}" , "hash" , self . hash ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > AppendReader < 'r > { pub const FIELD_COUNT : usize = 2 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn number ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn hash ( & self ) -> Byte32Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for AppendReader < 'r > { type Entity = Append ; const NAME : & 'static str = "AppendReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { AppendReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } Uint64Reader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; Byte32Reader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct AppendBuilder { pub ( crate ) number : Uint64 , pub ( crate ) hash : Byte32 , } impl AppendBuilder { pub const FIELD_COUNT : usize = 2 ; pub fn number ( mut self , v : Uint64 ) -> Self { self . number = v ; self } pub fn hash ( mut self , v : Byte32 ) -> Self { self . hash = v ; self } } impl molecule :: prelude :: Builder for AppendBuilder { type Entity = Append ; const NAME : & 'static str = "AppendBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . number . as_slice ( ) . len ( ) + self . hash . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . number . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . hash . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . number . as_slice ( ) ) ? ; writer . write_all ( self . hash . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Append :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct Reorg ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Reorg { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Reorg { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Reorg { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "depth" , self . depth ( ) ) ? ; write ! ( f , ", {}: {}" , "new_tail_number" , self . new_tail_number ( ) ) ? ; write ! ( f , ", {}: {}" , "new_tail_hash" , self . new_tail_hash ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for Reorg { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 64 , 0 , 0 , 0 , 16 , 0 , 0 , 0 , 24 , 0 , 0 , 0 , 32 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; Reorg :: new_unchecked ( v . into ( ) ) } } impl Reorg { pub const FIELD_COUNT : usize = 3 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn depth ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn new_tail_number ( & self ) -> Uint64 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint64 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } pub fn new_tail_hash ( & self ) -> Byte32 { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Byte32 :: new_unchecked ( self . 0 . slice ( start .. end ) ) } else { Byte32 :: new_unchecked ( self . 0 . slice ( start .. ) ) } } pub fn as_reader < 'r > ( & 'r self ) -> ReorgReader < 'r > { ReorgReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Reorg { type Builder = ReorgBuilder ; const NAME : & 'static str = "Reorg" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Reorg ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ReorgReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ReorgReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . depth ( self . depth ( ) ) . new_tail_number ( self . new_tail_number ( ) ) . new_tail_hash ( self . new_tail_hash ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct ReorgReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for ReorgReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for ReorgReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for ReorgReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; write ! ( f , "{}: {}" , "depth" , self . depth ( ) ) ? ; write ! ( f , ", {}: {}" , "new_tail_number" , self . new_tail_number ( ) ) ? ; write ! ( f , ", {}: {}" , "new_tail_hash" , self . new_tail_hash ( ) ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ", .. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > ReorgReader < 'r > { pub const FIELD_COUNT : usize = 3 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn depth ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 4 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn new_tail_number ( & self ) -> Uint64Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 8 .. ] ) as usize ; let end = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; Uint64Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } pub fn new_tail_hash ( & self ) -> Byte32Reader < 'r > { let slice = self . as_slice ( ) ; let start = molecule :: unpack_number ( & slice [ 12 .. ] ) as usize ; if self . has_extra_fields ( ) { let end = molecule :: unpack_number ( & slice [ 16 .. ] ) as usize ; Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. end ] ) } else { Byte32Reader :: new_unchecked ( & self . as_slice ( ) [ start .. ] ) } } } impl < 'r > molecule :: prelude :: Reader < 'r > for ReorgReader < 'r > { type Entity = Reorg ; const NAME : & 'static str = "ReorgReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { ReorgReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len == molecule :: NUMBER_SIZE && Self :: FIELD_COUNT == 0 { return Ok ( ( ) ) ; } if slice_len < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE * 2 , slice_len ) ; } let offset_first = molecule :: unpack_number ( & slice [ molecule :: NUMBER_SIZE .. ] ) as usize ; if offset_first % 4 != 0 || offset_first < molecule :: NUMBER_SIZE * 2 { return ve ! ( Self , OffsetsNotMatch ) ; } let field_count = offset_first / 4 - 1 ; if field_count < Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } else if ! compatible && field_count > Self :: FIELD_COUNT { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , field_count ) ; } ; let header_size = molecule :: NUMBER_SIZE * ( field_count + 1 ) ; if slice_len < header_size { return ve ! ( Self , HeaderIsBroken , header_size , slice_len ) ; } let mut offsets : Vec < usize > = slice [ molecule :: NUMBER_SIZE .. ] . chunks ( molecule :: NUMBER_SIZE ) . take ( field_count ) . map ( | x | molecule :: unpack_number ( x ) as usize ) . collect ( ) ; offsets . push ( total_size ) ; if offsets . windows ( 2 ) . any ( | i | i [ 0 ] > i [ 1 ] ) { return ve ! ( Self , OffsetsNotMatch ) ; } Uint64Reader :: verify ( & slice [ offsets [ 0 ] .. offsets [ 1 ] ] , compatible ) ? ; Uint64Reader :: verify ( & slice [ offsets [ 1 ] .. offsets [ 2 ] ] , compatible ) ? ; Byte32Reader :: verify ( & slice [ offsets [ 2 ] .. offsets [ 3 ] ] , compatible ) ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct ReorgBuilder { pub ( crate ) depth : Uint64 , pub ( crate ) new_tail_number : Uint64 , pub ( crate ) new_tail_hash : Byte32 , } impl ReorgBuilder { pub const FIELD_COUNT : usize = 3 ; pub fn depth ( mut self , v : Uint64 ) -> Self { self . depth = v ; self } pub fn new_tail_number ( mut self , v : Uint64 ) -> Self { self . new_tail_number = v ; self } pub fn new_tail_hash ( mut self , v : Byte32 ) -> Self { self . new_tail_hash = v ; self } } impl molecule :: prelude :: Builder for ReorgBuilder { type Entity = Reorg ; const NAME : & 'static str = "ReorgBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) + self . depth . as_slice ( ) . len ( ) + self . new_tail_number . as_slice ( ) . len ( ) + self . new_tail_hash . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W ) -> :: molecule :: io :: Result < ( ) > { let mut total_size = molecule :: NUMBER_SIZE * ( Self :: FIELD_COUNT + 1 ) ; let mut offsets = Vec :: with_capacity ( Self :: FIELD_COUNT ) ; offsets . push ( total_size ) ; total_size += self . depth . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . new_tail_number . as_slice ( ) . len ( ) ; offsets . push ( total_size ) ; total_size += self . new_tail_hash . as_slice ( ) . len ( ) ; writer . write_all ( & molecule :: pack_number ( total_size as molecule :: Number ) ) ? ; for offset in offsets . into_iter ( ) { writer . write_all ( & molecule :: pack_number ( offset as molecule :: Number ) ) ? ; } writer . write_all ( self . depth . as_slice ( ) ) ? ; writer . write_all ( self . new_tail_number . as_slice ( ) ) ? ; writer . write_all ( self . new_tail_hash . as_slice ( ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Reorg :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct Unchanged ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for Unchanged { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for Unchanged { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for Unchanged { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ".. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl :: core :: default :: Default for Unchanged { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 4 , 0 , 0 , 0 , ] ; Unchanged :: new_unchecked ( v . into ( ) ) } } impl Unchanged { pub const FIELD_COUNT : usize = 0 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } pub fn as_reader < 'r > ( & 'r self ) -> UnchangedReader < 'r > { UnchangedReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for Unchanged { type Builder = UnchangedBuilder ; const NAME : & 'static str = "Unchanged" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { Unchanged ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { UnchangedReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { UnchangedReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) } }
+# [ derive ( Clone , Copy ) ] pub struct UnchangedReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for UnchangedReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for UnchangedReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for UnchangedReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{} {{ " , Self :: NAME ) ? ; let extra_count = self . count_extra_fields ( ) ; if extra_count != 0 { write ! ( f , ".. ({} fields)" , extra_count ) ? ; } write ! ( f , " }}" ) } } impl < 'r > UnchangedReader < 'r > { pub const FIELD_COUNT : usize = 0 ; pub fn total_size ( & self ) -> usize { molecule :: unpack_number ( self . as_slice ( ) ) as usize } pub fn field_count ( & self ) -> usize { if self . total_size ( ) == molecule :: NUMBER_SIZE { 0 } else { ( molecule :: unpack_number ( & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ) as usize / 4 ) - 1 } } pub fn count_extra_fields ( & self ) -> usize { self . field_count ( ) - Self :: FIELD_COUNT } pub fn has_extra_fields ( & self ) -> bool { Self :: FIELD_COUNT != self . field_count ( ) } } impl < 'r > molecule :: prelude :: Reader < 'r > for UnchangedReader < 'r > { type Entity = Unchanged ; const NAME : & 'static str = "UnchangedReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { UnchangedReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool , ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let total_size = molecule :: unpack_number ( slice ) as usize ; if slice_len != total_size { return ve ! ( Self , TotalSizeNotMatch , total_size , slice_len ) ; } if slice_len > molecule :: NUMBER_SIZE && ! compatible { return ve ! ( Self , FieldCountNotMatch , Self :: FIELD_COUNT , ! 0 ) ; } Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct UnchangedBuilder { } impl UnchangedBuilder { pub const FIELD_COUNT : usize = 0 ; } impl molecule :: prelude :: Builder for UnchangedBuilder { type Entity = Unchanged ; const NAME : & 'static str = "UnchangedBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W , ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( & molecule :: pack_number ( molecule :: NUMBER_SIZE as molecule :: Number , ) ) ? ; Ok ( ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; Unchanged :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Clone ) ] pub struct ChainEvent ( molecule :: bytes :: Bytes ) ; impl :: core :: fmt :: LowerHex for ChainEvent { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl :: core :: fmt :: Debug for ChainEvent { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl :: core :: fmt :: Display for ChainEvent { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}(" , Self :: NAME ) ? ; self . to_enum ( ) . display_inner ( f ) ? ; write ! ( f , ")" ) } } impl :: core :: default :: Default for ChainEvent { fn default ( ) -> Self { let v : Vec < u8 > = vec ! [ 0 , 0 , 0 , 0 , 52 , 0 , 0 , 0 , 12 , 0 , 0 , 0 , 20 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , 0 , ] ; ChainEvent :: new_unchecked ( v . into ( ) ) } } impl ChainEvent { pub const ITEMS_COUNT : usize = 3 ; pub fn item_id ( & self ) -> molecule :: Number { molecule :: unpack_number ( self . as_slice ( ) ) } pub fn to_enum ( & self ) -> ChainEventUnion { let inner = self . 0 . slice ( molecule :: NUMBER_SIZE .. ) ; match self . item_id ( ) { 0 => Append :: new_unchecked ( inner ) . into ( ) , 1 => Reorg :: new_unchecked ( inner ) . into ( ) , 2 => Unchanged :: new_unchecked ( inner ) . into ( ) , _ => panic ! ( "{}: invalid data" , Self :: NAME ) , } } pub fn as_reader < 'r > ( & 'r self ) -> ChainEventReader < 'r > { ChainEventReader :: new_unchecked ( self . as_slice ( ) ) } } impl molecule :: prelude :: Entity for ChainEvent { type Builder = ChainEventBuilder ; const NAME : & 'static str = "ChainEvent" ; fn new_unchecked ( data : molecule :: bytes :: Bytes ) -> Self { ChainEvent ( data ) } fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { self . 0 . clone ( ) } fn as_slice ( & self ) -> & [ u8 ] { & self . 0 [ .. ] } fn from_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ChainEventReader :: from_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn from_compatible_slice ( slice : & [ u8 ] ) -> molecule :: error :: VerificationResult < Self > { ChainEventReader :: from_compatible_slice ( slice ) . map ( | reader | reader . to_entity ( ) ) } fn new_builder ( ) -> Self :: Builder { :: core :: default :: Default :: default ( ) } fn as_builder ( self ) -> Self :: Builder { Self :: new_builder ( ) . set ( self . to_enum ( ) ) } }
+# [ derive ( Clone , Copy ) ] pub struct ChainEventReader < 'r > ( & 'r [ u8 ] ) ; impl < 'r > :: core :: fmt :: LowerHex for ChainEventReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { use molecule :: hex_string ; if f . alternate ( ) { write ! ( f , "0x" ) ? ; } write ! ( f , "{}" , hex_string ( self . as_slice ( ) ) ) } } impl < 'r > :: core :: fmt :: Debug for ChainEventReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}({:#x})" , Self :: NAME , self ) } } impl < 'r > :: core :: fmt :: Display for ChainEventReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { write ! ( f , "{}(" , Self :: NAME ) ? ; self . to_enum ( ) . display_inner ( f ) ? ; write ! ( f , ")" ) } } impl < 'r > ChainEventReader < 'r > { pub const ITEMS_COUNT : usize = 3 ; pub fn item_id ( & self ) -> molecule :: Number { molecule :: unpack_number ( self . as_slice ( ) ) } pub fn to_enum ( & self ) -> ChainEventUnionReader < 'r > { let inner = & self . as_slice ( ) [ molecule :: NUMBER_SIZE .. ] ; match self . item_id ( ) { 0 => AppendReader :: new_unchecked ( inner ) . into ( ) , 1 => ReorgReader :: new_unchecked ( inner ) . into ( ) , 2 => UnchangedReader :: new_unchecked ( inner ) . into ( ) , _ => panic ! ( "{}: invalid data" , Self :: NAME ) , } } } impl < 'r > molecule :: prelude :: Reader < 'r > for ChainEventReader < 'r > { type Entity = ChainEvent ; const NAME : & 'static str = "ChainEventReader" ; fn to_entity ( & self ) -> Self :: Entity { Self :: Entity :: new_unchecked ( self . as_slice ( ) . to_owned ( ) . into ( ) ) } fn new_unchecked ( slice : & 'r [ u8 ] ) -> Self { ChainEventReader ( slice ) } fn as_slice ( & self ) -> & 'r [ u8 ] { self . 0 } fn verify ( slice : & [ u8 ] , compatible : bool ) -> molecule :: error :: VerificationResult < ( ) > { use molecule :: verification_error as ve ; let slice_len = slice . len ( ) ; if slice_len < molecule :: NUMBER_SIZE { return ve ! ( Self , HeaderIsBroken , molecule :: NUMBER_SIZE , slice_len ) ; } let item_id = molecule :: unpack_number ( slice ) ; let inner_slice = & slice [ molecule :: NUMBER_SIZE .. ] ; match item_id { 0 => AppendReader :: verify ( inner_slice , compatible ) , 1 => ReorgReader :: verify ( inner_slice , compatible ) , 2 => UnchangedReader :: verify ( inner_slice , compatible ) , _ => ve ! ( Self , UnknownItem , Self :: ITEMS_COUNT , item_id ) , } ? ; Ok ( ( ) ) } }
+# [ derive ( Debug , Default ) ] pub struct ChainEventBuilder ( pub ( crate ) ChainEventUnion ) ; impl ChainEventBuilder { pub const ITEMS_COUNT : usize = 3 ; pub fn set < I > ( mut self , v : I ) -> Self where I : :: core :: convert :: Into < ChainEventUnion > { self . 0 = v . into ( ) ; self } } impl molecule :: prelude :: Builder for ChainEventBuilder { type Entity = ChainEvent ; const NAME : & 'static str = "ChainEventBuilder" ; fn expected_length ( & self ) -> usize { molecule :: NUMBER_SIZE + self . 0 . as_slice ( ) . len ( ) } fn write < W : :: molecule :: io :: Write > ( & self , writer : & mut W , ) -> :: molecule :: io :: Result < ( ) > { writer . write_all ( & molecule :: pack_number ( self . 0 . item_id ( ) ) ) ? ; writer . write_all ( self . 0 . as_slice ( ) ) } fn build ( & self ) -> Self :: Entity { let mut inner = Vec :: with_capacity ( self . expected_length ( ) ) ; self . write ( & mut inner ) . unwrap_or_else ( | _ | panic ! ( "{} build should be ok" , Self :: NAME ) ) ; ChainEvent :: new_unchecked ( inner . into ( ) ) } }
+# [ derive ( Debug , Clone ) ] pub enum ChainEventUnion { Append ( Append ) , Reorg ( Reorg ) , Unchanged ( Unchanged ) , } # [ derive ( Debug , Clone , Copy ) ] pub enum ChainEventUnionReader < 'r > { Append ( AppendReader < 'r > ) , Reorg ( ReorgReader < 'r > ) , Unchanged ( UnchangedReader < 'r > ) , } impl :: core :: default :: Default for ChainEventUnion { fn default ( ) -> Self { ChainEventUnion :: Append ( :: core :: default :: Default :: default ( ) ) } } impl :: core :: fmt :: Display for ChainEventUnion { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { match self { ChainEventUnion :: Append ( ref item ) => { write ! ( f , "{}::{}({})" , Self :: NAME , Append :: NAME , item ) } ChainEventUnion :: Reorg ( ref item ) => { write ! ( f , "{}::{}({})" , Self :: NAME , Reorg :: NAME , item ) } ChainEventUnion :: Unchanged ( ref item ) => { write ! ( f , "{}::{}({})" , Self :: NAME , Unchanged :: NAME , item ) } } } } impl < 'r > :: core :: fmt :: Display for ChainEventUnionReader < 'r > { fn fmt ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { match self { ChainEventUnionReader :: Append ( ref item ) => { write ! ( f , "{}::{}({})" , Self :: NAME , Append :: NAME , item ) } ChainEventUnionReader :: Reorg ( ref item ) => { write ! ( f , "{}::{}({})" , Self :: NAME , Reorg :: NAME , item ) } ChainEventUnionReader :: Unchanged ( ref item ) => { write ! ( f , "{}::{}({})" , Self :: NAME , Unchanged :: NAME , item ) } } } } impl ChainEventUnion { pub ( crate ) fn display_inner ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { match self { ChainEventUnion :: Append ( ref item ) => write ! ( f , "{}" , item ) , ChainEventUnion :: Reorg ( ref item ) => write ! ( f , "{}" , item ) , ChainEventUnion :: Unchanged ( ref item ) => write ! ( f , "{}" , item ) , } } } impl < 'r > ChainEventUnionReader < 'r > { pub ( crate ) fn display_inner ( & self , f : & mut :: core :: fmt :: Formatter ) -> :: core :: fmt :: Result { match self { ChainEventUnionReader :: Append ( ref item ) => write ! ( f , "{}" , item ) , ChainEventUnionReader :: Reorg ( ref item ) => write ! ( f , "{}" , item ) , ChainEventUnionReader :: Unchanged ( ref item ) => write ! ( f , "{}" , item ) , } } } impl :: core :: convert :: From < Append > for ChainEventUnion { fn from ( item : Append ) -> Self { ChainEventUnion :: Append ( item ) } } impl :: core :: convert :: From < Reorg > for ChainEventUnion { fn from ( item : Reorg ) -> Self { ChainEventUnion :: Reorg ( item ) } } impl :: core :: convert :: From < Unchanged > for ChainEventUnion { fn from ( item : Unchanged ) -> Self { ChainEventUnion :: Unchanged ( item ) } } impl < 'r > :: core :: convert :: From < AppendReader < 'r >> for ChainEventUnionReader < 'r > { fn from ( item : AppendReader < 'r > ) -> Self { ChainEventUnionReader :: Append ( item ) } } impl < 'r > :: core :: convert :: From < ReorgReader < 'r >> for ChainEventUnionReader < 'r > { fn from ( item : ReorgReader < 'r > ) -> Self { ChainEventUnionReader :: Reorg ( item ) } } impl < 'r > :: core :: convert :: From < UnchangedReader < 'r >> for ChainEventUnionReader < 'r > { fn from ( item : UnchangedReader < 'r > ) -> Self { ChainEventUnionReader :: Unchanged ( item ) } } impl ChainEventUnion { pub const NAME : & 'static str = "ChainEventUnion" ; pub fn as_bytes ( & self ) -> molecule :: bytes :: Bytes { match self { ChainEventUnion :: Append ( item ) => item . as_bytes ( ) , ChainEventUnion :: Reorg ( item ) => item . as_bytes ( ) , ChainEventUnion :: Unchanged ( item ) => item . as_bytes ( ) , } } pub fn as_slice ( & self ) -> & [ u8 ] { match self { ChainEventUnion :: Append ( item ) => item . as_slice ( ) , ChainEventUnion :: Reorg ( item ) => item . as_slice ( ) , ChainEventUnion :: Unchanged ( item ) => item . as_slice ( ) , } } pub fn item_id ( & self ) -> molecule :: Number { match self { ChainEventUnion :: Append ( _ ) => 0 , ChainEventUnion :: Reorg ( _ ) => 1 , ChainEventUnion :: Unchanged ( _ ) => 2 , } } pub fn item_name ( & self ) -> & str { match self { ChainEventUnion :: Append ( _ ) => "Append" , ChainEventUnion :: Reorg ( _ ) => "Reorg" , ChainEventUnion :: Unchanged ( _ ) => "Unchanged" , } } pub fn as_reader < 'r > ( & 'r self ) -> ChainEventUnionReader < 'r > { match self { ChainEventUnion :: Append ( item ) => item . as_reader ( ) . into ( ) , ChainEventUnion :: Reorg ( item ) => item . as_reader ( ) . into ( ) , ChainEventUnion :: Unchanged ( item ) => item . as_reader ( ) . into ( ) , } } } impl < 'r > ChainEventUnionReader < 'r > { pub const NAME : & 'r str = "ChainEventUnionReader" ; pub fn as_slice ( & self ) -> & 'r [ u8 ] { match self { ChainEventUnionReader :: Append ( item ) => item . as_slice ( ) , ChainEventUnionReader :: Reorg ( item ) => item . as_slice ( ) , ChainEventUnionReader :: Unchanged ( item ) => item . as_slice ( ) , } } pub fn item_id ( & self ) -> molecule :: Number { match self { ChainEventUnionReader :: Append ( _ ) => 0 , ChainEventUnionReader :: Reorg ( _ ) => 1 , ChainEventUnionReader :: Unchanged ( _ ) => 2 , } } pub fn item_name ( & self ) -> & str { match self { ChainEventUnionReader :: Append ( _ ) => "Append" , ChainEventUnionReader :: Reorg ( _ ) => "Reorg" , ChainEventUnionReader :: Unchanged ( _ ) => "Unchanged" , } } }
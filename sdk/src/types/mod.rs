@@ -0,0 +1,4 @@
+pub mod generated;
+mod convert;
+
+pub use generated::{basic, cell_data, witness, dags_merkle_roots, double_node_with_merkle_proof};
\ No newline at end of file
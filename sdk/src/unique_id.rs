@@ -0,0 +1,68 @@
+use ckb_tool::ckb_hash::new_blake2b;
+use ckb_tool::ckb_types::{bytes::Bytes, packed::CellInput, prelude::*};
+
+pub use eth_client_constants::UNIQUE_ID_LEN;
+
+/// Calculate the unique id of a new client cell.
+///
+/// `first_input` is the first input of the creating transaction and `output_index`
+/// is the index of the client cell in the transaction outputs. Since an outpoint can
+/// only be spent once, bridges can pin to a single client by type hash + args.
+pub fn calc_unique_id(first_input: &CellInput, output_index: u64) -> [u8; UNIQUE_ID_LEN] {
+    let mut blake2b = new_blake2b();
+    blake2b.update(first_input.as_slice());
+    blake2b.update(&output_index.to_le_bytes());
+    let mut ret = [0u8; UNIQUE_ID_LEN];
+    blake2b.finalize(&mut ret);
+    ret
+}
+
+/// Build the type script args of a new client cell.
+pub fn build_type_args(first_input: &CellInput, output_index: u64) -> Bytes {
+    Bytes::from(calc_unique_id(first_input, output_index).to_vec())
+}
+
+/// Build the type script args of a new client cell which accepts header updates at most
+/// once per `min_update_interval` CKB blocks.
+///
+/// The interval follows the unique id as a u64 LE. Relayers must then spend the client
+/// cell with a relative block number `since` of at least the interval.
+pub fn build_throttled_type_args(first_input: &CellInput, output_index: u64, min_update_interval: u64) -> Bytes {
+    let mut args = calc_unique_id(first_input, output_index).to_vec();
+    args.extend_from_slice(&min_update_interval.to_le_bytes());
+    Bytes::from(args)
+}
+
+/// Build the type script args of a new client cell which only trusts the dags merkle roots
+/// cells with the type script hash `roots_type_hash`, e.g. the cells of every roots shard
+/// share a type id. `min_update_interval` is 0 for no throttling.
+pub fn build_pinned_type_args(
+    first_input: &CellInput,
+    output_index: u64,
+    min_update_interval: u64,
+    roots_type_hash: [u8; 32],
+) -> Bytes {
+    let mut args = calc_unique_id(first_input, output_index).to_vec();
+    args.extend_from_slice(&min_update_interval.to_le_bytes());
+    args.extend_from_slice(&roots_type_hash);
+    Bytes::from(args)
+}
+
+/// Build the type script args of a new client cell which accepts batches where the headers
+/// buried by at least `pow_skip_depth` later headers of the batch omit their ethash proofs.
+///
+/// Those headers are decoded and linked like the others, their difficulty is bounded by the next
+/// header with proofs and not added to the total difficulty. The last `pow_skip_depth` headers of
+/// every batch carry their proofs. `roots_type_hash` is zero to trust any dags merkle roots cell,
+/// and `min_update_interval` is 0 for no throttling.
+pub fn build_pow_skip_type_args(
+    first_input: &CellInput,
+    output_index: u64,
+    min_update_interval: u64,
+    roots_type_hash: [u8; 32],
+    pow_skip_depth: u64,
+) -> Bytes {
+    let mut args = build_pinned_type_args(first_input, output_index, min_update_interval, roots_type_hash).to_vec();
+    args.extend_from_slice(&pow_skip_depth.to_le_bytes());
+    Bytes::from(args)
+}
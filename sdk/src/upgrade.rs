@@ -0,0 +1,85 @@
+use crate::unique_id::calc_unique_id;
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{Capacity, CapacityResult, DepType, ScriptHashType, TransactionBuilder, TransactionView},
+    packed::{CellDep, CellInput, CellOutput, OutPoint, Script},
+    prelude::*,
+};
+
+/// The code hash of the type id script, "TYPE_ID" in ascii.
+pub const TYPE_ID_CODE_HASH: [u8; 32] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x54, 0x59, 0x50, 0x45, 0x5f,
+    0x49, 0x44,
+];
+
+/// Build the type id script of a new contract cell.
+///
+/// The args are calculated from the first input and the output index like the unique id
+/// of a client cell, so the script hash stays the same across upgrades of the binary.
+pub fn build_type_id_script(first_input: &CellInput, output_index: u64) -> Script {
+    Script::new_builder()
+        .code_hash(TYPE_ID_CODE_HASH.pack())
+        .hash_type(ScriptHashType::Type.into())
+        .args(Bytes::from(calc_unique_id(first_input, output_index).to_vec()).pack())
+        .build()
+}
+
+/// Build the type script of a client cell which runs the contract deployed with `type_id_script`.
+///
+/// The client cell follows upgrades of the contract.
+pub fn client_type_script(type_id_script: &Script, unique_id: &[u8]) -> Script {
+    Script::new_builder()
+        .code_hash(type_id_script.calc_script_hash())
+        .hash_type(ScriptHashType::Type.into())
+        .args(Bytes::from(unique_id.to_vec()).pack())
+        .build()
+}
+
+/// Build the type script of a client cell pinned to the data hash of `contract_binary`.
+///
+/// The client cell can not be upgraded, so it doesn't trust the owner of the contract cell.
+pub fn pinned_client_type_script(contract_binary: &Bytes, unique_id: &[u8]) -> Script {
+    Script::new_builder()
+        .code_hash(CellOutput::calc_data_hash(contract_binary))
+        .hash_type(ScriptHashType::Data.into())
+        .args(Bytes::from(unique_id.to_vec()).pack())
+        .build()
+}
+
+/// The cell dep of a deployed contract. The out point changes on every upgrade.
+pub fn contract_cell_dep(contract_out_point: OutPoint) -> CellDep {
+    CellDep::new_builder()
+        .out_point(contract_out_point)
+        .dep_type(DepType::Code.into())
+        .build()
+}
+
+/// Build a transaction which replaces the binary of a deployed contract cell.
+///
+/// The type id script and the lock are kept, so the live client cells run `new_binary`
+/// from then on without being recreated. The capacity is increased if the new binary
+/// is larger; the caller adds the inputs paying for it and the fee, and unlocks the
+/// contract cell.
+pub fn build_upgrade_tx(
+    contract_out_point: OutPoint,
+    contract_output: &CellOutput,
+    new_binary: Bytes,
+) -> CapacityResult<TransactionView> {
+    let exact_output = contract_output
+        .clone()
+        .as_builder()
+        .build_exact_capacity(Capacity::bytes(new_binary.len())?)?;
+    let exact_capacity: Capacity = exact_output.capacity().unpack();
+    let capacity: Capacity = contract_output.capacity().unpack();
+    let output = if exact_capacity > capacity {
+        exact_output
+    } else {
+        contract_output.clone()
+    };
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new(contract_out_point, 0))
+        .output(output)
+        .output_data(new_binary.pack())
+        .build();
+    Ok(tx)
+}
@@ -4,23 +4,182 @@ use crate::eth_client::types::{
 use crate::*;
 use super::types::*;
 use ckb_testtool::{builtin::ALWAYS_SUCCESS, context::Context};
-use ckb_tool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_tool::ckb_types::{bytes::Bytes, core::{HeaderBuilder, TransactionBuilder}, packed::*, prelude::*};
 use molecule::prelude::*;
 use std::convert::TryInto;
+use eth_client_sdk::unique_id::build_type_args;
 
 pub const MAX_CYCLES: u64 = 100_000_000_000;
 pub const PLEDGE: u64 = 10000;
 pub const XT_CELL_CAPACITY: u64 = 200;
 
 pub fn run_test_case(case: TestCase) {
+    run_named_test_case("test case", case)
+}
+
+/// run the case like `run_test_case`, a failed assertion names the case.
+pub fn run_named_test_case(name: &str, case: TestCase) {
+    let witness_data = witness_bytes(&case.witness);
+    let dep_data = dep_bytes(&case.cell_deps_data);
+
+    let res = verify_update_tx(
+        case.input_capacity,
+        case.output_capacity,
+        case.input_data,
+        case.output_data,
+        witness_data,
+        case.chain_event,
+        vec![dep_data],
+        case.type_args,
+        case.since,
+        case.output_lock_args,
+        case.header_dep_timestamp,
+    );
+    dbg!(&res);
+    match res {
+        Ok(_cycles) => assert_eq!(case.expect_return_code, 0, "{}", name),
+        Err(err) => assert!(check_err(err, case.expect_return_code), "{}", name),
+    }
+}
+
+/// the molecule `Witness` of the test case.
+pub fn witness_bytes(case_witness: &Witness) -> Bytes {
+    let proof_json_vec: Vec<DoubleNodeWithMerkleProofJson> =
+        case_witness.merkle_proof.iter().map(DoubleNodeWithMerkleProofJson::from).collect();
+    let mut merkle_proofs: Vec<double_node_with_merkle_proof::DoubleNodeWithMerkleProof> = vec![];
+    for i in 0..proof_json_vec.len() {
+        let proof = &proof_json_vec[i];
+        let p:double_node_with_merkle_proof::DoubleNodeWithMerkleProof = (*proof).clone().try_into().unwrap();
+        merkle_proofs.push(p);
+    }
+    let mut proofs = vec![];
+    for i in 0..merkle_proofs.len() {
+        proofs.push(basic::Bytes::from(merkle_proofs[i].as_slice().to_vec()));
+    }
+    witness::Witness::new_builder()
+        .header(case_witness.header.clone().into())
+        .merkle_proof(BytesVec::new_builder().set(proofs).build())
+        .cell_dep_index_list(case_witness.cell_dep_index_list.clone().into())
+        .expected_tail_hash(case_witness.expected_tail_hash.clone().into())
+        .build()
+        .as_bytes()
+}
+
+/// the molecule `DagsMerkleRoots` of the dep cell.
+pub fn dep_bytes(dep_data_raw: &RootsCollectionRaw) -> Bytes {
+    let mut dag_root = vec![];
+    for i in 0..dep_data_raw.dag_merkle_roots.len() {
+        dag_root.push(hex::encode(&dep_data_raw.dag_merkle_roots[i].0).clone());
+    }
+    let dep_data_string = RootsCollectionJson{
+        dag_merkle_roots: dag_root,
+    };
+    let dep_data: dags_merkle_roots::DagsMerkleRoots = dep_data_string.try_into().unwrap();
+    dep_data.as_bytes()
+}
+
+/// save the cases with their whole transactions, so long scenarios can be generated once and
+/// replayed by `replay_snapshot`.
+pub fn save_snapshot(path: &str, cases: &[(&str, &TestCase)]) {
+    let snapshot = Snapshot {
+        version: 1,
+        cases: cases.iter().map(|(name, case)| case.to_snapshot(name)).collect(),
+    };
+    serde_json::to_writer_pretty(std::fs::File::create(std::path::Path::new(path)).unwrap(), &snapshot).unwrap()
+}
+
+pub fn replay_snapshot(path: &str) {
+    for (name, case) in TestCase::from_snapshot(path) {
+        run_named_test_case(&name, case);
+    }
+}
+
+/// run a conformance vector, the data cell deps are deployed before the contract so
+/// their indexes are the same across implementations.
+pub fn run_conformance_vector(cell_deps: &[Hex], vector: &ConformanceVector) {
+    let res = verify_update_tx(
+        100000,
+        100000,
+        vector.input_data.0.clone().into(),
+        vector.output_data.0.clone().into(),
+        vector.witness.0.clone().into(),
+        None,
+        cell_deps.iter().map(|dep| dep.0.clone().into()).collect(),
+        Bytes::new(),
+        0,
+        Bytes::new(),
+        None,
+    );
+    dbg!(&vector.name, &res);
+    match res {
+        Ok(_cycles) => assert_eq!(vector.expected_error, 0, "{}", vector.name),
+        Err(err) => assert!(check_err(err, vector.expected_error), "{}", vector.name),
+    }
+}
+
+/// spend the client cell without a witness, the output capacity is doubled as in a top-up. the
+/// output is locked by the always success lock with `output_lock_args`, see `TestCase`.
+pub fn run_touch_case(input_data: Bytes, output_data: Bytes, output_lock_args: Bytes, expect_return_code: i8) {
+    let res = verify_update_tx(100000, 200000, input_data, output_data, Bytes::new(), None, vec![], Bytes::new(), 0, output_lock_args, None);
+    dbg!(&res);
+    match res {
+        Ok(_cycles) => assert_eq!(expect_return_code, 0),
+        Err(err) => assert!(check_err(err, expect_return_code)),
+    }
+}
+
+/// update the client cell with a molecule `input_type` of the client cell witness built by the test, e.g. a
+/// `VersionedWitness` of a batch, under the client type script args `type_args`.
+pub fn run_witness_case(
+    input_data: Bytes,
+    output_data: Bytes,
+    witness: Bytes,
+    type_args: Bytes,
+    cell_deps_data: &RootsCollectionRaw,
+    expect_return_code: i8,
+) {
+    let res = verify_update_tx(100000, 100000, input_data, output_data, witness, None, vec![dep_bytes(cell_deps_data)], type_args, 0, Bytes::new(), None);
+    dbg!(&res);
+    match res {
+        Ok(_cycles) => assert_eq!(expect_return_code, 0),
+        Err(err) => assert!(check_err(err, expect_return_code)),
+    }
+}
+
+/// update the client cell, `witness` is the `input_type` of the client cell witness, none if empty.
+/// the `output_type` is `chain_event`, or the chain event of the cell data as the relayer builds it.
+/// a block of `header_dep_timestamp` is in the header deps if any.
+#[allow(clippy::too_many_arguments)]
+fn verify_update_tx(
+    input_capacity: u64,
+    output_capacity: u64,
+    input_data: Bytes,
+    output_data: Bytes,
+    witness: Bytes,
+    chain_event: Option<Bytes>,
+    cell_deps_data: Vec<Bytes>,
+    type_args: Bytes,
+    since: u64,
+    output_lock_args: Bytes,
+    header_dep_timestamp: Option<u64>,
+) -> Result<u64, ckb_tool::ckb_error::Error> {
+    let chain_event = chain_event.or_else(|| {
+        eth_client_sdk::relay::chain_event(&input_data, &output_data)
+            .ok()
+            .map(|event| event.as_slice().to_vec().into())
+    });
     let mut context = Context::default();
+    let data_deps: Vec<CellDep> = cell_deps_data
+        .into_iter()
+        .map(|data| CellDep::new_builder().out_point(context.deploy_cell(data)).build())
+        .collect();
     let typescript_bin: Bytes = Loader::default().load_binary("eth-client");
     let typescript_out_point = context.deploy_cell(typescript_bin);
     let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
 
     // prepare scripts
     let typescript = context
-        .build_script(&typescript_out_point, Default::default())
+        .build_script(&typescript_out_point, type_args)
         .expect("script");
     let typescript_dep = CellDep::new_builder()
         .out_point(typescript_out_point)
@@ -28,97 +187,115 @@ pub fn run_test_case(case: TestCase) {
     let always_success_lockscript = context
         .build_script(&always_success_out_point, Default::default())
         .expect("script");
+    let output_lockscript = context
+        .build_script(&always_success_out_point, output_lock_args)
+        .expect("script");
     let always_success_lockscript_dep = CellDep::new_builder()
         .out_point(always_success_out_point)
         .build();
 
     let input_cell_out_point = context.create_cell(
         CellOutput::new_builder()
-            .capacity(case.input_capacity.pack())
-            .lock(always_success_lockscript.clone())
+            .capacity(input_capacity.pack())
+            .lock(always_success_lockscript)
             .type_(Some(typescript.clone()).pack())
             .build(),
-        case.input_data,
+        input_data,
     );
     let input_cell = CellInput::new_builder()
         .previous_output(input_cell_out_point)
+        .since(since.pack())
         .build();
     let inputs = vec![input_cell];
     let outputs = vec![CellOutput::new_builder()
-        .capacity(case.output_capacity.pack())
+        .capacity(output_capacity.pack())
         .type_(Some(typescript.clone()).pack())
-        .lock(always_success_lockscript.clone())
+        .lock(output_lockscript)
         .build()];
-    let outputs_data = vec![case.output_data];
-    let proof_vec = case.witness.merkle_proof;
-    let mut proof_json_vec = vec![];
-    for i in 0..proof_vec.len() {
-        let dag_nodes = &proof_vec[i].dag_nodes;
-        let mut dag_nodes_string = vec![];
-        for j in 0..dag_nodes.len() {
-            dag_nodes_string.push(hex::encode(dag_nodes[j].0));
-        }
-        let proof = &proof_vec[i].proof;
-        let mut proof_string = vec![];
-        for j in 0..proof.len() {
-            proof_string.push(hex::encode(proof[j].0));
-        }
-        proof_json_vec.push(DoubleNodeWithMerkleProofJson{
-            dag_nodes: dag_nodes_string,
-            proof: proof_string,
+    let outputs_data = vec![output_data];
+    let header_deps: Vec<Byte32> = header_dep_timestamp
+        .map(|timestamp| {
+            let header = HeaderBuilder::default().timestamp(timestamp.pack()).build();
+            context.insert_header(header.clone());
+            header.hash()
         })
-    }
-    let mut merkle_proofs: Vec<double_node_with_merkle_proof::DoubleNodeWithMerkleProof> = vec![];
-    for i in 0..proof_json_vec.len() {
-        let proof = &proof_json_vec[i];
-        let p:double_node_with_merkle_proof::DoubleNodeWithMerkleProof = (*proof).clone().try_into().unwrap();
-        merkle_proofs.push(p);
-    }
-    let mut proofs = vec![];
-    for i in 0..merkle_proofs.len() {
-        proofs.push(basic::Bytes::from(merkle_proofs[i].as_slice().to_vec()));
-    }
-    let witness_data = witness::Witness::new_builder()
-        .header(case.witness.header.into())
-        .merkle_proof(BytesVec::new_builder().set(proofs).build())
-        .cell_dep_index_list(case.witness.cell_dep_index_list.into())
-        .build();
+        .into_iter()
+        .collect();
     let witness = WitnessArgs::new_builder()
-        .input_type(Some(witness_data.as_bytes()).pack())
+        .input_type(if witness.is_empty() { None } else { Some(witness) }.pack())
+        .output_type(chain_event.pack())
         .build();
-    let dep_data_raw= case.cell_deps_data;
-    let mut dag_root = vec![];
-    for i in 0..dep_data_raw.dag_merkle_roots.len() {
-        dag_root.push(hex::encode(&dep_data_raw.dag_merkle_roots[i].0).clone());
-    }
-    let dep_data_string = RootsCollectionJson{
-        dag_merkle_roots: dag_root,
-    };
-    let dep_data: dags_merkle_roots::DagsMerkleRoots = dep_data_string.try_into().unwrap();
-    let data_out_point = context.deploy_cell(dep_data.as_bytes());
-    let data_dep = CellDep::new_builder().out_point(data_out_point).build();
 
     let tx = TransactionBuilder::default()
         .inputs(inputs)
         .outputs(outputs)
         .outputs_data(outputs_data.pack())
-        .cell_dep(data_dep)
+        .cell_deps(data_deps)
         .cell_dep(typescript_dep)
         .cell_dep(always_success_lockscript_dep)
         .witness(witness.as_bytes().pack())
+        .header_deps(header_deps)
+        .build();
+
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+pub fn run_create_case(output_data: molecule::bytes::Bytes, expect_return_code: i8) {
+    let mut context = Context::default();
+    let typescript_bin: Bytes = Loader::default().load_binary("eth-client");
+    let typescript_out_point = context.deploy_cell(typescript_bin);
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+
+    let typescript_dep = CellDep::new_builder()
+        .out_point(typescript_out_point.clone())
+        .build();
+    let always_success_lockscript = context
+        .build_script(&always_success_out_point, Default::default())
+        .expect("script");
+    let always_success_lockscript_dep = CellDep::new_builder()
+        .out_point(always_success_out_point)
+        .build();
+
+    let input_cell_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(always_success_lockscript.clone())
+            .build(),
+        Bytes::new(),
+    );
+    let input_cell = CellInput::new_builder()
+        .previous_output(input_cell_out_point)
+        .build();
+    // the client cell is the first output, bind it to the first input.
+    let typescript = context
+        .build_script(&typescript_out_point, build_type_args(&input_cell, 0))
+        .expect("script");
+    let outputs = vec![CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .type_(Some(typescript).pack())
+        .lock(always_success_lockscript)
+        .build()];
+    let outputs_data = vec![output_data];
+
+    let tx = TransactionBuilder::default()
+        .input(input_cell)
+        .outputs(outputs)
+        .outputs_data(outputs_data.pack())
+        .cell_dep(typescript_dep)
+        .cell_dep(always_success_lockscript_dep)
         .build();
 
     let res = context.verify_tx(&tx, MAX_CYCLES);
     dbg!(&res);
     match res {
-        Ok(_cycles) => assert_eq!(case.expect_return_code, 0),
-        Err(err) => assert!(check_err(err, case.expect_return_code)),
+        Ok(_cycles) => assert_eq!(expect_return_code, 0),
+        Err(err) => assert!(check_err(err, expect_return_code)),
     }
 }
 
 pub fn check_err(err: ckb_tool::ckb_error::Error, code: i8) -> bool {
     let get = format!("{}", err);
     let expected = format!("Script(ValidationFailure({}))", code);
-    dbg!(&get, &expected);
+    dbg!(&get, &expected, eth_client_errors::diagnose(&get, None).map(|diagnostic| diagnostic.to_string()));
     get == expected
 }
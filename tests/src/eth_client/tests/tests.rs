@@ -1,15 +1,24 @@
 use super::*;
 use crate::eth_client::types::{
-    generated::{basic::BytesVec, Chain}
+    generated::{basic::{Byte32Vec, BytesVec}, Chain, ClientConfig, RelayerSet}
 };
-use helper::run_test_case;
+use helper::{replay_snapshot, run_conformance_vector, run_create_case, run_named_test_case, run_test_case, run_touch_case, run_witness_case, save_snapshot, witness_bytes};
 use types::*;
+use eth_client_constants::{DIGEST_LEN, PENDING_MIN_DIFFICULTY_DIVISOR};
 use ckb_testtool::{builtin::ALWAYS_SUCCESS, context::Context};
 use rlp;
 use eth_spv_lib::eth_types::*;
-use molecule::prelude::{Entity, Builder};
+use molecule::prelude::{Byte, Entity, Builder};
 
 const MAX_CYCLES: u64 = 10_000_000;
+const MAIN_HEADER_CACHE_LIMIT: u32 = 500;
+const UNCLE_HEADER_CACHE_LIMIT: u32 = 500;
+const MAX_TIMESTAMP_GAP: u64 = 3600;
+const MAX_FUTURE_DRIFT: u64 = 3600;
+const UNCLE_RETENTION_DEPTH: u64 = 256;
+const MAX_REORG_DEPTH: u64 = 256;
+// any header with a child is final, so the finalized number of the tests is not 0.
+const FINALITY_WEIGHT: u64 = 1;
 
 fn generate_correct_case(input: molecule::bytes::Bytes, output:molecule::bytes::Bytes, witness_:Witness) -> TestCase {
     TestCase {
@@ -20,53 +29,1217 @@ fn generate_correct_case(input: molecule::bytes::Bytes, output:molecule::bytes::
         witness: witness_,
         cell_deps_data: read_roots_collection_raw(),
         expect_return_code: 0,
+        chain_event: None,
+        type_args: Default::default(),
+        since: 0,
+        output_lock_args: Default::default(),
+        header_dep_timestamp: None,
     }
 }
 
 #[test]
 fn test_basic() {
+    run_test_case(append_case());
+}
+
+#[test]
+fn test_create() {
+    let case = append_case();
+    run_create_case(case.input_data.clone(), 0);
+
+    // the digest is recomputed from the tail and its parent on creation too.
+    let header_3: BlockHeader = rlp::decode(&case.witness.header).unwrap();
+    let difficulty = header_3.difficulty.0.as_u64();
+    for &(total_difficulty, code) in &[(difficulty, 0), (difficulty + 1, 8)] {
+        let mut main = main_chain(&case.output_data);
+        main[1] = create_header_info(&case.witness.header, total_difficulty);
+        // Error::InvalidCellData
+        run_create_case(set_main_chain(&case.input_data, &main), code);
+    }
+
+    // the main chain is contiguous, the tail is linked to its parent but not the parent to the oldest header.
+    let main = main_chain(&case.output_data);
+    // Error::InvalidCellData
+    run_create_case(set_main_chain(&case.input_data, &[main[1].clone(), main[0].clone(), main[1].clone()]), 8);
+
+    // the uncles are sorted by hash without duplicates.
+    let mut uncles = main.clone();
+    uncles.sort_by(|a, b| a.hash().as_slice().cmp(b.hash().as_slice()));
+    let set_uncles = |uncles: Vec<basic::HeaderInfo>| {
+        edit_cell_data(&case.input_data, |data| {
+            let headers = data.headers().as_builder().uncle(basic::HeaderInfoVec::new_builder().set(uncles).build()).build();
+            data.as_builder().headers(headers).build()
+        })
+    };
+    run_create_case(set_uncles(uncles.clone()), 0);
+    // Error::InvalidCellData
+    run_create_case(set_uncles(vec![uncles[1].clone(), uncles[0].clone()]), 8);
+    run_create_case(set_uncles(vec![uncles[0].clone(), uncles[0].clone()]), 8);
+
+    // a reorg can not be deeper than the retained uncles nor reach past the cached main chain.
+    for &max_reorg_depth in &[0, UNCLE_RETENTION_DEPTH + 1] {
+        let data = edit_cell_data(&case.input_data, |data| {
+            let config = data.config().as_builder().max_reorg_depth(max_reorg_depth.into()).build();
+            data.as_builder().config(config).build()
+        });
+        // Error::InvalidConfig
+        run_create_case(data, 16);
+    }
+    // the main chain tail can not be final.
+    let data = edit_cell_data(&case.input_data, |data| {
+        let config = data.config().as_builder().finality_weight(0u64.into()).build();
+        data.as_builder().config(config).build()
+    });
+    // Error::InvalidConfig
+    run_create_case(data, 16);
+}
+
+// a header is final once the difficulty on top of it reaches the finality weight, the digest
+// must carry the number of the newest final header.
+#[test]
+fn test_finalized_number() {
+    let case = append_case();
+    let header_3: BlockHeader = rlp::decode(&case.witness.header).unwrap();
+    let difficulty = header_3.difficulty.0.as_u64();
+    for &(finality_weight, finalized_number) in &[(difficulty, 2u64), (difficulty + 1, 0)] {
+        let weighted_case = || {
+            let config = |data: CellData| {
+                let config = data.config().as_builder().finality_weight(finality_weight.into()).build();
+                data.as_builder().config(config).build()
+            };
+            let mut case = append_case();
+            case.input_data = edit_cell_data(&case.input_data, config);
+            case.output_data = edit_cell_data(&case.output_data, config);
+            case
+        };
+        let case = weighted_case();
+        assert_eq!(eth_client_sdk::cell_data::parse_cell_data(&case.output_data).unwrap().0.finalized_number, finalized_number);
+        run_test_case(case);
+
+        let mut case = weighted_case();
+        let mut data = case.output_data.to_vec();
+        data[80..88].copy_from_slice(&(2 - finalized_number).to_le_bytes());
+        case.output_data = data.into();
+        // Error::InvalidDigest
+        case.expect_return_code = 25;
+        run_test_case(case);
+    }
+}
+
+// the number underflowed when looking for the parent of the header.
+#[test]
+fn test_header_number_zero() {
+    let mut case = append_case();
+    let header_raw = set_header_number(&case.witness.header, 0);
+    let mut main = main_chain(&case.output_data);
+    main[1] = create_header_info(&header_raw, 0);
+    case.output_data = set_main_chain(&case.output_data, &main);
+    case.witness.header = header_raw;
+    // Error::InvalidWitness
+    case.expect_return_code = 6;
+    run_test_case(case);
+}
+
+// the main tail is submitted again with an empty uncle chain, it used to underflow in the reorg branch.
+#[test]
+fn test_resubmit_main_tail() {
+    let mut case = append_case();
+    let main = main_chain(&case.output_data);
+    case.input_data = set_main_chain(&case.input_data, &main);
+    case.witness.expected_tail_hash = main[1].hash().as_slice().to_vec();
+    // Error::HeaderAlreadyExists
+    case.expect_return_code = 38;
+    run_test_case(case);
+}
+
+// every error the type script can return on an update, each from a single change to a valid append.
+// the other errors are unreachable from an update with one input and one output: ItemMissing,
+// LengthNotEnough, TxInvalid, InvalidTypeArgs (creation only), InvalidHeaderHash (the decoded hash
+// is the hash of the raw header), UnsupportedWitness and BatchTooLong (v2 witness), and the
+// errors of the reader. UpdateTooFrequent, UncleTooOld, UntrustedRootsCell, ReorgTooDeep,
+// PendingDifficultyTooLow and BuriedDifficultyTooHigh have their own tests.
+#[test]
+fn test_error_matrix() {
+    let matrix: Vec<(&str, i8, fn(&mut TestCase))> = vec![
+        // Error::IndexOutOfBound
+        ("dep cell index out of bound", 1, |case| case.witness.cell_dep_index_list = vec![9]),
+        // Error::Encoding
+        ("truncated output data", 4, |case| case.output_data = case.output_data.slice(0..10)),
+        // Error::HeaderDecodeFailed
+        ("undecodable header", 46, |case| case.witness.header = vec![1]),
+        // Error::InvalidGasLimit
+        ("gas used above the gas limit", 47, |case| case.witness.header = set_header_field(&case.witness.header, 10, &u64::MAX)),
+        // Error::InvalidDataChange
+        ("lockscript changed", 7, |case| {
+            case.output_data = edit_cell_data(&case.output_data, |data| data.as_builder().user_lockscript(Default::default()).build())
+        }),
+        // Error::InvalidCellData
+        ("header not added", 8, |case| case.output_data = case.input_data.clone()),
+        // Error::DagsMerkleRootsDataInvalid
+        ("malformed dags merkle root", 9, |case| case.cell_deps_data.dag_merkle_roots[0] = Hex(vec![0; 15])),
+        // Error::InvalidMerkleProofData
+        ("wrong dags merkle root", 10, |case| case.cell_deps_data.dag_merkle_roots[0] = Hex(vec![0; 16])),
+        // Error::ClientPaused
+        ("paused", 12, |case| {
+            case.input_data = edit_cell_data(&case.input_data, |data| data.as_builder().paused(Byte::new(1)).build());
+            case.output_data = edit_cell_data(&case.output_data, |data| data.as_builder().paused(Byte::new(1)).build());
+        }),
+        // Error::GovernanceLockMissing
+        ("paused without the governance lock", 13, |case| {
+            case.output_data = edit_cell_data(&case.input_data, |data| data.as_builder().paused(Byte::new(1)).build())
+        }),
+        // Error::InvalidRelayer
+        ("relayer lock missing", 15, |case| {
+            let relayers = RelayerSet::new_builder()
+                .lock_hashes(Byte32Vec::new_builder().push(basic::Byte32::from(vec![1; 32])).build())
+                .slot_length(1u64.into())
+                .build();
+            case.input_data = edit_cell_data(&case.input_data, |data| data.as_builder().relayers(relayers.clone()).build());
+            case.output_data = edit_cell_data(&case.output_data, |data| data.as_builder().relayers(relayers).build());
+        }),
+        // Error::InvalidConfig
+        ("zero main cache limit", 16, |case| {
+            let config = |data: CellData| {
+                let config = data.config().as_builder().main_cache_limit(0u32.into()).build();
+                data.as_builder().config(config).build()
+            };
+            case.input_data = edit_cell_data(&case.input_data, config);
+            case.output_data = edit_cell_data(&case.output_data, config);
+        }),
+        // Error::StaleWitness
+        ("stale witness", 22, |case| case.witness.expected_tail_hash = vec![0; 32]),
+        // Error::InvalidTimestamp
+        ("timestamp gap", 23, |case| {
+            let config = |data: CellData| {
+                let config = data.config().as_builder().max_timestamp_gap(1u64.into()).build();
+                data.as_builder().config(config).build()
+            };
+            case.input_data = edit_cell_data(&case.input_data, config);
+            case.output_data = edit_cell_data(&case.output_data, config);
+        }),
+        // Error::InvalidDigest
+        ("wrong digest", 25, |case| {
+            let mut data = case.output_data.to_vec();
+            data[0] ^= 1;
+            case.output_data = data.into();
+        }),
+        ("wrong finalized number", 25, |case| {
+            let mut data = case.output_data.to_vec();
+            data[80] ^= 1;
+            case.output_data = data.into();
+        }),
+        // Error::DagsMerkleRootNotFound
+        ("missing dags merkle root", 26, |case| case.cell_deps_data.dag_merkle_roots.clear()),
+        // Error::EpochRootMismatch
+        ("root of another epoch", 34, |case| case.witness.cell_dep_index_list = vec![0, 1, 0, 0, 0]),
+        // Error::NonCanonicalHeader
+        ("trailing bytes after the header", 39, |case| case.witness.header.push(0)),
+        // Error::InvalidUpdateSequence
+        ("update sequence not incremented", 40, |case| {
+            case.output_data = edit_cell_data(&case.output_data, |data| data.as_builder().update_sequence(0u64.into()).build())
+        }),
+        // Error::InvalidChainEvent
+        ("malformed chain event", 45, |case| case.chain_event = Some(molecule::bytes::Bytes::new())),
+        ("chain event of another update", 45, |case| {
+            use crate::eth_client::types::generated::witness::{ChainEvent, Unchanged};
+            let event = ChainEvent::new_builder().set(Unchanged::default()).build();
+            case.chain_event = Some(event.as_slice().to_vec().into());
+        }),
+        // Error::HeaderTooLarge
+        ("header too large", 28, |case| case.witness.header = vec![0; 2000]),
+        // Error::TooManyMerkleProofs
+        ("too many merkle proofs", 29, |case| {
+            let proofs = read_block("../tests/src/eth_client/tests/data/height-3.json".to_string()).to_double_node_with_merkle_proof_vec();
+            case.witness.merkle_proof.extend(proofs);
+        }),
+        ("65 merkle proofs", 29, |case| {
+            let mut proofs = read_block("../tests/src/eth_client/tests/data/height-3.json".to_string()).to_double_node_with_merkle_proof_vec();
+            case.witness.merkle_proof.push(proofs.remove(0));
+        }),
+        // Error::InvalidMerkleProofCount
+        ("no merkle proofs", 42, |case| case.witness.merkle_proof.clear()),
+        ("1 merkle proof", 42, |case| case.witness.merkle_proof.truncate(1)),
+        ("63 merkle proofs", 42, |case| {
+            case.witness.merkle_proof.pop();
+        }),
+    ];
+    for (name, code, mutate) in matrix {
+        let mut case = append_case();
+        mutate(&mut case);
+        case.expect_return_code = code;
+        run_named_test_case(name, case);
+    }
+}
+
+#[test]
+fn test_update_too_frequent() {
+    // the unique id is only checked on creation, the interval follows it.
+    let mut type_args = vec![0u8; 32];
+    type_args.extend_from_slice(&10u64.to_le_bytes());
+    let relative_blocks = |blocks: u64| 0x8000_0000_0000_0000 | blocks;
+
+    for &(since, code) in &[(0, 32), (relative_blocks(9), 32), (relative_blocks(10), 0)] {
+        let mut case = append_case();
+        case.type_args = type_args.clone().into();
+        case.since = since;
+        // Error::UpdateTooFrequent
+        case.expect_return_code = code;
+        run_test_case(case);
+    }
+}
+
+// the cell data is left as is, no witness is needed to top up the capacity. the cell lock is only
+// rotated with the user lockscript in the inputs, the client cell of `append_case` is locked by it.
+#[test]
+fn test_touch() {
+    let case = append_case();
+    run_touch_case(case.input_data.clone(), case.input_data.clone(), Default::default(), 0);
+    run_touch_case(case.input_data.clone(), case.input_data.clone(), vec![1].into(), 0);
+    let other_user = basic::Script::new_builder().args(basic::Bytes::from(vec![1])).build();
+    let data = edit_cell_data(&case.input_data, |data| data.as_builder().user_lockscript(other_user).build());
+    // Error::UserLockMissing
+    run_touch_case(data.clone(), data, vec![1].into(), 37);
+    // Error::InvalidWitness
+    run_touch_case(case.input_data, case.output_data, Default::default(), 6);
+}
+
+// the user lockscript is rotated by a tx with the current one in the inputs, the client cell is
+// locked by it in the tests.
+#[test]
+fn test_lockscript_rotation() {
+    let case = append_case();
+    let rotated = edit_cell_data(&case.input_data, |data| {
+        data.as_builder().user_lockscript(Default::default()).update_sequence(1u64.into()).build()
+    });
+    run_touch_case(case.input_data.clone(), rotated.clone(), Default::default(), 0);
+    // Error::UserLockMissing
+    run_touch_case(rotated, case.input_data, Default::default(), 37);
+}
+
+// only the user lockscript can change the lock of the client cell, so a relayer can not take the
+// client or lock it away. the client cell of `append_case` is locked by its user lockscript.
+#[test]
+fn test_cell_lock() {
+    let other_user = basic::Script::new_builder().args(basic::Bytes::from(vec![1])).build();
+    for &(user_lockscript, code) in &[(None, 0), (Some(&other_user), 37)] {
+        let mut case = append_case();
+        if let Some(user_lockscript) = user_lockscript {
+            case.input_data = edit_cell_data(&case.input_data, |data| data.as_builder().user_lockscript(user_lockscript.clone()).build());
+            case.output_data = edit_cell_data(&case.output_data, |data| data.as_builder().user_lockscript(user_lockscript.clone()).build());
+        }
+        case.output_lock_args = vec![1].into();
+        // Error::UserLockMissing
+        case.expect_return_code = code;
+        run_test_case(case);
+    }
+}
+
+// only the user lockscript can take capacity from the client cell, anyone else can only add to it.
+#[test]
+fn test_capacity() {
+    let other_user = basic::Script::new_builder().args(basic::Bytes::from(vec![1])).build();
+    for &(output_capacity, code) in &[(90000, 44), (110000, 0)] {
+        let mut case = append_case();
+        case.input_data = edit_cell_data(&case.input_data, |data| data.as_builder().user_lockscript(other_user.clone()).build());
+        case.output_data = edit_cell_data(&case.output_data, |data| data.as_builder().user_lockscript(other_user.clone()).build());
+        case.output_capacity = output_capacity;
+        // Error::CapacityDecreased
+        case.expect_return_code = code;
+        run_test_case(case);
+    }
+    // the client cell of `append_case` is locked by its user lockscript.
+    let mut case = append_case();
+    case.output_capacity = 90000;
+    run_test_case(case);
+    // a relayer tops up the capacity to the occupied capacity of the output, the scripts have no args.
+    let mut case = append_case();
+    case.input_data = edit_cell_data(&case.input_data, |data| data.as_builder().user_lockscript(other_user.clone()).build());
+    case.output_data = edit_cell_data(&case.output_data, |data| data.as_builder().user_lockscript(other_user.clone()).build());
+    case.output_capacity = eth_client_sdk::cell_data::occupied_capacity(case.output_data.len(), 0, Some(0));
+    assert!(case.output_capacity > case.input_capacity);
+    run_test_case(case);
+}
+
+// a zero slot length lets any relayer of the set submit, the client cell is locked by the user lockscript.
+#[test]
+fn test_relayer_allowlist() {
+    let relayers = |lock_hashes: Vec<basic::Byte32>| {
+        RelayerSet::new_builder()
+            .lock_hashes(Byte32Vec::new_builder().set(lock_hashes).build())
+            .slot_length(0u64.into())
+            .build()
+    };
+    let case = append_case();
+    let user_lockscript = CellData::from_slice(&case.input_data[DIGEST_LEN..]).unwrap().user_lockscript();
+    let user_lock_hash = basic::Byte32::from(ckb_tool::ckb_hash::blake2b_256(user_lockscript.as_slice()).to_vec());
+    for (lock_hashes, code) in vec![
+        (vec![basic::Byte32::from(vec![1; 32]), user_lock_hash], 0),
+        // Error::InvalidRelayer
+        (vec![basic::Byte32::from(vec![1; 32])], 15),
+    ] {
+        let mut case = append_case();
+        let relayers = relayers(lock_hashes);
+        case.input_data = edit_cell_data(&case.input_data, |data| data.as_builder().relayers(relayers.clone()).build());
+        case.output_data = edit_cell_data(&case.output_data, |data| data.as_builder().relayers(relayers).build());
+        case.expect_return_code = code;
+        run_test_case(case);
+    }
+}
+
+// the args pin the type hash of the dags merkle roots cells, the test dep cell has no type script.
+#[test]
+fn test_untrusted_roots_cell() {
+    let mut type_args = vec![0u8; 32];
+    type_args.extend_from_slice(&0u64.to_le_bytes());
+    type_args.extend_from_slice(&[1u8; 32]);
+    let mut case = append_case();
+    case.type_args = type_args.into();
+    // Error::UntrustedRootsCell
+    case.expect_return_code = 35;
+    run_test_case(case);
+}
+
+// the parent is a known uncle, but the new uncle is further behind the main chain tail than the
+// retention depth of the config.
+#[test]
+fn test_uncle_too_old() {
+    let retention_depth = 10u64;
+    let mut case = append_case();
+    let header_info_2 = main_chain(&case.input_data).remove(0);
+    let tail_info = create_header_info(&set_header_number(&case.witness.header, 4 + retention_depth), 0);
+    let data = edit_cell_data(&case.input_data, |data| {
+        let headers = data.headers().as_builder()
+            .main(BytesVec::new_builder().push(tail_info.as_slice().to_vec().into()).build())
+            .uncle(basic::HeaderInfoVec::new_builder().push(header_info_2).build())
+            .build();
+        let config = data.config().as_builder().uncle_retention_depth(retention_depth.into()).build();
+        data.as_builder().headers(headers).config(config).build()
+    });
+    case.input_data = data.clone();
+    case.output_data = data;
+    case.witness.expected_tail_hash = tail_info.hash().as_slice().to_vec();
+    // Error::UncleTooOld
+    case.expect_return_code = 33;
+    run_test_case(case);
+}
+
+// the header forks from the header 2, which is followed by one main header more than the max reorg
+// depth of the config.
+#[test]
+fn test_reorg_too_deep() {
+    let max_reorg_depth = 3u64;
+    let mut case = append_case();
+    let header_info_2 = main_chain(&case.input_data).remove(0);
+    let mut input_main = vec![header_info_2.clone()];
+    for number in 3..=max_reorg_depth + 3 {
+        input_main.push(create_header_info(&set_header_number(&header_info_2.header().raw_data(), number), 0));
+    }
+    let config = |data: CellData| {
+        let config = data.config().as_builder().max_reorg_depth(max_reorg_depth.into()).build();
+        data.as_builder().config(config).build()
+    };
+    case.input_data = edit_cell_data(&set_main_chain(&case.input_data, &input_main), config);
+    case.output_data = edit_cell_data(&case.output_data, config);
+    case.witness.expected_tail_hash = input_main[input_main.len() - 1].hash().as_slice().to_vec();
+    // Error::ReorgTooDeep
+    case.expect_return_code = 36;
+    run_test_case(case);
+}
+
+// a header with an unknown parent is parked with at least half the difficulty of the main chain tail,
+// which is checked before its proof of work.
+#[test]
+fn test_pending_difficulty() {
+    let mut case = append_case();
+    let tail: BlockHeader = rlp::decode(&main_chain(&case.input_data)[0].header().raw_data()).unwrap();
+    let difficulty = tail.difficulty.0.as_u64() / PENDING_MIN_DIFFICULTY_DIVISOR - 1;
+    let header_raw = set_header_field(&set_header_field(&case.witness.header, 0, &vec![1u8; 32]), 7, &difficulty);
+    case.output_data = edit_cell_data(&case.input_data, |data| {
+        let pending = BytesVec::new_builder().push(create_header_info(&header_raw, 0).as_slice().to_vec().into()).build();
+        let headers = data.headers().as_builder().pending(pending).build();
+        data.as_builder().headers(headers).update_sequence(1u64.into()).build()
+    });
+    case.witness.header = header_raw;
+    // Error::PendingDifficultyTooLow
+    case.expect_return_code = 48;
+    run_test_case(case);
+}
+
+// the header 3 replaces a sibling one unit of difficulty lighter, and not one unit heavier. the
+// cases around the tie are saved as a snapshot and replayed.
+#[test]
+fn test_fork_difficulty_boundary() {
+    let block_with_proof_3 = read_block("../tests/src/eth_client/tests/data/height-3.json".to_string());
+    let header_3: BlockHeader = rlp::decode(block_with_proof_3.header_rlp.0.as_slice()).unwrap();
+    let difficulty = header_3.difficulty.0.as_u64();
+
+    let lighter = fork_case(difficulty - 1);
+    let mut tie = fork_case(difficulty);
+    tie.expect_return_code = 8;
+    let mut heavier = fork_case(difficulty + 1);
+    heavier.expect_return_code = 8;
+
+    let path = std::env::temp_dir().join("eth-client-fork-difficulty.json");
+    save_snapshot(
+        path.to_str().unwrap(),
+        &[("reorg_lighter_by_one", &lighter), ("reorg_tie", &tie), ("reorg_heavier_by_one", &heavier)],
+    );
+    replay_snapshot(path.to_str().unwrap());
+}
+
+// the total difficulties are little endian molecule numbers. the sibling is lighter, but its low byte
+// is the higher one, so a big endian read would keep it on the main chain.
+#[test]
+fn test_total_difficulty_byte_order() {
+    let header_3: BlockHeader = rlp::decode(&append_case().witness.header).unwrap();
+    let difficulty = header_3.difficulty.0.as_u64();
+    let sibling_difficulty = (difficulty & !0xff) - 1;
+    assert!(sibling_difficulty < difficulty && sibling_difficulty & 0xff > difficulty & 0xff);
+    run_test_case(fork_case(sibling_difficulty));
+}
+
+// the header 3 reorgs the main chain ending with a sibling of the given difficulty, both on top of the header 2.
+fn fork_case(sibling_difficulty: u64) -> TestCase {
+    let mut case = append_case();
+    let header_info_2 = main_chain(&case.input_data).remove(0);
+    // the sibling differs from the header 3 by its extra data and difficulty.
+    let sibling_raw = set_header_field(&case.witness.header, 12, &b"sibling".to_vec());
+    let sibling_info = create_header_info(&set_header_field(&sibling_raw, 7, &sibling_difficulty), sibling_difficulty);
+
+    case.input_data = set_main_chain(&case.input_data, &[header_info_2, sibling_info.clone()]);
+    case.output_data = edit_cell_data(&case.output_data, |data| {
+        let headers = data.headers().as_builder()
+            .uncle(basic::HeaderInfoVec::new_builder().push(sibling_info.clone()).build())
+            .build();
+        data.as_builder().headers(headers).build()
+    });
+    case.witness.expected_tail_hash = sibling_info.hash().as_slice().to_vec();
+    case
+}
+
+// the sibling replaced by the header 3 is an uncle of the output cell, other scripts read its number
+// with `eth_client_reader::uncle_number`.
+#[test]
+fn test_uncle_number() {
+    let case = fork_case(1);
+    let sibling_info = main_chain(&case.input_data).remove(1);
+    let data = eth_client_reader::types::CellDataView::from_slice(&case.output_data).unwrap();
+    assert_eq!(eth_client_reader::uncle_number(&data, sibling_info.hash().as_slice()), Ok(Some(3)));
+    // the main chain headers are not uncles.
+    for header_info in main_chain(&case.output_data) {
+        assert_eq!(eth_client_reader::uncle_number(&data, header_info.hash().as_slice()), Ok(None));
+    }
+}
+
+// other scripts walk the main chain of the client cell with `eth_client_reader::iter_main_chain`, and
+// look a header up by its number with `eth_client_reader::find_by_number`.
+#[test]
+fn test_iter_main_chain() {
+    let case = append_case();
+    let data = eth_client_reader::types::CellDataView::from_slice(&case.output_data).unwrap();
+    let main: Vec<(u64, Vec<u8>)> = (2..).zip(main_chain(&case.output_data)).map(|(number, header_info)| (number, header_info.hash().as_slice().to_vec())).collect();
+    let iterated: Vec<(u64, Vec<u8>)> = eth_client_reader::iter_main_chain(&data)
+        .unwrap()
+        .map(|item| item.map(|(number, header_info)| (number, header_info.hash().raw_data().to_vec())).unwrap())
+        .collect();
+    assert_eq!(iterated, main);
+    for (number, hash) in &main {
+        let header_info = eth_client_reader::find_by_number(&data, *number).unwrap().unwrap();
+        assert_eq!(header_info.hash().raw_data(), &hash[..]);
+    }
+    // the numbers before the oldest cached header and after the tail.
+    assert!(eth_client_reader::find_by_number(&data, 1).unwrap().is_none());
+    assert!(eth_client_reader::find_by_number(&data, 4).unwrap().is_none());
+}
+
+// a `Locked` event of the locker contract, proven against the receipts root of a main chain header,
+// is the lock a mint script reads with `eth_client_reader::bridge_adapter::verify_lock`. the receipts
+// trie has the single receipt of the transaction 0, and the logs bloom has every bit set.
+#[test]
+fn test_bridge_lock() {
+    use eth_client_reader::{bridge_adapter::{proven_lock, BridgeLock, LOCKED_EVENT}, receipt::event_topic, types::{CellDataView, Error}};
+
+    let (locker, token, sender) = ([7u8; 20], [1u8; 20], [2u8; 20]);
+    let recipient = user_lock_script().as_slice().to_vec();
+    let word = |value: u64| {
+        let mut word = vec![0u8; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        word
+    };
+    let topic = |address: &[u8; 20]| {
+        let mut topic = vec![0u8; 12];
+        topic.extend_from_slice(address);
+        topic
+    };
+    // the amount, then the offset and the length of the recipient bytes, padded to whole words.
+    let mut log_data = [word(1000), word(64), word(recipient.len() as u64), recipient.clone()].concat();
+    log_data.resize((log_data.len() + 31) / 32 * 32, 0);
+    let mut log = rlp::RlpStream::new_list(3);
+    log.append(&locker.to_vec());
+    log.begin_list(3);
+    log.append(&event_topic(LOCKED_EVENT).0.as_bytes().to_vec()).append(&topic(&token)).append(&topic(&sender));
+    log.append(&log_data);
+    let mut receipt = rlp::RlpStream::new_list(4);
+    receipt.append(&1u8).append(&21000u64).append(&vec![0xffu8; 256]);
+    receipt.begin_list(1).append_raw(&log.out(), 1);
+    // a leaf with the whole key, the rlp encoded transaction index 0.
+    let mut leaf = rlp::RlpStream::new_list(2);
+    leaf.append(&vec![0x20u8, 0x80]).append(&receipt.out());
+    let leaf = leaf.out();
+
+    let block_with_proof_2 = read_block("../tests/src/eth_client/tests/data/height-2.json".to_string());
+    let block_with_proof_3 = read_block("../tests/src/eth_client/tests/data/height-3.json".to_string());
+    let header_3 = set_header_field(&block_with_proof_3.header_rlp.0, 5, &my_keccak256(&leaf).to_vec());
+    let header_info_3 = create_header_info(&set_header_field(&header_3, 6, &vec![0xffu8; 256]), 0);
+    let main = vec![create_header_info(&block_with_proof_2.header_rlp.0, 0).as_slice().to_vec().into(), header_info_3.as_slice().to_vec().into()];
+    let data = CellDataView::from_slice(&cell_data_bytes(&create_cell_data(main, user_lock_script()))).unwrap();
+    let mut block_hash = [0u8; 32];
+    block_hash.copy_from_slice(header_info_3.hash().as_slice());
+    let proof = eth_client_sdk::event::build_receipt_proof(block_hash, 3, 0, &[leaf], 0);
+
+    let lock = proven_lock(&data, proof.as_slice(), &locker.into()).unwrap();
+    assert_eq!(lock, BridgeLock { token: token.into(), amount: U256(1000u64.into()), recipient, depth: 0 });
+    // the log is not emitted by another locker.
+    assert_eq!(proven_lock(&data, proof.as_slice(), &[8u8; 20].into()), Err(Error::LogNotFound));
+}
+
+// the client cells of the tests are locked by the always success lock, which is also their user lockscript.
+fn user_lock_script() -> basic::Script {
     let mut context = Context::default();
     let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
     let user_lock_script = context
         .build_script(&always_success_out_point, Default::default())
         .expect("script");
-    let user_lock_script =
-        basic::Script::from_slice(user_lock_script.as_slice()).unwrap();
-
-    let blocks_with_proofs: Vec<BlockWithProofs> = ["../tests/src/eth_client/tests/data/height-2.json","../tests/src/eth_client/tests/data/height-3.json"]
-        .iter()
-        .map(|filename| read_block((&filename).to_string()))
-        .collect();
-    let block_with_proof_2 = blocks_with_proofs.get(0).expect("error");
-    let block_with_proof_3 = blocks_with_proofs.get(1).expect("error");
+    basic::Script::from_slice(user_lock_script.as_slice()).unwrap()
+}
 
-    let header_input: BlockHeader = rlp::decode(block_with_proof_2.header_rlp.0.as_slice()).unwrap();
-    let header_info_input = basic::HeaderInfo::new_builder().header(basic::Bytes::from(block_with_proof_2.header_rlp.0.clone()))
-        .total_difficulty(Default::default())
-        .hash(basic::Byte32::from_slice(header_input.hash.unwrap().0.as_bytes()).unwrap() )
-        .build();
-    let input_main_data = vec![header_info_input.as_slice().to_vec().into()];
-    let input_data = create_cell_data(input_main_data, user_lock_script.clone());
+// appending the header 3 to the main chain ending with the header 2, the valid case the other tests change.
+fn append_case() -> TestCase {
+    let block_with_proof_2 = read_block("../tests/src/eth_client/tests/data/height-2.json".to_string());
+    let block_with_proof_3 = read_block("../tests/src/eth_client/tests/data/height-3.json".to_string());
+    let header_3: BlockHeader = rlp::decode(block_with_proof_3.header_rlp.0.as_slice()).unwrap();
+    let header_info_2 = create_header_info(&block_with_proof_2.header_rlp.0, 0);
+    let header_info_3 = create_header_info(&block_with_proof_3.header_rlp.0, header_3.difficulty.0.as_u64());
 
-    let header_output: BlockHeader = rlp::decode(block_with_proof_3.header_rlp.0.as_slice()).unwrap();
-    let header_info_output = basic::HeaderInfo::new_builder().header(basic::Bytes::from(block_with_proof_3.header_rlp.0.clone()))
-        .total_difficulty(header_output.difficulty.0.as_u64().into())
-        .hash(basic::Byte32::from_slice(header_output.hash.unwrap().0.as_bytes()).unwrap() )
-        .build();
-    let output_main_data = vec![header_info_input.as_slice().to_vec().into(), header_info_output.as_slice().to_vec().into()];
-    let output_data = create_cell_data(output_main_data, user_lock_script.clone());
+    let input_data = create_cell_data(vec![header_info_2.as_slice().to_vec().into()], user_lock_script());
+    let output_main_data = vec![header_info_2.as_slice().to_vec().into(), header_info_3.as_slice().to_vec().into()];
+    let output_data = create_output_data(output_main_data, user_lock_script());
     let witness = Witness {
         cell_dep_index_list: vec![0],
         header: block_with_proof_3.header_rlp.0.clone(),
         merkle_proof: block_with_proof_3.to_double_node_with_merkle_proof_vec(),
+        expected_tail_hash: header_info_2.hash().as_slice().to_vec(),
+    };
+    generate_correct_case(cell_data_bytes(&input_data), cell_data_bytes(&output_data), witness)
+}
+
+// the header infos of the main chain of the cell data.
+fn main_chain(data: &molecule::bytes::Bytes) -> Vec<basic::HeaderInfo> {
+    CellData::from_slice(&data[DIGEST_LEN..]).unwrap().headers().main()
+        .into_iter()
+        .map(|item| basic::HeaderInfo::from_slice(&item.raw_data()).unwrap())
+        .collect()
+}
+
+// replace the main chain of the cell data, the digest is rebuilt.
+fn set_main_chain(data: &molecule::bytes::Bytes, main: &[basic::HeaderInfo]) -> molecule::bytes::Bytes {
+    let main = BytesVec::new_builder().set(main.iter().map(|info| info.as_slice().to_vec().into()).collect()).build();
+    edit_cell_data(data, |data| {
+        let headers = data.headers().as_builder().main(main).build();
+        data.as_builder().headers(headers).build()
+    })
+}
+
+// change the molecule cell data, the digest is rebuilt.
+fn edit_cell_data(data: &molecule::bytes::Bytes, edit: impl FnOnce(CellData) -> CellData) -> molecule::bytes::Bytes {
+    cell_data_bytes(&edit(CellData::from_slice(&data[DIGEST_LEN..]).unwrap()))
+}
+
+// the error table of the operators follows the contract errors, which have contiguous codes.
+#[test]
+fn test_error_codes() {
+    for (i, error) in eth_client_errors::ERRORS.iter().enumerate() {
+        assert_eq!(error.code as usize, i + 1);
+    }
+    // the table has the codes and the names of the `Error` enum of the contract, which counts from its
+    // explicit discriminants.
+    let source = std::fs::read_to_string("../reader/src/types/error.rs").unwrap();
+    let body = source.split("pub enum Error {").nth(1).unwrap().split('}').next().unwrap();
+    let mut code = 0;
+    let mut variants = vec![];
+    for line in body.lines().map(|line| line.split("//").next().unwrap().trim()).filter(|line| !line.is_empty()) {
+        let mut parts = line.trim_end_matches(',').split('=').map(str::trim);
+        let name = parts.next().unwrap().to_string();
+        code = parts.next().map_or(code + 1, |value| value.parse().unwrap());
+        variants.push((code, name));
+    }
+    let table: Vec<(i8, String)> = eth_client_errors::ERRORS.iter().map(|error| (error.code, error.name.to_string())).collect();
+    assert_eq!(table, variants);
+    let diagnostic = eth_client_errors::diagnose("Script(ValidationFailure(22))", Some(13_998_201)).unwrap();
+    assert_eq!(
+        diagnostic.to_string(),
+        "StaleWitness at tail 13998201: the witness is built on another main chain tail (error code 22)"
+    );
+    assert_eq!(eth_client_errors::diagnose("Script(ValidationFailure(-1))", None).unwrap().error, None);
+}
+
+// build -> serialize -> reader -> rebuild gives the same bytes, and no prefix or extension of the bytes
+// passes the strict verification the contract runs before its `new_unchecked` reads.
+macro_rules! assert_round_trip {
+    ($reader:ty, $entity:expr) => {{
+        let bytes = $entity.as_slice().to_vec();
+        let reader = <$reader>::from_slice(&bytes).expect(stringify!($reader));
+        assert_eq!(reader.to_entity().as_builder().build().as_slice(), &bytes[..]);
+        for len in 0..bytes.len() {
+            assert!(<$reader>::verify(&bytes[..len], false).is_err(), "{} truncated to {} bytes", stringify!($reader), len);
+        }
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert!(<$reader>::verify(&longer, false).is_err(), "{} with a trailing byte", stringify!($reader));
+    }};
+}
+
+#[test]
+fn test_molecule_round_trip() {
+    use crate::eth_client::types::generated::{cell_data, dags_merkle_roots, double_node_with_merkle_proof, witness};
+    use molecule::prelude::Reader;
+
+    let bytes = |v: &[u8]| -> basic::Bytes { v.to_vec().into() };
+    let bytes_vec = |items: &[&[u8]]| BytesVec::new_builder().set(items.iter().map(|v| bytes(v)).collect()).build();
+    let hash = |b: u8| -> basic::Byte32 { vec![b; 32].into() };
+    let header_info = |b: u8| {
+        basic::HeaderInfo::new_builder()
+            .header(bytes(&[b; 5]))
+            .total_difficulty(u64::from(b).into())
+            .hash(hash(b))
+            .ckb_timestamp(u64::from(b).into())
+            .build()
     };
-    let case = generate_correct_case(input_data.as_bytes(), output_data.as_bytes(), witness);
+    let uncles = basic::HeaderInfoVec::new_builder().push(header_info(1)).push(header_info(2)).build();
+    let chain = Chain::new_builder()
+        .main(bytes_vec(&[header_info(3).as_slice(), header_info(4).as_slice()]))
+        .uncle(uncles.clone())
+        .pending(bytes_vec(&[header_info(5).as_slice()]))
+        .build();
+    let script = basic::Script::new_builder().code_hash(hash(6)).hash_type(Byte::new(1)).args(bytes(&[7; 20])).build();
+    let relayers = RelayerSet::new_builder()
+        .lock_hashes(Byte32Vec::new_builder().push(hash(8)).push(hash(9)).build())
+        .slot_length(100u64.into())
+        .build();
+    let config = ClientConfig::new_builder()
+        .main_cache_limit(MAIN_HEADER_CACHE_LIMIT.into())
+        .uncle_cache_limit(UNCLE_HEADER_CACHE_LIMIT.into())
+        .max_timestamp_gap(MAX_TIMESTAMP_GAP.into())
+        .max_future_drift(MAX_FUTURE_DRIFT.into())
+        .uncle_retention_depth(UNCLE_RETENTION_DEPTH.into())
+        .max_reorg_depth(MAX_REORG_DEPTH.into())
+        .finality_weight(FINALITY_WEIGHT.into())
+        .build();
+    let epoch_root = dags_merkle_roots::EpochMerkleRoot::new_builder()
+        .epoch(3u64.into())
+        .root(basic::Byte16::new_builder().set([Byte::new(10); 16]).build())
+        .build();
+    let epoch_roots = dags_merkle_roots::EpochMerkleRootVec::new_builder().push(epoch_root.clone()).build();
+    let witness_header = witness::WitnessHeader::new_builder()
+        .header(bytes(&[11; 5]))
+        .merkle_proof(bytes_vec(&[&[12; 3], &[13; 4]]))
+        .build();
+    let witness_headers = witness::WitnessHeaderVec::new_builder().push(witness_header.clone()).build();
+    let witness_v1 = witness::Witness::new_builder()
+        .header(bytes(&[14; 5]))
+        .merkle_proof(bytes_vec(&[&[15; 3]]))
+        .cell_dep_index_list(bytes(&[0]))
+        .expected_tail_hash(hash(16))
+        .build();
+    let witness_v2 = witness::WitnessV2::new_builder()
+        .headers(witness_headers.clone())
+        .cell_dep_index_list(bytes(&[0, 1]))
+        .expected_tail_hash(hash(17))
+        .receipt_proofs(bytes_vec(&[&[18; 2]]))
+        .checkpoint_signatures(bytes_vec(&[&[19; 65]]))
+        .build();
+    let ancestry_proof = witness::AncestryProof::new_builder().hash(hash(20)).number(21u64.into()).build();
+
+    assert_round_trip!(basic::Byte4Reader, basic::Byte4::from(vec![1, 2, 3, 4]));
+    assert_round_trip!(basic::Byte16Reader, basic::Byte16::new_builder().set([Byte::new(1); 16]).build());
+    assert_round_trip!(basic::Byte32Reader, hash(1));
+    assert_round_trip!(basic::BytesReader, bytes(&[1, 2, 3]));
+    assert_round_trip!(basic::Uint32Reader, basic::Uint32::from(1u32));
+    assert_round_trip!(basic::Uint64Reader, basic::Uint64::from(1u64));
+    assert_round_trip!(basic::BytesVecReader, bytes_vec(&[&[1], &[2, 3]]));
+    assert_round_trip!(basic::Byte32VecReader, Byte32Vec::new_builder().push(hash(1)).build());
+    assert_round_trip!(basic::ScriptReader, script.clone());
+    assert_round_trip!(basic::HeaderInfoReader, header_info(1));
+    assert_round_trip!(basic::HeaderInfoVecReader, uncles);
+    assert_round_trip!(basic::ChainReader, chain.clone());
+    assert_round_trip!(cell_data::RelayerSetReader, relayers.clone());
+    assert_round_trip!(cell_data::ClientConfigReader, config.clone());
+    assert_round_trip!(
+        cell_data::CellDataReader,
+        CellData::new_builder()
+            .headers(chain)
+            .user_lockscript(script)
+            .paused(Byte::new(1))
+            .governance_lock_hash(hash(22))
+            .relayers(relayers)
+            .config(config)
+            .update_sequence(23u64.into())
+            .build()
+    );
+    assert_round_trip!(
+        dags_merkle_roots::DagsMerkleRootsReader,
+        dags_merkle_roots::DagsMerkleRoots::new_builder().dags_merkle_roots(bytes_vec(&[&[1; 16]])).build()
+    );
+    assert_round_trip!(dags_merkle_roots::EpochMerkleRootReader, epoch_root);
+    assert_round_trip!(dags_merkle_roots::EpochMerkleRootVecReader, epoch_roots.clone());
+    assert_round_trip!(
+        dags_merkle_roots::EpochMerkleRootsReader,
+        dags_merkle_roots::EpochMerkleRoots::new_builder().roots(epoch_roots).build()
+    );
+    assert_round_trip!(
+        double_node_with_merkle_proof::DoubleNodeWithMerkleProofReader,
+        double_node_with_merkle_proof::DoubleNodeWithMerkleProof::new_builder()
+            .dag_nodes(bytes_vec(&[&[1; 64], &[2; 64]]))
+            .proof(bytes_vec(&[&[3; 16]]))
+            .build()
+    );
+    assert_round_trip!(witness::WitnessHeaderReader, witness_header);
+    assert_round_trip!(witness::WitnessHeaderVecReader, witness_headers);
+    assert_round_trip!(witness::WitnessReader, witness_v1.clone());
+    assert_round_trip!(witness::WitnessV2Reader, witness_v2.clone());
+    assert_round_trip!(witness::VersionedWitnessReader, witness::VersionedWitness::new_builder().set(witness_v1).build());
+    assert_round_trip!(witness::VersionedWitnessReader, witness::VersionedWitness::new_builder().set(witness_v2).build());
+    assert_round_trip!(witness::AncestryProofReader, ancestry_proof.clone());
+    assert_round_trip!(
+        witness::ReceiptProofReader,
+        witness::ReceiptProof::new_builder()
+            .header(ancestry_proof)
+            .tx_index(24u64.into())
+            .proof(bytes_vec(&[&[25; 32]]))
+            .log_index(26u64.into())
+            .build()
+    );
+}
+
+// the main chain cache of the sdk rolls over 10000 appended headers without drifting. the simulated
+// headers have no proof of work, so the contract never runs: they are appended by `append_header` of the
+// sdk, and every step is checked against the rotation rule of `verify_original_chain_data` restated here.
+// `test_rolling_window_step` runs a step through the contract. run with `cargo test -- --ignored`.
+#[test]
+#[ignore]
+fn test_rolling_window() {
+    use eth_client_sdk::{cell_data::parse_cell_data, relay::append_header};
+
+    let block_with_proof_2 = read_block("../tests/src/eth_client/tests/data/height-2.json".to_string());
+    let block_with_proof_3 = read_block("../tests/src/eth_client/tests/data/height-3.json".to_string());
+    let header_info_2 = create_header_info(&block_with_proof_2.header_rlp.0, 0);
+    let mut data = cell_data_bytes(&create_cell_data(vec![header_info_2.as_slice().to_vec().into()], Default::default())).to_vec();
+    let limit = MAIN_HEADER_CACHE_LIMIT as usize;
+    let last = 10_002u64;
+    let mut full_size = None;
+    for number in 3..=last {
+        let (digest, input) = parse_cell_data(&data).unwrap();
+        let header = set_header_field(&set_header_number(&block_with_proof_3.header_rlp.0, number), 0, &digest.hash.to_vec());
+        let output_data = append_header(&data, &header).unwrap();
+        let (_, output) = parse_cell_data(&output_data).unwrap();
+        let input_main: Vec<_> = input.headers().main().into_iter().map(|item| item.raw_data().to_vec()).collect();
+        let output_main: Vec<_> = output.headers().main().into_iter().map(|item| item.raw_data().to_vec()).collect();
+        let dropped = if input_main.len() == limit { 1 } else { 0 };
+        assert_eq!(output_main.len(), input_main.len() + 1 - dropped);
+        assert_eq!(output_main[..output_main.len() - 1], input_main[dropped..]);
+        // the size only changes with the length of the rlp encoded numbers, 3 bytes from 256 on.
+        if number >= 256 + limit as u64 {
+            assert_eq!(*full_size.get_or_insert(output_data.len()), output_data.len());
+        }
+        data = output_data;
+    }
+    let (digest, output) = parse_cell_data(&data).unwrap();
+    let main = output.headers().main();
+    let oldest = basic::HeaderInfo::from_slice(&main.get(0).unwrap().raw_data()).unwrap();
+    assert_eq!(digest.number, last);
+    assert_eq!(main.len(), limit);
+    assert_eq!(rlp::Rlp::new(&oldest.header().raw_data()).val_at::<u64>(8).unwrap(), last + 1 - limit as u64);
+    assert_eq!(output.update_sequence().as_slice(), &(last - 2).to_le_bytes()[..]);
+}
+
+// the contract accepts a rotation step of the sdk: appending the header 3 to a full main chain drops the
+// oldest header. the cache limit is lowered so the main chain is full below the header 2.
+#[test]
+fn test_rolling_window_step() {
+    use eth_client_sdk::relay::append_header;
+
+    let mut case = append_case();
+    let header_info_2 = main_chain(&case.input_data).remove(0);
+    let mut main: Vec<_> = (0..2)
+        .map(|number| create_header_info(&set_header_number(&header_info_2.header().raw_data(), number), 0))
+        .collect();
+    main.push(header_info_2);
+    let limit = main.len() as u32;
+    case.input_data = edit_cell_data(&set_main_chain(&case.input_data, &main), |data| {
+        let config = data.config().as_builder().main_cache_limit(limit.into()).max_reorg_depth(2u64.into()).build();
+        data.as_builder().config(config).build()
+    });
+    case.output_data = append_header(&case.input_data, &case.witness.header).unwrap().into();
+    let raw = |infos: &[basic::HeaderInfo]| infos.iter().map(|info| info.as_slice().to_vec()).collect::<Vec<_>>();
+    assert_eq!(raw(&main_chain(&case.output_data)[..2]), raw(&main[1..]));
     run_test_case(case);
 }
 
+// a submission raced by another relayer is rebased on the cell the other relayer left.
+#[test]
+fn test_rebase_append() {
+    use eth_client_sdk::{cell_data::parse_cell_data, relay::{append_header, rebase_append, Rebase, RelayError}};
+
+    let block_with_proof_2 = read_block("../tests/src/eth_client/tests/data/height-2.json".to_string());
+    let block_with_proof_3 = read_block("../tests/src/eth_client/tests/data/height-3.json".to_string());
+    let header_info_2 = create_header_info(&block_with_proof_2.header_rlp.0, 0);
+    let data_2 = cell_data_bytes(&create_cell_data(vec![header_info_2.as_slice().to_vec().into()], Default::default())).to_vec();
+    let header_3 = block_with_proof_3.header_rlp.0.clone();
+    let data_3 = append_header(&data_2, &header_3).unwrap();
+    let header_4 = set_header_field(&set_header_number(&header_3, 4), 0, &parse_cell_data(&data_3).unwrap().0.hash.to_vec());
+    let sibling = set_header_field(&header_3, 12, &b"sibling".to_vec());
+
+    assert_eq!(rebase_append(&data_3, &header_4).unwrap(), Rebase::Output(append_header(&data_3, &header_4).unwrap()));
+    assert_eq!(rebase_append(&data_3, &header_3).unwrap(), Rebase::AlreadyRelayed);
+    assert!(matches!(rebase_append(&data_3, &sibling), Err(RelayError::NotTailChild)));
+}
+
+// a batch of the headers 2 and 3 on a client which lets the header 2 omit its proofs. the header 2 adds no
+// difficulty to the total difficulty, and it can't claim more difficulty than the header 3 with its proofs.
+#[test]
+fn test_pow_skip() {
+    use eth_client_sdk::{
+        batch::build_batch_witness,
+        fixture::BlockWithProofsJson,
+        types::witness::{VersionedWitness, VersionedWitnessUnion, WitnessHeader, WitnessHeaderVec},
+    };
+
+    let read = |height: u64| {
+        let json = std::fs::read_to_string(format!("../tests/src/eth_client/tests/data/height-{}.json", height)).unwrap();
+        serde_json::from_str::<BlockWithProofsJson>(&json).unwrap().validate().unwrap()
+    };
+    let (block_2, block_3) = (read(2), read(3));
+    let header_2: BlockHeader = rlp::decode(&block_2.header_rlp).unwrap();
+    let difficulty_3 = rlp::decode::<BlockHeader>(&block_3.header_rlp).unwrap().difficulty.0.as_u64();
+    // the parent of the header 2 is only known by its hash, its fields are the ones of the header 2.
+    let header_1 = set_header_field(&set_header_number(&block_2.header_rlp, 1), 11, &(header_2.timestamp - 1));
+    let header_info_1 = create_header_info(&header_1, 0)
+        .as_builder()
+        .hash(basic::Byte32::from_slice(header_2.parent_hash.0.as_bytes()).unwrap())
+        .build();
+    let mut type_args = vec![0u8; 32];
+    type_args.extend_from_slice(&0u64.to_le_bytes());
+    type_args.extend_from_slice(&[0u8; 32]);
+    type_args.extend_from_slice(&1u64.to_le_bytes());
+    let input_data = cell_data_bytes(&create_cell_data(vec![header_info_1.as_slice().to_vec().into()], user_lock_script()));
+    let output_data = |difficulty_2: u64| {
+        let main = vec![
+            header_info_1.as_slice().to_vec().into(),
+            create_header_info(&block_2.header_rlp, difficulty_2).as_slice().to_vec().into(),
+            create_header_info(&block_3.header_rlp, difficulty_2 + difficulty_3).as_slice().to_vec().into(),
+        ];
+        cell_data_bytes(&create_output_data(main, user_lock_script()))
+    };
+    let witness = build_batch_witness(&[block_2.clone(), block_3.clone()], 0, header_2.parent_hash.0.to_fixed_bytes(), 1).unwrap();
+    let headers = match witness.to_enum() {
+        VersionedWitnessUnion::WitnessV2(witness) => witness.headers(),
+        _ => unreachable!(),
+    };
+    assert!(headers.get(0).unwrap().merkle_proof().is_empty());
+    assert!(!headers.get(1).unwrap().merkle_proof().is_empty());
+    let cell_deps_data = read_roots_collection_raw();
+
+    run_witness_case(input_data.clone(), output_data(0), witness.as_bytes(), type_args.clone().into(), &cell_deps_data, 0);
+    // Error::InvalidCellData
+    let difficulty_2 = header_2.difficulty.0.as_u64();
+    run_witness_case(input_data.clone(), output_data(difficulty_2), witness.as_bytes(), type_args.clone().into(), &cell_deps_data, 8);
+
+    // the sdk keeps the proofs of a header above the bound, a relayer skipping them anyway is rejected.
+    let inflated = set_header_field(&block_2.header_rlp, 7, &(difficulty_3 + 1));
+    let inflated_header = WitnessHeader::new_builder().header(inflated.into()).build();
+    let witness = match witness.to_enum() {
+        VersionedWitnessUnion::WitnessV2(witness) => {
+            let headers = WitnessHeaderVec::new_builder().push(inflated_header).push(headers.get(1).unwrap()).build();
+            VersionedWitness::new_builder().set(witness.as_builder().headers(headers).build()).build()
+        }
+        _ => unreachable!(),
+    };
+    // Error::BuriedDifficultyTooHigh
+    run_witness_case(input_data, output_data(difficulty_3 + 1), witness.as_bytes(), type_args.into(), &cell_deps_data, 49);
+}
+
+// the vectors shared with other implementations of the client.
+#[test]
+fn test_conformance() {
+    let suite = read_conformance_suite();
+    assert_eq!(suite.version, 1);
+    for vector in &suite.vectors {
+        run_conformance_vector(&suite.cell_deps, vector);
+    }
+}
+
+// a London header carries the base fee after the fields of the earlier headers, its seal hash covers
+// the base fee too.
+#[test]
+fn test_decode_london_header() {
+    use eth_client_core::header::{decode_header, DecodeError};
+
+    let header_raw = append_case().witness.header;
+    let with_items = |items: &[&[u8]]| {
+        let header_rlp = rlp::Rlp::new(&header_raw);
+        let mut stream = rlp::RlpStream::new_list(header_rlp.item_count().unwrap() + items.len());
+        for item in header_rlp.iter() {
+            stream.append_raw(item.as_raw(), 1);
+        }
+        for item in items {
+            stream.append_raw(item, 1);
+        }
+        stream.out()
+    };
+    let base_fee = rlp::encode(&7u64);
+    let header = decode_header(&header_raw).unwrap();
+    let london = decode_header(&with_items(&[&base_fee[..]])).unwrap();
+    assert_eq!((london.number, london.gas_limit, london.hash.is_some()), (header.number, header.gas_limit, true));
+    assert_ne!(london.partial_hash, header.partial_hash);
+    // a base fee with a leading zero, and an item after the base fee.
+    assert!(decode_header(&with_items(&[&[0x82, 0, 7][..]])).is_err());
+    assert_eq!(decode_header(&with_items(&[&base_fee[..], &base_fee[..]])).unwrap_err(), DecodeError::NonCanonical);
+}
+
+// the gas limit follows the one of the parent, the London fork block doubles it for eip-1559.
+#[test]
+fn test_gas_bounds() {
+    use eth_client_core::header::{gas_in_bounds, GAS_LIMIT_BOUND_DIVISOR};
+
+    let parent = 15_000_000u64;
+    let bound = parent / GAS_LIMIT_BOUND_DIVISOR;
+    assert!(gas_in_bounds(parent + bound - 1, parent, Some(parent), false));
+    assert!(!gas_in_bounds(parent + bound, parent, Some(parent), false));
+    assert!(!gas_in_bounds(parent * 2, parent, Some(parent), false));
+    assert!(gas_in_bounds(parent * 2 - 1, parent * 2 - 1, Some(parent), true));
+    assert!(!gas_in_bounds(parent, parent, Some(parent), true));
+    // the gas used never exceeds the gas limit.
+    assert!(!gas_in_bounds(parent * 2, parent * 2 + 1, Some(parent), true));
+}
+
+// a segment is checked off-chain with the rules of the contract, the header 3 extends the header 2.
+#[test]
+fn test_verify_segment() {
+    use eth_client_core::{pow, verify_segment, SegmentConfig, SegmentError, TrustedAnchor};
+
+    let block_with_proof_2 = read_block("../tests/src/eth_client/tests/data/height-2.json".to_string());
+    let block_with_proof_3 = read_block("../tests/src/eth_client/tests/data/height-3.json".to_string());
+    let header_2: BlockHeader = rlp::decode(block_with_proof_2.header_rlp.0.as_slice()).unwrap();
+    let header_3: BlockHeader = rlp::decode(block_with_proof_3.header_rlp.0.as_slice()).unwrap();
+    let anchor = TrustedAnchor { hash: header_2.hash.unwrap(), number: 2, timestamp: header_2.timestamp, total_difficulty: 0 };
+    let proofs: Vec<pow::DoubleNodeWithMerkleProof> = block_with_proof_3
+        .to_double_node_with_merkle_proof_vec()
+        .into_iter()
+        .map(|proof| pow::DoubleNodeWithMerkleProof::new(proof.dag_nodes, proof.proof))
+        .collect();
+    let roots = read_roots_collection().dag_merkle_roots;
+    let config = SegmentConfig { max_timestamp_gap: MAX_TIMESTAMP_GAP, max_future_drift: MAX_FUTURE_DRIFT, dag_merkle_roots: &roots };
+    let headers = [block_with_proof_3.header_rlp.0.as_slice()];
+
+    let segment = verify_segment(&anchor, &headers, &[proofs.clone()], &config).unwrap();
+    assert_eq!(segment.hashes, vec![header_3.hash.unwrap()]);
+    assert_eq!((segment.tail.number, segment.tail.total_difficulty), (3, header_3.difficulty.0.as_u64()));
+
+    let tail = segment.tail;
+    assert_eq!(verify_segment(&tail, &headers, &[proofs.clone()], &config), Err(SegmentError::NotChild(0)));
+    assert_eq!(verify_segment(&anchor, &headers, &[proofs[1..].to_vec()], &config), Err(SegmentError::InvalidMerkleProofCount(0)));
+    assert_eq!(verify_segment(&anchor, &headers, &[], &config), Err(SegmentError::ProofsMissing));
+    let config = SegmentConfig { dag_merkle_roots: &[], ..config };
+    assert_eq!(verify_segment(&anchor, &headers, &[proofs], &config), Err(SegmentError::DagsMerkleRootNotFound(0)));
+}
+
+// malformed fixtures are rejected before they are turned into witnesses.
+#[test]
+fn test_fixture_validation() {
+    use eth_client_sdk::fixture::{BlockWithProofsJson, FixtureError};
+
+    let json = std::fs::read_to_string("../tests/src/eth_client/tests/data/height-3.json").unwrap();
+    let block: BlockWithProofsJson = serde_json::from_str(&json).unwrap();
+    assert_eq!(block.validate().unwrap().merkle_proofs().unwrap().len(), 64);
+
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["merkle_proof"] = value["merkle_proofs"].clone();
+    assert!(serde_json::from_value::<BlockWithProofsJson>(value).is_err());
+
+    let mut short = block.clone();
+    short.elements.pop();
+    assert!(matches!(short.validate(), Err(FixtureError::InvalidProofShape { elements: 255, .. })));
+    let mut long_word = block.clone();
+    long_word.merkle_proofs[0] = format!("0x{}", "11".repeat(17));
+    assert!(matches!(long_word.validate(), Err(FixtureError::InvalidWord { field: "merkle_proofs", .. })));
+    let mut next_version = block;
+    next_version.version = 2;
+    assert_eq!(next_version.validate().unwrap_err(), FixtureError::UnsupportedVersion(2));
+}
+
+// the unsigned relay tx of the sdk, as printed by `relay-one` of the cli, passes the contract once the
+// relayer adds the deps of the locks. the since of the input follows the min update interval of the client.
+#[test]
+fn test_relay_tx_skeleton() {
+    use ckb_tool::ckb_types::{bytes::Bytes, packed, prelude::*};
+    use eth_client_sdk::{
+        cell_data::parse_cell_data,
+        fixture::BlockWithProofsJson,
+        relay::{append_header, chain_event},
+        relay_tx::{relay_tx_skeleton, set_chain_event, set_relay_witness, top_up_client_capacity, ClientCell},
+    };
+
+    let case = append_case();
+    let json = std::fs::read_to_string("../tests/src/eth_client/tests/data/height-3.json").unwrap();
+    let block = serde_json::from_str::<BlockWithProofsJson>(&json).unwrap().validate().unwrap();
+    let mut context = Context::default();
+    let contract = context.deploy_cell(crate::Loader::default().load_binary("eth-client"));
+    let always_success = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let dags_merkle_roots = context.deploy_cell(helper::dep_bytes(&case.cell_deps_data));
+    let mut type_args = vec![0u8; 32];
+    type_args.extend_from_slice(&10u64.to_le_bytes());
+    let output = packed::CellOutput::new_builder()
+        .capacity(100_000_000_000_000u64.pack())
+        .lock(context.build_script(&always_success, Default::default()).unwrap())
+        .type_(Some(context.build_script(&contract, type_args.into()).unwrap()).pack())
+        .build();
+    let client = ClientCell {
+        out_point: context.create_cell(output.clone(), case.input_data.clone()),
+        output,
+        data: case.input_data.clone(),
+    };
+
+    let output_data = append_header(&client.data, &block.header_rlp).unwrap();
+    let event = chain_event(&client.data, &output_data).unwrap();
+    let tx = relay_tx_skeleton(&client, &contract, &dags_merkle_roots, &output_data);
+    let (digest, _) = parse_cell_data(&client.data).unwrap();
+    let tx = set_relay_witness(&tx, &client.out_point, &dags_merkle_roots, &block.header_rlp, &block.merkle_proofs().unwrap(), digest.hash).unwrap();
+    let tx = set_chain_event(&tx, 0, &event).unwrap();
+    let tx = top_up_client_capacity(&tx, 0).unwrap();
+    let since: u64 = tx.inputs().get(0).unwrap().since().unpack();
+    assert_eq!(since, 0x8000_0000_0000_0000 | 10);
+    assert_eq!(tx.outputs_data().get(0).unwrap().raw_data(), Bytes::from(output_data));
+
+    let always_success_dep = packed::CellDep::new_builder().out_point(always_success).build();
+    let tx = tx.as_advanced_builder().cell_dep(always_success_dep).build();
+    context.verify_tx(&tx, helper::MAX_CYCLES).unwrap();
+}
+
+// saved cases are replayed as the same transactions, including the ones of a non default case.
+#[test]
+fn test_snapshot_replay() {
+    let mut type_args = vec![0u8; 32];
+    type_args.extend_from_slice(&10u64.to_le_bytes());
+    let mut interval = append_case();
+    interval.type_args = type_args.into();
+    interval.since = 0x8000_0000_0000_0000 | 9;
+    interval.output_capacity = 110000;
+    // Error::UpdateTooFrequent
+    interval.expect_return_code = 32;
+
+    let path = std::env::temp_dir().join("eth-client-snapshot.json");
+    save_snapshot(path.to_str().unwrap(), &[("append_main_tail", &append_case()), ("update_too_frequent", &interval)]);
+    let cases = TestCase::from_snapshot(path.to_str().unwrap());
+    let (name, case) = &cases[1];
+    assert_eq!(name, "update_too_frequent");
+    assert_eq!(
+        (&case.type_args, case.since, case.output_capacity, case.expect_return_code),
+        (&interval.type_args, interval.since, interval.output_capacity, interval.expect_return_code)
+    );
+    assert_eq!(witness_bytes(&case.witness), witness_bytes(&interval.witness));
+    replay_snapshot(path.to_str().unwrap());
+}
+
+// a new header is stamped with the timestamp of the first header dep of the relay tx, 0 without one.
+#[test]
+fn test_ckb_timestamp() {
+    let timestamp = 1_600_000_000_000u64;
+    let stamp_tail = |data: &molecule::bytes::Bytes, ckb_timestamp: u64| {
+        let mut main = main_chain(data);
+        let tail = main.len() - 1;
+        main[tail] = main[tail].clone().as_builder().ckb_timestamp(ckb_timestamp.into()).build();
+        set_main_chain(data, &main)
+    };
+    for &(header_dep_timestamp, ckb_timestamp, code) in &[
+        (Some(timestamp), timestamp, 0),
+        (None, 0, 0),
+        // Error::InvalidCellData
+        (Some(timestamp), 0, 8),
+        (Some(timestamp), timestamp - 1, 8),
+        (None, timestamp, 8),
+    ] {
+        let mut case = append_case();
+        case.output_data = stamp_tail(&case.output_data, ckb_timestamp);
+        case.header_dep_timestamp = header_dep_timestamp;
+        case.expect_return_code = code;
+        run_test_case(case);
+    }
+}
+
+fn create_header_info(header_raw: &[u8], total_difficulty: u64) -> basic::HeaderInfo {
+    let header: BlockHeader = rlp::decode(header_raw).unwrap();
+    basic::HeaderInfo::new_builder().header(basic::Bytes::from(header_raw.to_vec()))
+        .total_difficulty(total_difficulty.into())
+        .hash(basic::Byte32::from_slice(header.hash.unwrap().0.as_bytes()).unwrap())
+        .build()
+}
+
+// re-encode the rlp header with another block number, which is the 9th field.
+fn set_header_number(header_raw: &[u8], number: u64) -> Vec<u8> {
+    set_header_field(header_raw, 8, &number)
+}
+
+fn set_header_field<T: rlp::Encodable>(header_raw: &[u8], index: usize, value: &T) -> Vec<u8> {
+    let header_rlp = rlp::Rlp::new(header_raw);
+    let mut stream = rlp::RlpStream::new_list(header_rlp.item_count().unwrap());
+    for (i, item) in header_rlp.iter().enumerate() {
+        if i == index {
+            stream.append(value);
+        } else {
+            stream.append_raw(item.as_raw(), 1);
+        }
+    }
+    stream.out()
+}
+
 fn create_cell_data(data: Vec<basic::Bytes>, script: basic::Script) -> CellData {
     CellData::new_builder()
         .headers(Chain::new_builder().main(BytesVec::new_builder().set(data).build()).build())
         .user_lockscript(script)
+        .config(
+            ClientConfig::new_builder()
+                .main_cache_limit(MAIN_HEADER_CACHE_LIMIT.into())
+                .uncle_cache_limit(UNCLE_HEADER_CACHE_LIMIT.into())
+                .max_timestamp_gap(MAX_TIMESTAMP_GAP.into())
+                .max_future_drift(MAX_FUTURE_DRIFT.into())
+                .uncle_retention_depth(UNCLE_RETENTION_DEPTH.into())
+                .max_reorg_depth(MAX_REORG_DEPTH.into())
+                .finality_weight(FINALITY_WEIGHT.into())
+                .build(),
+        )
         .build()
 }
+
+// the output of an update of the cell data built by `create_cell_data`.
+fn create_output_data(data: Vec<basic::Bytes>, script: basic::Script) -> CellData {
+    create_cell_data(data, script).as_builder().update_sequence(1u64.into()).build()
+}
+
+// prefix the digest of the main chain tail, see `ChainDigest` of the contract.
+fn cell_data_bytes(cell_data: &CellData) -> molecule::bytes::Bytes {
+    let main = cell_data.headers().main();
+    let tail = basic::HeaderInfo::from_slice(&main.get(main.len() - 1).unwrap().raw_data()).unwrap();
+    let header: BlockHeader = rlp::decode(&tail.header().raw_data()).unwrap();
+    let mut data = vec![];
+    data.extend_from_slice(&header.number.to_le_bytes());
+    data.extend_from_slice(tail.hash().as_slice());
+    data.extend_from_slice(tail.total_difficulty().as_slice());
+    // the mmr root is reserved and always zero.
+    data.extend_from_slice(&[0u8; 32]);
+    let mut total_difficulty = [0u8; 8];
+    total_difficulty.copy_from_slice(tail.total_difficulty().as_slice());
+    let finalized_number = eth_client_sdk::cell_data::finalized_number(cell_data, header.number, u64::from_le_bytes(total_difficulty)).unwrap();
+    data.extend_from_slice(&finalized_number.to_le_bytes());
+    data.extend_from_slice(cell_data.as_slice());
+    data.into()
+}
@@ -3,9 +3,10 @@ use ckb_tool::ckb_types::{packed::*, prelude::*};
 use molecule::prelude::*;
 use eth_spv_lib::eth_types::*;
 use crate::eth_client::types::generated::{double_node_with_merkle_proof, dags_merkle_roots, basic::{BytesVec, Bytes}};
-use serde::{Deserialize, Serialize, Deserializer};
+use serde::{Deserialize, Serialize, Deserializer, Serializer};
 use std::convert::TryFrom;
 use hex::FromHex;
+use eth_client_sdk::fixture;
 
 pub struct Output {
     pub typescript: Script,
@@ -18,6 +19,7 @@ pub struct Witness {
     pub cell_dep_index_list: Vec<u8>,
     pub header: Vec<u8>,
     pub merkle_proof: Vec<DoubleNodeWithMerkleProof>,
+    pub expected_tail_hash: Vec<u8>,
 }
 
 pub struct TestCase {
@@ -28,6 +30,114 @@ pub struct TestCase {
     pub witness: Witness,
     pub cell_deps_data: RootsCollectionRaw,
     pub expect_return_code: i8,
+    /// the `output_type` witness, the chain event of the cell data if none.
+    pub chain_event: Option<molecule::bytes::Bytes>,
+    /// the args of the client type script.
+    pub type_args: molecule::bytes::Bytes,
+    /// the `since` of the client cell input.
+    pub since: u64,
+    /// the args of the always success lock of the client cell output. the input lock has none, so
+    /// other args rotate the cell lock.
+    pub output_lock_args: molecule::bytes::Bytes,
+    /// the timestamp in milliseconds of a ckb block in the header deps, none without header deps.
+    pub header_dep_timestamp: Option<u64>,
+}
+
+impl TestCase {
+    /// the cases saved by `save_snapshot` of the helper, with their names.
+    pub fn from_snapshot(path: &str) -> Vec<(String, TestCase)> {
+        let snapshot: Snapshot = serde_json::from_reader(std::fs::File::open(std::path::Path::new(path)).unwrap()).unwrap();
+        assert_eq!(snapshot.version, 1);
+        snapshot
+            .cases
+            .into_iter()
+            .map(|case| {
+                let witness = Witness {
+                    cell_dep_index_list: case.witness.cell_dep_index_list.0,
+                    header: case.witness.header.0,
+                    merkle_proof: case.witness.merkle_proof.iter().map(DoubleNodeWithMerkleProof::from).collect(),
+                    expected_tail_hash: case.witness.expected_tail_hash.0,
+                };
+                let test_case = TestCase {
+                    input_capacity: case.input_capacity,
+                    output_capacity: case.output_capacity,
+                    input_data: case.input_data.0.into(),
+                    output_data: case.output_data.0.into(),
+                    witness,
+                    cell_deps_data: case.cell_deps_data,
+                    expect_return_code: case.expect_return_code,
+                    chain_event: case.chain_event.map(|event| event.0.into()),
+                    type_args: case.type_args.0.into(),
+                    since: case.since,
+                    output_lock_args: case.output_lock_args.0.into(),
+                    header_dep_timestamp: case.header_dep_timestamp,
+                };
+                (case.name, test_case)
+            })
+            .collect()
+    }
+
+    /// the case as saved in a snapshot.
+    pub fn to_snapshot(&self, name: &str) -> SnapshotCase {
+        SnapshotCase {
+            name: name.to_string(),
+            input_capacity: self.input_capacity,
+            output_capacity: self.output_capacity,
+            input_data: Hex(self.input_data.to_vec()),
+            output_data: Hex(self.output_data.to_vec()),
+            witness: WitnessJson {
+                cell_dep_index_list: Hex(self.witness.cell_dep_index_list.clone()),
+                header: Hex(self.witness.header.clone()),
+                merkle_proof: self.witness.merkle_proof.iter().map(DoubleNodeWithMerkleProofJson::from).collect(),
+                expected_tail_hash: Hex(self.witness.expected_tail_hash.clone()),
+            },
+            cell_deps_data: RootsCollectionRaw {
+                dag_merkle_roots: self.cell_deps_data.dag_merkle_roots.iter().map(|root| Hex(root.0.clone())).collect(),
+            },
+            type_args: Hex(self.type_args.to_vec()),
+            since: self.since,
+            output_lock_args: Hex(self.output_lock_args.to_vec()),
+            header_dep_timestamp: self.header_dep_timestamp,
+            chain_event: self.chain_event.as_ref().map(|event| Hex(event.to_vec())),
+            expect_return_code: self.expect_return_code,
+        }
+    }
+}
+
+/// test cases saved to replay long scenarios without generating them again. unlike the conformance
+/// vectors, every case keeps its whole transaction: capacities, scripts, since, chain event and dep cells.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Snapshot {
+    pub version: u32,
+    pub cases: Vec<SnapshotCase>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotCase {
+    pub name: String,
+    pub input_capacity: u64,
+    pub output_capacity: u64,
+    pub input_data: Hex,
+    pub output_data: Hex,
+    pub witness: WitnessJson,
+    pub cell_deps_data: RootsCollectionRaw,
+    pub type_args: Hex,
+    pub since: u64,
+    pub output_lock_args: Hex,
+    pub header_dep_timestamp: Option<u64>,
+    pub chain_event: Option<Hex>,
+    pub expect_return_code: i8,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WitnessJson {
+    pub cell_dep_index_list: Hex,
+    pub header: Hex,
+    pub merkle_proof: Vec<DoubleNodeWithMerkleProofJson>,
+    pub expected_tail_hash: Hex,
 }
 
 #[derive(Debug)]
@@ -51,7 +161,16 @@ impl<'de> Deserialize<'de> for Hex {
     }
 }
 
-#[derive(Debug, Deserialize)]
+impl Serialize for Hex {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+    {
+        serializer.serialize_str(&format!("0x{}", hex::encode(&self.0)))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RootsCollectionRaw {
     pub dag_merkle_roots: Vec<Hex>, // H128
 }
@@ -96,8 +215,35 @@ pub fn read_roots_collection() -> RootsCollection {
 }
 
 pub fn read_roots_collection_raw() -> RootsCollectionRaw {
+    let json = std::fs::read_to_string("../tests/src/eth_client/tests/data/dag_merkle_roots.json").unwrap();
+    // the fixtures follow the formats the relayer reads.
+    serde_json::from_str::<fixture::RootsCollectionJson>(&json).unwrap().validate().unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+/// the shared conformance vectors, see `data/conformance/README.md` for the format.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConformanceSuite {
+    pub version: u32,
+    pub cell_deps: Vec<Hex>,
+    pub vectors: Vec<ConformanceVector>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConformanceVector {
+    pub name: String,
+    pub description: String,
+    pub input_data: Hex,
+    pub output_data: Hex,
+    pub witness: Hex,
+    pub expected_error: i8,
+}
+
+pub fn read_conformance_suite() -> ConformanceSuite {
     serde_json::from_reader(
-        std::fs::File::open(std::path::Path::new("../tests/src/eth_client/tests/data/dag_merkle_roots.json")).unwrap(),
+        std::fs::File::open(std::path::Path::new("../tests/src/eth_client/tests/data/conformance/vectors.json")).unwrap(),
     )
         .unwrap()
 }
@@ -140,7 +286,9 @@ pub fn read_block(filename: String) -> BlockWithProofs {
 }
 
 fn read_block_raw(filename: String) -> BlockWithProofsRaw {
-    serde_json::from_reader(std::fs::File::open(std::path::Path::new(&filename)).unwrap()).unwrap()
+    let json = std::fs::read_to_string(&filename).unwrap();
+    serde_json::from_str::<fixture::BlockWithProofsJson>(&json).unwrap().validate().unwrap();
+    serde_json::from_str(&json).unwrap()
 }
 
 impl BlockWithProofs {
@@ -188,6 +336,24 @@ pub struct DoubleNodeWithMerkleProofJson {
     pub proof: Vec<String>,
 }
 
+impl From<&DoubleNodeWithMerkleProof> for DoubleNodeWithMerkleProofJson {
+    fn from(proof: &DoubleNodeWithMerkleProof) -> Self {
+        Self {
+            dag_nodes: proof.dag_nodes.iter().map(|node| hex::encode(node.0)).collect(),
+            proof: proof.proof.iter().map(|node| hex::encode(node.0)).collect(),
+        }
+    }
+}
+
+impl From<&DoubleNodeWithMerkleProofJson> for DoubleNodeWithMerkleProof {
+    fn from(proof: &DoubleNodeWithMerkleProofJson) -> Self {
+        Self::new(
+            proof.dag_nodes.iter().map(|node| H512::from(&hex::decode(node).unwrap())).collect(),
+            proof.proof.iter().map(|node| H128::from(&hex::decode(node).unwrap())).collect(),
+        )
+    }
+}
+
 impl TryFrom<DoubleNodeWithMerkleProofJson> for double_node_with_merkle_proof::DoubleNodeWithMerkleProof {
     type Error = anyhow::Error;
     fn try_from(proof: DoubleNodeWithMerkleProofJson) -> Result<Self> {
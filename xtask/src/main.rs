@@ -0,0 +1,199 @@
+//! Reproducible release builds of the contract, run as `cargo xtask <command>`.
+//!
+//! The release binary is built by capsule, whose docker image pins the rust toolchain.
+//! `contracts/eth-client/release.manifest` records the capsule version and the data hash
+//! of the pinned binary, so integrators can rebuild it and compare the hash before
+//! trusting a deployed contract cell. `build --update` pins a new release and regenerates
+//! the code hash constants of the sdk. Until the first release is pinned, `build` only
+//! prints the hash of the binary.
+
+use ckb_tool::ckb_hash::blake2b_256;
+use std::{
+    convert::TryInto,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+const USAGE: &str = "usage:
+    cargo xtask build [--update]
+    cargo xtask verify";
+
+const CONTRACT: &str = "eth-client";
+const MANIFEST: &str = "contracts/eth-client/release.manifest";
+const SDK_CODE_HASH: &str = "sdk/src/code_hash.rs";
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let result = match (args.get(1).map(String::as_str), args.get(2).map(String::as_str)) {
+        (Some("build"), None) if args.len() == 2 => build(false),
+        (Some("build"), Some("--update")) if args.len() == 3 => build(true),
+        (Some("verify"), None) if args.len() == 2 => verify(),
+        _ => Err(USAGE.to_string()),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+struct Manifest {
+    capsule_version: String,
+    data_hash: [u8; 32],
+}
+
+impl Manifest {
+    fn parse(text: &str) -> Result<Manifest, String> {
+        let mut capsule_version = None;
+        let mut data_hash = None;
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let mut fields = line.split_whitespace();
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some("capsule"), Some(version), None) => capsule_version = Some(version.to_string()),
+                (Some(CONTRACT), Some(hash), None) => data_hash = Some(decode_hash(hash)?),
+                _ => return Err(format!("invalid manifest line: {}", line)),
+            }
+        }
+        Ok(Manifest {
+            capsule_version: capsule_version.ok_or("the manifest has no capsule version")?,
+            data_hash: data_hash.ok_or("the manifest has no contract hash")?,
+        })
+    }
+
+    fn render(&self) -> String {
+        format!(
+            "# generated by `cargo xtask build --update`.\n\
+             # the docker image of the capsule version pins the rust toolchain.\n\
+             capsule {}\n\
+             {} 0x{}\n",
+            self.capsule_version,
+            CONTRACT,
+            hex::encode(self.data_hash)
+        )
+    }
+}
+
+fn build(update: bool) -> Result<(), String> {
+    let root = workspace_root();
+    let capsule_version = capsule_version()?;
+    let pinned = if update { None } else { read_manifest(&root)? };
+    if let Some(pinned) = &pinned {
+        if pinned.capsule_version != capsule_version {
+            return Err(format!(
+                "the release is pinned to capsule {}, found {}",
+                pinned.capsule_version, capsule_version
+            ));
+        }
+    }
+    run(Command::new("capsule").args(["build", "--release"]).current_dir(&root))?;
+    let manifest = Manifest {
+        capsule_version,
+        data_hash: binary_hash(&root)?,
+    };
+    match pinned {
+        Some(pinned) => check_hash(&pinned, &manifest),
+        None if !update => {
+            println!(
+                "{} 0x{}, no release is pinned in {} yet, pin this one with `build --update`",
+                CONTRACT,
+                hex::encode(manifest.data_hash),
+                MANIFEST
+            );
+            Ok(())
+        }
+        None => {
+            write(&root.join(MANIFEST), &manifest.render())?;
+            write(&root.join(SDK_CODE_HASH), &render_code_hash(&manifest.data_hash))?;
+            println!("pinned {} 0x{}", CONTRACT, hex::encode(manifest.data_hash));
+            Ok(())
+        }
+    }
+}
+
+/// check a binary built elsewhere, e.g. by `capsule build --release` in a clean checkout.
+fn verify() -> Result<(), String> {
+    let root = workspace_root();
+    let pinned = read_manifest(&root)?
+        .ok_or_else(|| format!("no release is pinned in {}, pin one with `build --update`", MANIFEST))?;
+    let data_hash = binary_hash(&root)?;
+    check_hash(&pinned, &Manifest { capsule_version: pinned.capsule_version.clone(), data_hash })
+}
+
+fn check_hash(pinned: &Manifest, built: &Manifest) -> Result<(), String> {
+    if pinned.data_hash != built.data_hash {
+        return Err(format!(
+            "{} is 0x{}, the pinned release is 0x{}",
+            CONTRACT,
+            hex::encode(built.data_hash),
+            hex::encode(pinned.data_hash)
+        ));
+    }
+    println!("{} 0x{} matches the pinned release", CONTRACT, hex::encode(built.data_hash));
+    Ok(())
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is a workspace member")
+        .to_path_buf()
+}
+
+/// the pinned release, `None` before the first one is pinned.
+fn read_manifest(root: &Path) -> Result<Option<Manifest>, String> {
+    match fs::read_to_string(root.join(MANIFEST)) {
+        Ok(text) => Manifest::parse(&text).map(Some),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(format!("can not read {}: {}", MANIFEST, err)),
+    }
+}
+
+fn capsule_version() -> Result<String, String> {
+    let output = Command::new("capsule")
+        .arg("--version")
+        .output()
+        .map_err(|err| format!("can not run capsule: {}", err))?;
+    // e.g. "ckb-capsule 0.2.3"
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .nth(1)
+        .map(str::to_string)
+        .ok_or_else(|| "unexpected output of `capsule --version`".to_string())
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let status = command.status().map_err(|err| format!("can not run {:?}: {}", command, err))?;
+    if !status.success() {
+        return Err(format!("{:?} failed: {}", command, status));
+    }
+    Ok(())
+}
+
+/// the ckb data hash of the release binary, i.e. the code hash of a `hash_type = data` script.
+fn binary_hash(root: &Path) -> Result<[u8; 32], String> {
+    let path = root.join("build/release").join(CONTRACT);
+    let binary = fs::read(&path).map_err(|err| format!("can not read {}: {}", path.display(), err))?;
+    Ok(blake2b_256(&binary))
+}
+
+fn render_code_hash(data_hash: &[u8; 32]) -> String {
+    let bytes: Vec<String> = data_hash.iter().map(|byte| format!("0x{:02x}", byte)).collect();
+    format!(
+        "//! Generated by `cargo xtask build --update`, do not edit.\n\
+         \n\
+         /// The data hash of the pinned release binary of the contract, `None` until a release\n\
+         /// is pinned in `contracts/eth-client/release.manifest`.\n\
+         pub const ETH_CLIENT_DATA_HASH: Option<[u8; 32]> = Some([\n    {},\n    {},\n]);\n",
+        bytes[..16].join(", "),
+        bytes[16..].join(", ")
+    )
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content).map_err(|err| format!("can not write {}: {}", path.display(), err))
+}
+
+fn decode_hash(hash_hex: &str) -> Result<[u8; 32], String> {
+    let hash = hex::decode(hash_hex.trim_start_matches("0x")).map_err(|err| format!("invalid hash: {}", err))?;
+    hash.as_slice().try_into().map_err(|_| format!("invalid hash: {}", hash_hex))
+}